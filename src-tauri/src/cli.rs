@@ -0,0 +1,283 @@
+use std::path::Path;
+
+use tauri::{State, WebviewWindow};
+
+use crate::package;
+use crate::settings::{SyncRole, SyncSettings};
+use crate::widget::WidgetOptions;
+
+/// Names accepted by `--color-mode`; the viewer maps them to its color modes.
+pub const COLOR_MODES: [&str; 9] = [
+    "bw",
+    "black-white",
+    "details",
+    "colored",
+    "frames",
+    "palette",
+    "grayscale",
+    "inverted",
+    "gradient",
+];
+
+const USAGE: &str = "Usage: decorator [PATH] [OPTIONS]
+
+Arguments:
+  [PATH]               Frames folder, frame file or .decorator package to open,
+                       or the library for --slideshow
+
+Options:
+      --fps <N>          Playback speed, overriding details.toml
+      --fullscreen       Start fullscreen with the controls hidden
+      --no-loop          Stop at the last frame
+      --play             Start playing as soon as the frames are loaded
+      --color-mode <M>   bw, details, colored, palette, grayscale, inverted or gradient
+      --start <N>        Frame to start on (1 is the first)
+
+Slideshow:
+      --slideshow        Cycle through every project under PATH, fullscreen
+      --shuffle          Play the projects in random order
+      --ordered          Play the projects in path order
+      --loops <N>        Move on after N plays of each animation
+      --seconds <N>      Move on after N seconds
+      --crossfade <MS>   Crossfade length in milliseconds
+
+Live:
+      --stream <SOURCE>     Show frames pushed to tcp:PORT, unix:PATH or pipe:PATH
+                            as they arrive, instead of a frames folder
+      --stream-buffer <MS>  Hold streamed frames this long to even out their timing
+
+Automation:
+      --rpc-stdio        Take JSON-RPC requests on stdin and answer on stdout,
+                         with state changes as notifications; implies --new-instance
+
+Sync:
+      --sync-lead <PORT>       Lead other instances, accepting them on PORT
+      --sync-follow <ADDRESS>  Follow the leader at host:port
+      --sync-latency <MS>      Run this many milliseconds ahead of the leader
+      --new-instance           Don't hand over to a running decorator, e.g. to
+                               run a leader and followers on one machine
+  -h, --help             Print this help
+  -V, --version          Print the version";
+
+/// What a window opens with, from the command line of this or a later launch.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LaunchOptions {
+    pub directory_path: Option<String>,
+    pub fps: Option<u32>,
+    pub fullscreen: bool,
+    pub no_loop: bool,
+    pub play: bool,
+    pub color_mode: Option<String>,
+    /// Zero-based frame index
+    pub start: Option<usize>,
+    /// Treat the path as a library to cycle through
+    pub slideshow: bool,
+    /// Slideshow settings overriding the saved ones
+    pub shuffle: Option<bool>,
+    pub loops: Option<u32>,
+    pub seconds: Option<u32>,
+    pub crossfade_ms: Option<u32>,
+    /// Where to receive live frames from, in place of a frames folder
+    pub stream: Option<String>,
+    pub stream_buffer_ms: Option<u32>,
+    /// Open as a desktop widget; set on widget windows, never from the command line
+    pub widget: Option<WidgetOptions>,
+    /// Sync settings for this run only, in place of the saved ones
+    #[serde(skip)]
+    pub sync: Option<SyncSettings>,
+    /// Run alongside an already running instance instead of forwarding to it
+    #[serde(skip)]
+    pub new_instance: bool,
+    /// Be driven over stdin/stdout by the process that started us
+    #[serde(skip)]
+    pub rpc_stdio: bool,
+}
+
+/// What to do after reading the arguments.
+pub enum Command {
+    Run(LaunchOptions),
+    Exit(String),
+}
+
+fn number<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("{} needs a value", flag))?;
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value for {}: {}", flag, value))
+}
+
+/// Resolve the path relative to `cwd`. A details.toml opens its project, and a
+/// package is unpacked to open the project inside.
+pub fn project_path(path: &str, cwd: &Path) -> Result<String, String> {
+    let path = cwd.join(path);
+    let path = path
+        .canonicalize()
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    if package::is_package(&path) {
+        return Ok(package::unpack(&path)?.to_string_lossy().to_string());
+    }
+    let path = match path.file_name() {
+        Some(name) if name == "details.toml" => path.parent().map(Path::to_path_buf).unwrap_or(path),
+        _ => path,
+    };
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Parse the arguments after the program name; relative paths are resolved against
+/// `cwd`, which differs from ours when a second launch forwards its arguments.
+pub fn parse(args: impl IntoIterator<Item = String>, cwd: &Path) -> Result<Command, String> {
+    let mut options = LaunchOptions::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        // `--flag=value` is the same as `--flag value`
+        let (flag, mut inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+            _ => (arg.clone(), None),
+        };
+        let mut value = || inline.take().or_else(|| args.next());
+        match flag.as_str() {
+            "-h" | "--help" => return Ok(Command::Exit(USAGE.to_string())),
+            "-V" | "--version" => {
+                return Ok(Command::Exit(format!("decorator {}", env!("CARGO_PKG_VERSION"))))
+            }
+            "--fps" => {
+                let fps: u32 = number(&flag, value())?;
+                if fps == 0 {
+                    return Err("--fps must be at least 1".to_string());
+                }
+                options.fps = Some(fps);
+            }
+            "--fullscreen" => options.fullscreen = true,
+            "--no-loop" => options.no_loop = true,
+            "--play" => options.play = true,
+            "--color-mode" => {
+                let mode = value().ok_or("--color-mode needs a value")?;
+                if !COLOR_MODES.contains(&mode.as_str()) {
+                    return Err(format!("Unknown color mode: {}", mode));
+                }
+                options.color_mode = Some(mode);
+            }
+            "--start" => {
+                let frame: usize = number(&flag, value())?;
+                options.start = Some(frame.saturating_sub(1));
+            }
+            "--slideshow" => options.slideshow = true,
+            "--shuffle" => options.shuffle = Some(true),
+            "--ordered" => options.shuffle = Some(false),
+            "--loops" => {
+                options.loops = Some(number::<u32>(&flag, value())?.max(1));
+                options.seconds = None;
+            }
+            "--seconds" => {
+                options.seconds = Some(number::<u32>(&flag, value())?.max(1));
+                options.loops = None;
+            }
+            "--crossfade" => options.crossfade_ms = Some(number(&flag, value())?),
+            "--stream" => options.stream = Some(value().ok_or("--stream needs a source, e.g. tcp:7650")?),
+            "--stream-buffer" => options.stream_buffer_ms = Some(number(&flag, value())?),
+            "--sync-lead" => {
+                let sync = options.sync.get_or_insert_with(SyncSettings::default);
+                sync.role = SyncRole::Leader;
+                sync.port = number(&flag, value())?;
+            }
+            "--sync-follow" => {
+                let sync = options.sync.get_or_insert_with(SyncSettings::default);
+                sync.role = SyncRole::Follower;
+                sync.leader = value().ok_or("--sync-follow needs a host:port")?;
+            }
+            "--sync-latency" => {
+                options.sync.get_or_insert_with(SyncSettings::default).latency_ms = number(&flag, value())?;
+            }
+            "--new-instance" => options.new_instance = true,
+            "--rpc-stdio" => options.rpc_stdio = true,
+            // Added by macOS when launched from Finder
+            _ if flag.starts_with("-psn_") => {}
+            _ if flag.starts_with('-') => return Err(format!("Unknown option: {}", flag)),
+            _ if options.directory_path.is_none() => options.directory_path = Some(project_path(&arg, cwd)?),
+            _ => return Err(format!("Unexpected argument: {}", arg)),
+        }
+        if inline.is_some() {
+            return Err(format!("{} does not take a value", flag));
+        }
+    }
+    Ok(Command::Run(options))
+}
+
+/// Launch options for the window asking; only the main window was started from the command line.
+#[tauri::command]
+pub fn get_launch_options(window: WebviewWindow, launch: State<'_, LaunchOptions>) -> Option<LaunchOptions> {
+    (window.label() == "main").then(|| launch.inner().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(args: &[&str]) -> Result<LaunchOptions, String> {
+        let cwd = std::env::temp_dir();
+        match parse(args.iter().map(|arg| arg.to_string()), &cwd)? {
+            Command::Run(options) => Ok(options),
+            Command::Exit(message) => Err(format!("exited: {}", message)),
+        }
+    }
+
+    #[test]
+    fn parses_flags_and_values() {
+        let options = run(&["--fps", "24", "--play", "--no-loop", "--color-mode=palette", "--start", "5"]).unwrap();
+        assert_eq!(options.fps, Some(24));
+        assert!(options.play);
+        assert!(options.no_loop);
+        assert_eq!(options.color_mode.as_deref(), Some("palette"));
+        assert_eq!(options.start, Some(4));
+    }
+
+    #[test]
+    fn rejects_bad_values() {
+        assert!(run(&["--fps", "0"]).is_err());
+        assert!(run(&["--fps", "fast"]).is_err());
+        assert!(run(&["--fps"]).is_err());
+        assert!(run(&["--color-mode", "sepia"]).is_err());
+        assert!(run(&["--play=yes"]).is_err());
+        assert!(run(&["--bogus"]).is_err());
+    }
+
+    #[test]
+    fn last_advance_flag_wins() {
+        let options = run(&["--loops", "3", "--seconds", "0"]).unwrap();
+        assert_eq!(options.loops, None);
+        assert_eq!(options.seconds, Some(1));
+    }
+
+    #[test]
+    fn sync_flags_build_settings() {
+        let options = run(&["--sync-follow", "10.0.0.2:7646", "--sync-latency", "-20"]).unwrap();
+        let sync = options.sync.unwrap();
+        assert_eq!(sync.role, SyncRole::Follower);
+        assert_eq!(sync.leader, "10.0.0.2:7646");
+        assert_eq!(sync.latency_ms, -20);
+    }
+
+    #[test]
+    fn resolves_the_path_against_cwd() {
+        let dir = std::env::temp_dir().join(format!("decorator-cli-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("details.toml"), "").unwrap();
+        let cwd = dir.parent().unwrap();
+        let name = dir.file_name().unwrap().to_string_lossy().to_string();
+        let options = match parse([format!("{}/details.toml", name)], cwd).unwrap() {
+            Command::Run(options) => options,
+            Command::Exit(message) => panic!("exited: {}", message),
+        };
+        assert_eq!(options.directory_path, Some(dir.canonicalize().unwrap().to_string_lossy().to_string()));
+        assert!(parse([name.clone(), name], cwd).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn help_and_version_exit() {
+        for flag in ["-h", "--help", "-V", "--version"] {
+            assert!(matches!(parse([flag.to_string()], Path::new(".")), Ok(Command::Exit(_))));
+        }
+    }
+}
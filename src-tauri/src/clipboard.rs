@@ -0,0 +1,37 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use tauri::image::Image;
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+/// Copy plain (or ANSI-escaped) text to the system clipboard.
+#[tauri::command]
+pub fn copy_text_to_clipboard(app: AppHandle, text: String) -> Result<(), String> {
+    app.clipboard()
+        .write_text(text)
+        .map_err(|e| format!("Failed to copy text: {}", e))
+}
+
+/// Copy HTML to the clipboard, with a plain-text fallback for apps without HTML paste.
+#[tauri::command]
+pub fn copy_html_to_clipboard(app: AppHandle, html: String, alt_text: String) -> Result<(), String> {
+    app.clipboard()
+        .write_html(html, Some(alt_text))
+        .map_err(|e| format!("Failed to copy HTML: {}", e))
+}
+
+/// Copy a PNG image to the clipboard.
+/// Accepts either a `data:image/png;base64,...` URL (as produced by canvas.toDataURL) or bare base64.
+#[tauri::command]
+pub fn copy_png_to_clipboard(app: AppHandle, png_data: String) -> Result<(), String> {
+    let b64 = png_data
+        .strip_prefix("data:image/png;base64,")
+        .unwrap_or(&png_data);
+    let bytes = STANDARD
+        .decode(b64)
+        .map_err(|e| format!("Invalid PNG data: {}", e))?;
+    let image = Image::from_bytes(&bytes).map_err(|e| format!("Failed to decode PNG: {}", e))?;
+
+    app.clipboard()
+        .write_image(&image)
+        .map_err(|e| format!("Failed to copy image: {}", e))
+}
@@ -0,0 +1,126 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use toml_edit::{DocumentMut, Item, Value};
+
+use crate::history;
+
+const DETAILS_FILE: &str = "details.toml";
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldKind {
+    String,
+    Integer,
+    Float,
+    Boolean,
+}
+
+/// One top-level scalar key of details.toml, with its value as text.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct DetailsField {
+    pub key: String,
+    pub value: String,
+    pub kind: FieldKind,
+}
+
+fn details_path(directory_path: &str) -> PathBuf {
+    history::project_dir(Path::new(directory_path)).join(DETAILS_FILE)
+}
+
+fn load_document(path: &Path) -> Result<DocumentMut, String> {
+    if !path.exists() {
+        return Ok(DocumentMut::new());
+    }
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read details.toml: {}", e))?;
+    content
+        .parse::<DocumentMut>()
+        .map_err(|e| format!("Failed to parse details.toml: {}", e))
+}
+
+fn parse_field(field: &DetailsField) -> Result<Value, String> {
+    let text = field.value.trim();
+    let value = match field.kind {
+        FieldKind::String => Value::from(field.value.as_str()),
+        FieldKind::Integer => {
+            let n = text
+                .parse::<i64>()
+                .map_err(|_| format!("{} must be a whole number", field.key))?;
+            if field.key == "fps" && n <= 0 {
+                return Err("fps must be greater than zero".to_string());
+            }
+            Value::from(n)
+        }
+        FieldKind::Float => Value::from(
+            text.parse::<f64>()
+                .map_err(|_| format!("{} must be a number", field.key))?,
+        ),
+        FieldKind::Boolean => Value::from(
+            text.parse::<bool>()
+                .map_err(|_| format!("{} must be true or false", field.key))?,
+        ),
+    };
+    Ok(value)
+}
+
+/// List the top-level scalar keys of details.toml in file order.
+/// Tables, arrays and dates are left out since the settings panel can't edit them.
+#[tauri::command]
+pub fn read_details_fields(directory_path: String) -> Result<Vec<DetailsField>, String> {
+    let doc = load_document(&details_path(&directory_path))?;
+    let fields = doc
+        .iter()
+        .filter_map(|(key, item)| {
+            let (value, kind) = match item.as_value()? {
+                Value::String(s) => (s.value().clone(), FieldKind::String),
+                Value::Integer(i) => (i.value().to_string(), FieldKind::Integer),
+                Value::Float(f) => (f.value().to_string(), FieldKind::Float),
+                Value::Boolean(b) => (b.value().to_string(), FieldKind::Boolean),
+                _ => return None,
+            };
+            Some(DetailsField {
+                key: key.to_string(),
+                value,
+                kind,
+            })
+        })
+        .collect();
+    Ok(fields)
+}
+
+/// Update keys in details.toml, keeping comments, formatting and every key
+/// not listed in `fields`. An empty value removes the key.
+#[tauri::command]
+pub fn write_project_details(directory_path: String, fields: Vec<DetailsField>) -> Result<(), String> {
+    let path = details_path(&directory_path);
+    let mut doc = load_document(&path)?;
+
+    for field in &fields {
+        if field.value.trim().is_empty() {
+            doc.remove(&field.key);
+            continue;
+        }
+        let mut value = parse_field(field)?;
+        match doc.get_mut(&field.key) {
+            // Keep the comments and spacing around the existing value
+            Some(Item::Value(existing)) => {
+                *value.decor_mut() = existing.decor().clone();
+                *existing = value;
+            }
+            _ => {
+                doc.insert(&field.key, Item::Value(value));
+            }
+        }
+    }
+
+    let dir = history::project_dir(Path::new(&directory_path));
+    history::record(&dir, "Edit details.toml", &|| vec![DETAILS_FILE.to_string()], || {
+        let tmp_path = path.with_extension("toml.tmp");
+        fs::write(&tmp_path, doc.to_string())
+            .map_err(|e| format!("Failed to write details.toml: {}", e))?;
+        fs::rename(&tmp_path, &path).map_err(|e| {
+            let _ = fs::remove_file(&tmp_path);
+            format!("Failed to replace details.toml: {}", e)
+        })
+    })
+}
@@ -0,0 +1,150 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+
+const FONT_EXTENSIONS: [&str; 4] = ["ttf", "otf", "ttc", "woff2"];
+/// How deep to look inside the system font folders.
+const MAX_SCAN_DEPTH: usize = 4;
+
+/// Fonts offered for the `font` key of details.toml.
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct FontList {
+    /// Monospace families installed on the system, usable by name
+    pub system: Vec<String>,
+    /// Font files in the project folder, relative to it
+    pub project: Vec<String>,
+}
+
+fn is_font_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| FONT_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+fn system_font_dirs() -> Vec<PathBuf> {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let mut dirs = Vec::new();
+    if cfg!(target_os = "macos") {
+        dirs.extend(["/System/Library/Fonts", "/Library/Fonts"].map(PathBuf::from));
+        dirs.extend(home.map(|h| h.join("Library/Fonts")));
+    } else if cfg!(target_os = "windows") {
+        if let Some(windir) = std::env::var_os("WINDIR") {
+            dirs.push(PathBuf::from(windir).join("Fonts"));
+        }
+        if let Some(local) = std::env::var_os("LOCALAPPDATA") {
+            dirs.push(PathBuf::from(local).join("Microsoft/Windows/Fonts"));
+        }
+    } else {
+        dirs.extend(["/usr/share/fonts", "/usr/local/share/fonts"].map(PathBuf::from));
+        if let Some(home) = home {
+            dirs.push(home.join(".local/share/fonts"));
+            dirs.push(home.join(".fonts"));
+        }
+    }
+    dirs
+}
+
+fn collect_font_files(dir: &Path, depth: usize, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if depth < MAX_SCAN_DEPTH {
+                collect_font_files(&path, depth + 1, files);
+            }
+        } else if is_font_file(&path) {
+            files.push(path);
+        }
+    }
+}
+
+/// Family names of the monospaced faces in a font file (collections hold several).
+fn monospace_families(path: &Path) -> Vec<String> {
+    let Ok(data) = fs::read(path) else {
+        return Vec::new();
+    };
+    let faces = ttf_parser::fonts_in_collection(&data).unwrap_or(1);
+    (0..faces)
+        .filter_map(|index| ttf_parser::Face::parse(&data, index).ok())
+        .filter(|face| face.is_monospaced())
+        .filter_map(|face| {
+            let names = face.names();
+            let family = |id: u16| names.into_iter().find(|n| n.name_id == id).and_then(|n| n.to_string());
+            family(ttf_parser::name_id::TYPOGRAPHIC_FAMILY).or_else(|| family(ttf_parser::name_id::FAMILY))
+        })
+        .collect()
+}
+
+/// Installed monospace families. Scanning reads every font file, so it runs once per session.
+fn system_monospace_fonts() -> &'static Vec<String> {
+    static FONTS: OnceLock<Vec<String>> = OnceLock::new();
+    FONTS.get_or_init(|| {
+        let mut files = Vec::new();
+        for dir in system_font_dirs() {
+            collect_font_files(&dir, 0, &mut files);
+        }
+        let families: BTreeSet<String> = files.iter().flat_map(|f| monospace_families(f)).collect();
+        families.into_iter().collect()
+    })
+}
+
+/// Font files at the top of the project folder or in its `fonts/` subfolder.
+fn project_fonts(dir: &Path) -> Vec<String> {
+    let mut fonts = Vec::new();
+    for sub in ["", "fonts"] {
+        let Ok(entries) = fs::read_dir(dir.join(sub)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_file() && is_font_file(&path) {
+                if let Ok(relative) = path.strip_prefix(dir) {
+                    fonts.push(relative.to_string_lossy().replace('\\', "/"));
+                }
+            }
+        }
+    }
+    fonts.sort();
+    fonts
+}
+
+#[tauri::command]
+pub async fn list_fonts(directory_path: String) -> Result<FontList, String> {
+    let dir = crate::history::project_dir(&PathBuf::from(&directory_path));
+    tauri::async_runtime::spawn_blocking(move || FontList {
+        system: system_monospace_fonts().clone(),
+        project: project_fonts(&dir),
+    })
+    .await
+    .map_err(|e| format!("Font scan failed: {}", e))
+}
+
+/// Read a font file referenced from details.toml as a data URL for `@font-face`.
+/// The path is relative to the project folder and may not leave it.
+#[tauri::command]
+pub fn read_font_file(directory_path: String, font_path: String) -> Result<String, String> {
+    let dir = crate::history::project_dir(&PathBuf::from(&directory_path));
+    let path = dir
+        .join(&font_path)
+        .canonicalize()
+        .map_err(|e| format!("Failed to find font file: {}", e))?;
+    let root = dir.canonicalize().map_err(|e| format!("Failed to resolve project folder: {}", e))?;
+    if !path.starts_with(&root) || !is_font_file(&path) {
+        return Err(format!("Not a font file in this project: {}", font_path));
+    }
+
+    let data = fs::read(&path).map_err(|e| format!("Failed to read font file: {}", e))?;
+    let mime = match path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()) {
+        Some(ext) if ext == "otf" => "font/otf",
+        Some(ext) if ext == "woff2" => "font/woff2",
+        Some(ext) if ext == "ttc" => "font/collection",
+        _ => "font/ttf",
+    };
+    Ok(format!("data:{};base64,{}", mime, STANDARD.encode(&data)))
+}
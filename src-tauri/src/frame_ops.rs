@@ -0,0 +1,358 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use cascii_core_view::FrameFile;
+
+use crate::history;
+use crate::journal::Journal;
+use crate::{read_frame_file, scan_frames_in_dir};
+
+/// Every file that belongs to one frame, keyed by extension.
+const FRAME_EXTENSIONS: [&str; 3] = ["txt", "cframe", "colors"];
+
+/// Where the frame at each position of the rewritten sequence comes from.
+enum FrameSource {
+    /// An existing frame, by file stem
+    Existing(String),
+    /// A new frame of spaces
+    Blank { width: usize, height: usize },
+}
+
+fn frame_stem(frame: &FrameFile) -> String {
+    Path::new(&frame.path)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Naming scheme of the existing frames: stem prefix, digit width and first number.
+/// `frame_0001` gives ("frame_", 4, 1).
+fn numbering(frames: &[FrameFile]) -> (String, usize, usize) {
+    let Some(first) = frames.first() else {
+        return ("frame_".to_string(), 4, 1);
+    };
+    let stem = frame_stem(first);
+    let prefix = stem.trim_end_matches(|c: char| c.is_ascii_digit());
+    let digits = &stem[prefix.len()..];
+    match digits.parse::<usize>() {
+        Ok(start) => (prefix.to_string(), digits.len(), start),
+        Err(_) => (format!("{}_", stem), 4, 1),
+    }
+}
+
+/// Character grid size of a frame, used to size blank frames.
+fn frame_dimensions(frame: &FrameFile) -> (usize, usize) {
+    let content = read_frame_file(frame.path.clone()).unwrap_or_default();
+    let width = content.lines().map(|l| l.chars().count()).max().unwrap_or(0);
+    (width, content.lines().count())
+}
+
+fn project_frames(directory_path: &str) -> Result<(PathBuf, Vec<FrameFile>), String> {
+    let dir = PathBuf::from(directory_path);
+    if !dir.is_dir() {
+        return Err("Frame operations need a project folder".to_string());
+    }
+    let frames = scan_frames_in_dir(&dir)?;
+    Ok((dir, frames))
+}
+
+/// Stems of the rewritten sequence, continuing the existing naming scheme.
+fn target_stems(frames: &[FrameFile], count: usize) -> Vec<String> {
+    let (prefix, width, start) = numbering(frames);
+    (0..count)
+        .map(|i| format!("{}{:0width$}", prefix, start + i, width = width))
+        .collect()
+}
+
+/// Frames that already have their final name, and are used nowhere else: the
+/// rewrite leaves them alone, so their files are neither moved nor journaled.
+fn kept_in_place(sources: &[FrameSource], targets: &[String]) -> HashSet<String> {
+    let mut uses: HashMap<&str, usize> = HashMap::new();
+    for source in sources {
+        if let FrameSource::Existing(stem) = source {
+            *uses.entry(stem.as_str()).or_default() += 1;
+        }
+    }
+    sources
+        .iter()
+        .zip(targets)
+        .filter_map(|(source, target)| match source {
+            FrameSource::Existing(stem) if stem == target && uses[stem.as_str()] == 1 => Some(stem.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Names of the files a rewrite may move, create or delete.
+fn touched_files(frames: &[FrameFile], targets: &[String], kept: &HashSet<String>) -> Vec<String> {
+    frames
+        .iter()
+        .map(frame_stem)
+        .chain(targets.iter().cloned())
+        .filter(|stem| !kept.contains(stem))
+        .flat_map(|stem| FRAME_EXTENSIONS.map(|ext| format!("{}.{}", stem, ext)))
+        .collect()
+}
+
+fn existing_sources(frames: &[FrameFile]) -> Vec<FrameSource> {
+    frames
+        .iter()
+        .map(|f| FrameSource::Existing(frame_stem(f)))
+        .collect()
+}
+
+/// Rewrite the frame files of `dir` so they follow `sources`, renumbering every
+/// frame so the `FrameFile::extract_index` ordering matches the new sequence.
+///
+/// Runs as a transaction: the files of every frame that changes are first moved
+/// into a backup folder, then placed under their new names. Any failure replays
+/// the journal backwards so the folder is left exactly as it was. Successful
+/// rewrites are recorded in the project history under `label`, snapshotting
+/// only the files they touch.
+fn rewrite_frames(dir: &Path, frames: &[FrameFile], sources: &[FrameSource], label: &str) -> Result<(), String> {
+    let targets = target_stems(frames, sources.len());
+    let kept = kept_in_place(sources, &targets);
+    let touched = touched_files(frames, &targets, &kept);
+    history::record(dir, label, &|| touched.clone(), || {
+        apply_rewrite(dir, frames, sources, &targets, &kept)
+    })
+}
+
+fn apply_rewrite(
+    dir: &Path,
+    frames: &[FrameFile],
+    sources: &[FrameSource],
+    targets: &[String],
+    kept: &HashSet<String>,
+) -> Result<(), String> {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let backup_dir = dir.join(format!(".decorator-frames-{}", millis));
+
+    let mut journal = Journal::default();
+    match place_frames(dir, &backup_dir, frames, sources, targets, kept, &mut journal) {
+        Ok(()) => {
+            // Committed: whatever is left in the backup belonged to deleted frames
+            let _ = fs::remove_dir_all(&backup_dir);
+            Ok(())
+        }
+        Err(e) => {
+            journal.rollback();
+            Err(e)
+        }
+    }
+}
+
+fn place_frames(
+    dir: &Path,
+    backup_dir: &Path,
+    frames: &[FrameFile],
+    sources: &[FrameSource],
+    targets: &[String],
+    kept: &HashSet<String>,
+    journal: &mut Journal,
+) -> Result<(), String> {
+    journal.create_dir(backup_dir)?;
+    for frame in frames {
+        let stem = frame_stem(frame);
+        if kept.contains(&stem) {
+            continue;
+        }
+        for ext in FRAME_EXTENSIONS {
+            let path = dir.join(format!("{}.{}", stem, ext));
+            if path.exists() {
+                journal.rename(&path, &backup_dir.join(format!("{}.{}", stem, ext)))?;
+            }
+        }
+    }
+
+    // Duplicated frames are copied out of the backup; the last use is moved
+    let mut remaining_uses: HashMap<&str, usize> = HashMap::new();
+    for source in sources {
+        if let FrameSource::Existing(stem) = source {
+            *remaining_uses.entry(stem.as_str()).or_default() += 1;
+        }
+    }
+
+    for (source, target_stem) in sources.iter().zip(targets) {
+        match source {
+            FrameSource::Existing(stem) if kept.contains(stem) => {}
+            FrameSource::Existing(stem) => {
+                let uses = remaining_uses.get_mut(stem.as_str()).expect("counted above");
+                *uses -= 1;
+                for ext in FRAME_EXTENSIONS {
+                    let from = backup_dir.join(format!("{}.{}", stem, ext));
+                    if !from.exists() {
+                        continue;
+                    }
+                    let to = dir.join(format!("{}.{}", target_stem, ext));
+                    if *uses > 0 {
+                        journal.copy(&from, &to)?;
+                    } else {
+                        journal.rename(&from, &to)?;
+                    }
+                }
+            }
+            FrameSource::Blank { width, height } => {
+                let line = " ".repeat(*width);
+                let content = vec![line; *height].join("\n") + "\n";
+                journal.write(&dir.join(format!("{}.txt", target_stem)), content.as_bytes())?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn check_index(frames: &[FrameFile], index: usize) -> Result<(), String> {
+    if index >= frames.len() {
+        return Err(format!("Frame {} does not exist", index + 1));
+    }
+    Ok(())
+}
+
+/// Duplicate the frame at `index`; the copy is inserted right after it.
+#[tauri::command]
+pub fn duplicate_frame(directory_path: String, index: usize) -> Result<Vec<FrameFile>, String> {
+    let (dir, frames) = project_frames(&directory_path)?;
+    check_index(&frames, index)?;
+
+    let mut sources = existing_sources(&frames);
+    sources.insert(index + 1, FrameSource::Existing(frame_stem(&frames[index])));
+    rewrite_frames(&dir, &frames, &sources, &format!("Duplicate frame {}", index + 1))?;
+    scan_frames_in_dir(&dir)
+}
+
+/// Delete the frame at `index` together with its .cframe and .colors siblings.
+#[tauri::command]
+pub fn delete_frame(directory_path: String, index: usize) -> Result<Vec<FrameFile>, String> {
+    let (dir, frames) = project_frames(&directory_path)?;
+    check_index(&frames, index)?;
+    if frames.len() == 1 {
+        return Err("Cannot delete the only frame".to_string());
+    }
+
+    let mut sources = existing_sources(&frames);
+    sources.remove(index);
+    rewrite_frames(&dir, &frames, &sources, &format!("Delete frame {}", index + 1))?;
+    scan_frames_in_dir(&dir)
+}
+
+/// Move the frame at `from` so it ends up at position `to`.
+#[tauri::command]
+pub fn move_frame(directory_path: String, from: usize, to: usize) -> Result<Vec<FrameFile>, String> {
+    let (dir, frames) = project_frames(&directory_path)?;
+    check_index(&frames, from)?;
+    check_index(&frames, to)?;
+    if from == to {
+        return Ok(frames);
+    }
+
+    let mut sources = existing_sources(&frames);
+    let moved = sources.remove(from);
+    sources.insert(to, moved);
+    rewrite_frames(&dir, &frames, &sources, &format!("Move frame {} to {}", from + 1, to + 1))?;
+    scan_frames_in_dir(&dir)
+}
+
+/// Insert a blank frame at `index`, sized like the frame currently there
+/// (or the last frame when appending).
+#[tauri::command]
+pub fn insert_blank_frame(directory_path: String, index: usize) -> Result<Vec<FrameFile>, String> {
+    let (dir, frames) = project_frames(&directory_path)?;
+    let index = index.min(frames.len());
+
+    let (width, height) = frames
+        .get(index)
+        .or_else(|| frames.last())
+        .map(frame_dimensions)
+        .unwrap_or((80, 24));
+    let mut sources = existing_sources(&frames);
+    sources.insert(index, FrameSource::Blank { width, height });
+    rewrite_frames(&dir, &frames, &sources, &format!("Insert blank frame {}", index + 1))?;
+    scan_frames_in_dir(&dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frames(stems: &[&str]) -> Vec<FrameFile> {
+        stems
+            .iter()
+            .zip(0..)
+            .map(|(stem, i)| FrameFile::new(format!("/art/{}.txt", stem), format!("{}.txt", stem), i))
+            .collect()
+    }
+
+    fn existing(stems: &[&str]) -> Vec<FrameSource> {
+        stems.iter().map(|stem| FrameSource::Existing(stem.to_string())).collect()
+    }
+
+    #[test]
+    fn numbering_follows_the_first_frame() {
+        assert_eq!(numbering(&frames(&["frame_0001"])), ("frame_".to_string(), 4, 1));
+        assert_eq!(numbering(&frames(&["shot07"])), ("shot".to_string(), 2, 7));
+        assert_eq!(numbering(&frames(&["title"])), ("title_".to_string(), 4, 1));
+        assert_eq!(numbering(&[]), ("frame_".to_string(), 4, 1));
+    }
+
+    #[test]
+    fn target_stems_continue_the_numbering() {
+        assert_eq!(target_stems(&frames(&["f_098", "f_099"]), 3), ["f_098", "f_099", "f_100"]);
+    }
+
+    #[test]
+    fn unchanged_frames_are_kept_in_place() {
+        let targets = ["f_1", "f_2", "f_3", "f_4"].map(String::from);
+        // Duplicating f_2: f_1 stays, f_2 is used twice and f_3 moves up
+        let sources = existing(&["f_1", "f_2", "f_2", "f_3"]);
+        let kept = kept_in_place(&sources, &targets);
+        assert_eq!(kept, HashSet::from(["f_1".to_string()]));
+
+        let touched = touched_files(&frames(&["f_1", "f_2", "f_3"]), &targets, &kept);
+        assert!(!touched.iter().any(|name| name.starts_with("f_1.")));
+        assert!(touched.contains(&"f_4.colors".to_string()));
+    }
+
+    fn project(name: &str, stems: &[&str]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("decorator-frame-ops-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        for stem in stems {
+            fs::write(dir.join(format!("{}.txt", stem)), format!("{}\n", stem)).unwrap();
+        }
+        dir
+    }
+
+    fn contents(dir: &Path) -> Vec<String> {
+        scan_frames_in_dir(&dir.to_path_buf())
+            .unwrap()
+            .iter()
+            .map(|frame| fs::read_to_string(&frame.path).unwrap().trim().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn moves_and_duplicates_renumber_the_files() {
+        let dir = project("move", &["frame_0001", "frame_0002", "frame_0003"]);
+        let path = dir.to_string_lossy().to_string();
+
+        move_frame(path.clone(), 0, 2).unwrap();
+        assert_eq!(contents(&dir), ["frame_0002", "frame_0003", "frame_0001"]);
+
+        duplicate_frame(path.clone(), 1).unwrap();
+        assert_eq!(contents(&dir), ["frame_0002", "frame_0003", "frame_0003", "frame_0001"]);
+        assert!(dir.join("frame_0004.txt").exists());
+
+        delete_frame(path, 0).unwrap();
+        assert_eq!(contents(&dir), ["frame_0003", "frame_0003", "frame_0001"]);
+        assert!(!dir.join("frame_0004.txt").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,319 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use sha2::{Digest, Sha256};
+
+use crate::journal::Journal;
+
+/// Hidden folder inside the project holding the undo journal and file snapshots.
+const HISTORY_DIR: &str = ".decorator-history";
+const LOG_FILE: &str = "history.json";
+const BLOB_DIR: &str = "blobs";
+/// Oldest entries are dropped beyond this many.
+const MAX_ENTRIES: usize = 100;
+
+/// Serializes journal access between commands.
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+/// Contents of one file before and after a change, as blob names (`None` = file absent).
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+struct FileChange {
+    name: String,
+    before: Option<String>,
+    after: Option<String>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+struct Entry {
+    label: String,
+    timestamp: u64,
+    changes: Vec<FileChange>,
+}
+
+/// `entries[..cursor]` are applied; `entries[cursor..]` were undone and can be redone.
+#[derive(serde::Serialize, serde::Deserialize, Default, Debug)]
+struct HistoryLog {
+    cursor: usize,
+    entries: Vec<Entry>,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct HistoryItem {
+    pub label: String,
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+    pub files: usize,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct HistoryState {
+    pub entries: Vec<HistoryItem>,
+    pub cursor: usize,
+}
+
+/// Folder that owns `path`: the path itself for a folder, its parent for a file.
+pub fn project_dir(path: &Path) -> PathBuf {
+    if path.is_file() {
+        path.parent().map(|p| p.to_path_buf()).unwrap_or_default()
+    } else {
+        path.to_path_buf()
+    }
+}
+
+fn history_dir(dir: &Path) -> PathBuf {
+    dir.join(HISTORY_DIR)
+}
+
+fn load_log(dir: &Path) -> Result<HistoryLog, String> {
+    let path = history_dir(dir).join(LOG_FILE);
+    if !path.exists() {
+        return Ok(HistoryLog::default());
+    }
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read history: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse history: {}", e))
+}
+
+fn save_log(dir: &Path, log: &HistoryLog) -> Result<(), String> {
+    let path = history_dir(dir).join(LOG_FILE);
+    let content = serde_json::to_string(log).map_err(|e| format!("Failed to serialize history: {}", e))?;
+    write_atomic(&path, content.as_bytes())
+}
+
+fn write_atomic(path: &Path, data: &[u8]) -> Result<(), String> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+    fs::write(&tmp_path, data).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    fs::rename(&tmp_path, path).map_err(|e| {
+        let _ = fs::remove_file(&tmp_path);
+        format!("Failed to replace {}: {}", path.display(), e)
+    })
+}
+
+/// Store `data` under its SHA-256 so identical snapshots (e.g. renamed frames)
+/// share one blob, and different ones never collide.
+fn store_blob(dir: &Path, data: &[u8]) -> Result<String, String> {
+    let name: String = Sha256::digest(data).iter().map(|byte| format!("{:02x}", byte)).collect();
+
+    let blob_dir = history_dir(dir).join(BLOB_DIR);
+    fs::create_dir_all(&blob_dir).map_err(|e| format!("Failed to create history folder: {}", e))?;
+    let path = blob_dir.join(&name);
+    if !path.exists() {
+        write_atomic(&path, data)?;
+    }
+    Ok(name)
+}
+
+fn read_blob(dir: &Path, name: &str) -> Result<Vec<u8>, String> {
+    fs::read(history_dir(dir).join(BLOB_DIR).join(name))
+        .map_err(|e| format!("Failed to read history snapshot: {}", e))
+}
+
+/// Delete blobs no longer referenced by any entry.
+fn collect_garbage(dir: &Path, log: &HistoryLog) {
+    let referenced: HashSet<&str> = log
+        .entries
+        .iter()
+        .flat_map(|entry| &entry.changes)
+        .flat_map(|change| [change.before.as_deref(), change.after.as_deref()])
+        .flatten()
+        .collect();
+    let Ok(blobs) = fs::read_dir(history_dir(dir).join(BLOB_DIR)) else {
+        return;
+    };
+    for blob in blobs.flatten() {
+        let name = blob.file_name();
+        if !referenced.contains(name.to_str().unwrap_or_default()) {
+            let _ = fs::remove_file(blob.path());
+        }
+    }
+}
+
+fn snapshot(dir: &Path, names: &BTreeSet<String>) -> BTreeMap<String, Option<Vec<u8>>> {
+    names
+        .iter()
+        .map(|name| (name.clone(), fs::read(dir.join(name)).ok()))
+        .collect()
+}
+
+fn append_entry(
+    dir: &Path,
+    label: &str,
+    before: BTreeMap<String, Option<Vec<u8>>>,
+    after: BTreeMap<String, Option<Vec<u8>>>,
+) -> Result<(), String> {
+    let mut changes = Vec::new();
+    for (name, after_data) in &after {
+        let before_data = before.get(name).cloned().flatten();
+        if before_data == *after_data {
+            continue;
+        }
+        changes.push(FileChange {
+            name: name.clone(),
+            before: before_data.map(|d| store_blob(dir, &d)).transpose()?,
+            after: after_data.as_deref().map(|d| store_blob(dir, d)).transpose()?,
+        });
+    }
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    let mut log = load_log(dir)?;
+    // A new change discards everything that was undone
+    log.entries.truncate(log.cursor);
+    log.entries.push(Entry {
+        label: label.to_string(),
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        changes,
+    });
+    if log.entries.len() > MAX_ENTRIES {
+        let excess = log.entries.len() - MAX_ENTRIES;
+        log.entries.drain(..excess);
+    }
+    log.cursor = log.entries.len();
+    save_log(dir, &log)?;
+    collect_garbage(dir, &log);
+    Ok(())
+}
+
+/// Run `change` and journal how it modified the files listed by `files`
+/// (names relative to `dir`, listed both before and after the change) so it
+/// can be undone later.
+///
+/// The change itself decides success; a journal failure is only logged.
+pub fn record<T>(
+    dir: &Path,
+    label: &str,
+    files: &dyn Fn() -> Vec<String>,
+    change: impl FnOnce() -> Result<T, String>,
+) -> Result<T, String> {
+    let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    let mut names: BTreeSet<String> = files().into_iter().collect();
+    let before = snapshot(dir, &names);
+    let result = change()?;
+    names.extend(files());
+    let after = snapshot(dir, &names);
+
+    if let Err(e) = append_entry(dir, label, before, after) {
+        eprintln!("Failed to record history for {}: {}", dir.display(), e);
+    }
+    Ok(result)
+}
+
+/// Restore every file of `entry` to its `before` (undo) or `after` (redo) contents,
+/// then run `commit` to move the cursor.
+///
+/// Runs as a transaction: all snapshots are read up front, current files are moved
+/// aside into a backup folder before being replaced, and any failure, including
+/// one from `commit`, moves them back so the project is left exactly as it was.
+fn restore(dir: &Path, entry: &Entry, undo: bool, commit: impl FnOnce() -> Result<(), String>) -> Result<(), String> {
+    let mut targets = Vec::new();
+    for change in &entry.changes {
+        let blob = if undo { &change.before } else { &change.after };
+        let data = blob.as_deref().map(|name| read_blob(dir, name)).transpose()?;
+        targets.push((change.name.as_str(), data));
+    }
+
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let backup_dir = history_dir(dir).join(format!("restore-{}", millis));
+    fs::create_dir_all(history_dir(dir)).map_err(|e| format!("Failed to create history folder: {}", e))?;
+
+    let mut journal = Journal::default();
+    match replace_files(dir, &backup_dir, &targets, &mut journal).and_then(|()| commit()) {
+        Ok(()) => {
+            let _ = fs::remove_dir_all(&backup_dir);
+            Ok(())
+        }
+        Err(e) => {
+            journal.rollback();
+            Err(e)
+        }
+    }
+}
+
+fn replace_files(
+    dir: &Path,
+    backup_dir: &Path,
+    targets: &[(&str, Option<Vec<u8>>)],
+    journal: &mut Journal,
+) -> Result<(), String> {
+    journal.create_dir(backup_dir)?;
+    for (name, data) in targets {
+        let path = dir.join(name);
+        if path.exists() {
+            journal.rename(&path, &backup_dir.join(name))?;
+        }
+        if let Some(data) = data {
+            journal.write(&path, data)?;
+        }
+    }
+    Ok(())
+}
+
+fn history_state(log: &HistoryLog) -> HistoryState {
+    HistoryState {
+        entries: log
+            .entries
+            .iter()
+            .map(|entry| HistoryItem {
+                label: entry.label.clone(),
+                timestamp: entry.timestamp,
+                files: entry.changes.len(),
+            })
+            .collect(),
+        cursor: log.cursor,
+    }
+}
+
+#[tauri::command]
+pub fn get_history(directory_path: String) -> Result<HistoryState, String> {
+    let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = project_dir(Path::new(&directory_path));
+    Ok(history_state(&load_log(&dir)?))
+}
+
+/// Revert the most recent applied change.
+#[tauri::command]
+pub fn undo(directory_path: String) -> Result<HistoryState, String> {
+    let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = project_dir(Path::new(&directory_path));
+    let mut log = load_log(&dir)?;
+    if log.cursor == 0 {
+        return Err("Nothing to undo".to_string());
+    }
+
+    let entry = log.entries[log.cursor - 1].clone();
+    restore(&dir, &entry, true, || {
+        log.cursor -= 1;
+        save_log(&dir, &log)
+    })?;
+    Ok(history_state(&log))
+}
+
+/// Re-apply the most recently undone change.
+#[tauri::command]
+pub fn redo(directory_path: String) -> Result<HistoryState, String> {
+    let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = project_dir(Path::new(&directory_path));
+    let mut log = load_log(&dir)?;
+    if log.cursor >= log.entries.len() {
+        return Err("Nothing to redo".to_string());
+    }
+
+    let entry = log.entries[log.cursor].clone();
+    restore(&dir, &entry, false, || {
+        log.cursor += 1;
+        save_log(&dir, &log)
+    })?;
+    Ok(history_state(&log))
+}
@@ -0,0 +1,123 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+enum FileOp {
+    Renamed { from: PathBuf, to: PathBuf },
+    Created(PathBuf),
+    CreatedDir(PathBuf),
+}
+
+/// File operations performed so far, undone in reverse order on failure.
+#[derive(Default)]
+pub struct Journal {
+    ops: Vec<FileOp>,
+    /// Files `replace` moved aside, deleted once the change is kept
+    backups: Vec<PathBuf>,
+}
+
+impl Journal {
+    pub fn create_dir(&mut self, path: &Path) -> Result<(), String> {
+        fs::create_dir(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+        self.ops.push(FileOp::CreatedDir(path.to_path_buf()));
+        Ok(())
+    }
+
+    pub fn rename(&mut self, from: &Path, to: &Path) -> Result<(), String> {
+        fs::rename(from, to).map_err(|e| format!("Failed to move {}: {}", from.display(), e))?;
+        self.ops.push(FileOp::Renamed {
+            from: from.to_path_buf(),
+            to: to.to_path_buf(),
+        });
+        Ok(())
+    }
+
+    pub fn copy(&mut self, from: &Path, to: &Path) -> Result<(), String> {
+        fs::copy(from, to).map_err(|e| format!("Failed to copy {}: {}", from.display(), e))?;
+        self.ops.push(FileOp::Created(to.to_path_buf()));
+        Ok(())
+    }
+
+    pub fn write(&mut self, path: &Path, content: &[u8]) -> Result<(), String> {
+        fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        self.ops.push(FileOp::Created(path.to_path_buf()));
+        Ok(())
+    }
+
+    /// Replace `path` with `content`. The new content is written next to it first,
+    /// and the old file is kept aside until `commit`.
+    pub fn replace(&mut self, path: &Path, content: &[u8]) -> Result<(), String> {
+        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let tmp = path.with_file_name(format!(".{}.tmp", name));
+        self.write(&tmp, content)?;
+        if path.exists() {
+            let backup = path.with_file_name(format!(".{}.bak", name));
+            self.rename(path, &backup)?;
+            self.backups.push(backup);
+        }
+        self.rename(&tmp, path)
+    }
+
+    /// Keep the changes made so far.
+    pub fn commit(self) {
+        for backup in self.backups {
+            let _ = fs::remove_file(backup);
+        }
+    }
+
+    pub fn rollback(self) {
+        for op in self.ops.into_iter().rev() {
+            let _ = match op {
+                FileOp::Renamed { from, to } => fs::rename(to, from),
+                FileOp::Created(path) => fs::remove_file(path),
+                FileOp::CreatedDir(path) => fs::remove_dir(path),
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn folder(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("decorator-journal-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.txt"), "old").unwrap();
+        dir
+    }
+
+    fn files(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn rollback_puts_replaced_files_back() {
+        let dir = folder("rollback");
+        let mut journal = Journal::default();
+        journal.replace(&dir.join("a.txt"), b"new").unwrap();
+        journal.replace(&dir.join("b.txt"), b"new").unwrap();
+        assert!(journal.replace(&dir.join("missing").join("c.txt"), b"new").is_err());
+        journal.rollback();
+        assert_eq!(files(&dir), ["a.txt"]);
+        assert_eq!(fs::read_to_string(dir.join("a.txt")).unwrap(), "old");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn commit_drops_the_backups() {
+        let dir = folder("commit");
+        let mut journal = Journal::default();
+        journal.replace(&dir.join("a.txt"), b"new").unwrap();
+        journal.commit();
+        assert_eq!(files(&dir), ["a.txt"]);
+        assert_eq!(fs::read_to_string(dir.join("a.txt")).unwrap(), "new");
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,7 +1,31 @@
 use std::collections::HashSet;
 use std::fs;
 use std::path::PathBuf;
-use tauri::Emitter;
+use tauri::{Emitter, Manager};
+
+use crate::journal::Journal;
+
+mod cli;
+mod clipboard;
+mod details;
+mod fonts;
+mod frame_ops;
+mod history;
+mod journal;
+mod library;
+#[cfg(target_os = "linux")]
+mod mpris;
+mod osc;
+mod package;
+mod remote;
+mod rpc;
+mod settings;
+mod single_instance;
+mod stream;
+mod sync;
+mod waveform;
+mod widget;
+mod windows;
 
 // Re-export shared types from cascii-core-view
 use cascii_core_view::FrameFile;
@@ -96,6 +120,25 @@ fn read_frame_file(file_path: String) -> Result<String, String> {
     cascii_core_view::parse_cframe_text(&data).map_err(|e| e.to_string())
 }
 
+/// Write frame text back to its .txt file.
+/// Writes to a temporary sibling first and renames it over the original so a
+/// failed write never leaves a truncated frame behind.
+fn frame_text_path(file_path: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(file_path);
+    if path.extension().and_then(|e| e.to_str()) != Some("txt") {
+        return Err(format!("Not a .txt frame file: {}", file_path));
+    }
+    Ok(path)
+}
+
+fn file_name_of(path: &str) -> String {
+    PathBuf::from(path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
 pub struct ColorData {
     pub width: u32,
@@ -116,14 +159,23 @@ fn read_colors_file(txt_file_path: String) -> Result<Option<ColorData>, String>
 
     let data =
         fs::read(&colors_path).map_err(|e| format!("Failed to read colors file: {}", e))?;
+    parse_colors_data(&data).map(Some)
+}
 
+/// Parse color data in the layout documented on `read_colors_file`.
+pub fn parse_colors_data(data: &[u8]) -> Result<ColorData, String> {
     if data.len() < 8 {
         return Err("Colors file too small (missing header)".to_string());
     }
 
     let width = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
     let height = u32::from_le_bytes([data[4], data[5], data[6], data[7]]);
-    let expected_size = 8 + (width as usize * height as usize * 3);
+    // Streamed frames come from other programs, so don't trust the header to be sane
+    let expected_size = (width as usize)
+        .checked_mul(height as usize)
+        .and_then(|cells| cells.checked_mul(3))
+        .and_then(|len| len.checked_add(8))
+        .ok_or("Colors size out of range")?;
 
     if data.len() < expected_size {
         return Err(format!(
@@ -135,7 +187,81 @@ fn read_colors_file(txt_file_path: String) -> Result<Option<ColorData>, String>
 
     let rgb = data[8..expected_size].to_vec();
 
-    Ok(Some(ColorData { width, height, rgb }))
+    Ok(ColorData { width, height, rgb })
+}
+
+/// Write per-character colors for a frame to its .colors sibling, using the
+/// layout documented on `read_colors_file`.
+/// Encode colors in the .colors format read by `read_colors_file`.
+fn colors_bytes(colors: &ColorData) -> Result<Vec<u8>, String> {
+    let expected_size = colors.width as usize * colors.height as usize * 3;
+    if colors.rgb.len() != expected_size {
+        return Err(format!(
+            "Color data size mismatch: expected {} bytes, got {}",
+            expected_size,
+            colors.rgb.len()
+        ));
+    }
+
+    let mut data = Vec::with_capacity(8 + expected_size);
+    data.extend_from_slice(&colors.width.to_le_bytes());
+    data.extend_from_slice(&colors.height.to_le_bytes());
+    data.extend_from_slice(&colors.rgb);
+    Ok(data)
+}
+
+#[derive(serde::Deserialize, Clone, Debug)]
+pub struct TextEdit {
+    pub file_path: String,
+    pub content: String,
+}
+
+#[derive(serde::Deserialize, Clone, Debug)]
+pub struct ColorEdit {
+    pub txt_file_path: String,
+    pub colors: ColorData,
+}
+
+/// Save edited frame text and colors as a single undoable change.
+/// A failed write puts back the files already replaced.
+#[tauri::command]
+fn save_frame_edits(
+    directory_path: String,
+    texts: Vec<TextEdit>,
+    colors: Vec<ColorEdit>,
+) -> Result<(), String> {
+    let dir = history::project_dir(&PathBuf::from(&directory_path));
+    let mut names: Vec<String> = texts.iter().map(|t| file_name_of(&t.file_path)).collect();
+    names.extend(colors.iter().map(|c| {
+        file_name_of(&PathBuf::from(&c.txt_file_path).with_extension("colors").to_string_lossy())
+    }));
+    let count = texts.len() + colors.len();
+    let label = if count == 1 {
+        format!("Save {}", names[0])
+    } else {
+        format!("Save {} files", count)
+    };
+
+    // Everything is checked before the first file changes
+    let mut writes = Vec::with_capacity(count);
+    for text in &texts {
+        writes.push((frame_text_path(&text.file_path)?, text.content.as_bytes().to_vec()));
+    }
+    for color in &colors {
+        writes.push((PathBuf::from(&color.txt_file_path).with_extension("colors"), colors_bytes(&color.colors)?));
+    }
+
+    history::record(&dir, &label, &|| names.clone(), || {
+        let mut journal = Journal::default();
+        for (path, content) in &writes {
+            if let Err(e) = journal.replace(path, content) {
+                journal.rollback();
+                return Err(e);
+            }
+        }
+        journal.commit();
+        Ok(())
+    })
 }
 
 /// Given a .txt frame file path, look for a matching .cframe file and return raw bytes.
@@ -169,6 +295,56 @@ pub struct ProjectDetails {
     pub audio_path: Option<String>,
     pub background_color: Option<String>,
     pub color: Option<String>,
+    pub timing: Vec<TimingRange>,
+    /// How far the audio lags behind the frames; negative when it runs ahead
+    pub audio_offset_ms: i32,
+    /// Font family name, or a font file relative to the project folder
+    pub font: Option<String>,
+}
+
+/// Frames shown for a fixed time instead of 1/fps seconds.
+/// `start` and `end` are 0-based and inclusive.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct TimingRange {
+    pub start: usize,
+    pub end: usize,
+    pub duration_ms: u32,
+}
+
+/// Parse the `[timing]` table of details.toml. Keys are 1-based frame numbers
+/// or ranges, values are milliseconds per frame:
+///
+/// ```toml
+/// [timing]
+/// 1 = 2000       # title card held for 2 s
+/// 10-12 = 500
+/// ```
+fn parse_timing(table: &toml::Table) -> Vec<TimingRange> {
+    let Some(timing) = table.get("timing").and_then(|t| t.as_table()) else {
+        return Vec::new();
+    };
+
+    timing
+        .iter()
+        .filter_map(|(key, value)| {
+            let duration_ms = u32::try_from(value.as_integer()?).ok().filter(|ms| *ms > 0)?;
+            let (start, end) = match key.split_once('-') {
+                Some((start, end)) => (start.trim().parse::<usize>().ok()?, end.trim().parse::<usize>().ok()?),
+                None => {
+                    let frame = key.trim().parse::<usize>().ok()?;
+                    (frame, frame)
+                }
+            };
+            if start == 0 || end < start {
+                return None;
+            }
+            Some(TimingRange {
+                start: start - 1,
+                end: end - 1,
+                duration_ms,
+            })
+        })
+        .collect()
 }
 
 /// Read audio file and return as base64 for data URL
@@ -188,6 +364,33 @@ fn read_audio_file(audio_path: String) -> Result<String, String> {
     Ok(format!("data:audio/mpeg;base64,{}", b64))
 }
 
+/// Palette files looked for in the project folder, in order.
+const PALETTE_FILES: [&str; 3] = ["palette.gpl", "palette.hex", "palette.txt"];
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct PaletteFile {
+    pub name: String,
+    pub text: String,
+}
+
+/// Read the project's custom palette (GIMP .gpl or one hex color per line), if it has one.
+#[tauri::command]
+fn read_palette_file(directory_path: String) -> Result<Option<PaletteFile>, String> {
+    let dir = history::project_dir(&PathBuf::from(&directory_path));
+    for name in PALETTE_FILES {
+        let path = dir.join(name);
+        if path.exists() {
+            let text = fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read palette file: {}", e))?;
+            return Ok(Some(PaletteFile {
+                name: name.to_string(),
+                text,
+            }));
+        }
+    }
+    Ok(None)
+}
+
 /// Read project metadata from details.toml (or fallback to details.md)
 #[tauri::command]
 fn read_project_details(directory_path: String) -> Result<ProjectDetails, String> {
@@ -211,6 +414,9 @@ fn read_project_details(directory_path: String) -> Result<ProjectDetails, String
     let mut fps: Option<u32> = None;
     let mut background_color: Option<String> = None;
     let mut color: Option<String> = None;
+    let mut timing = Vec::new();
+    let mut audio_offset_ms = 0;
+    let mut font: Option<String> = None;
 
     // Try details.toml first, fall back to details.md
     let toml_path = dir.join("details.toml");
@@ -223,6 +429,20 @@ fn read_project_details(directory_path: String) -> Result<ProjectDetails, String
                 background_color = details.background_color;
                 color = details.color;
             }
+            // Keys cascii itself doesn't know about
+            if let Ok(table) = content.parse::<toml::Table>() {
+                timing = parse_timing(&table);
+                audio_offset_ms = table
+                    .get("audio_offset_ms")
+                    .and_then(|v| v.as_integer())
+                    .and_then(|v| i32::try_from(v).ok())
+                    .unwrap_or(0);
+                font = table
+                    .get("font")
+                    .and_then(|v| v.as_str())
+                    .map(|v| v.trim().to_string())
+                    .filter(|v| !v.is_empty());
+            }
         }
     } else if md_path.exists() {
         if let Ok(content) = fs::read_to_string(&md_path) {
@@ -240,30 +460,164 @@ fn read_project_details(directory_path: String) -> Result<ProjectDetails, String
         audio_path: audio_path_str,
         background_color,
         color,
+        timing,
+        audio_offset_ms,
+        font,
     })
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let launch = match cli::parse(std::env::args().skip(1), &cwd) {
+        Ok(cli::Command::Run(options)) => options,
+        Ok(cli::Command::Exit(message)) => {
+            println!("{}", message);
+            return;
+        }
+        Err(e) => {
+            eprintln!("{}\n\nRun with --help for usage.", e);
+            std::process::exit(2);
+        }
+    };
+    let fullscreen = launch.fullscreen;
+    let rpc_stdio = launch.rpc_stdio;
+
+    let builder = tauri::Builder::default();
+    // Has to come first so a second launch exits before setting anything up.
+    // A child driven over stdio answers to its parent, never to another instance.
+    let builder = if launch.new_instance || launch.rpc_stdio {
+        builder
+    } else {
+        builder.plugin(tauri_plugin_single_instance::init(|app, argv, cwd| {
+            single_instance::forward(app, argv, cwd);
+        }))
+    };
+    #[cfg(target_os = "linux")]
+    let builder = builder.manage(mpris::MprisState::default());
+    builder
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(|app, _shortcut, event| {
+                    if event.state() == tauri_plugin_global_shortcut::ShortcutState::Pressed {
+                        widget::exit_all(app);
+                    }
+                })
+                .build(),
+        )
+        .manage(widget::WidgetState::default())
+        .manage(remote::RemoteState::default())
+        .manage(osc::OscState::default())
+        .manage(sync::SyncState::new(launch.sync.clone()))
+        .manage(rpc::RpcState::default())
+        .manage(stream::StreamState::default())
+        .manage(launch)
+        .setup(move |app| {
+            if fullscreen {
+                if let Some(window) = app.get_webview_window("main") {
+                    window.set_fullscreen(true)?;
+                }
+            }
+            // A server that can't start shouldn't keep the viewer from opening
+            let saved = settings::load(app.handle());
+            if let Err(e) = remote::apply(app.handle(), &saved.remote) {
+                eprintln!("{}", e);
+            }
+            if let Err(e) = osc::apply(app.handle(), &saved.osc) {
+                eprintln!("{}", e);
+            }
+            if let Err(e) = sync::apply(app.handle(), &saved.sync) {
+                eprintln!("{}", e);
+            }
+            #[cfg(target_os = "linux")]
+            mpris::start(app.handle());
+            if rpc_stdio {
+                rpc::start(app.handle());
+            }
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
+            cli::get_launch_options,
+            settings::get_app_settings,
+            settings::set_app_settings,
             get_frame_files,
             read_frame_file,
             read_colors_file,
+            save_frame_edits,
             read_cframe_file,
             get_frame_count,
             read_project_details,
-            read_audio_file
+            read_palette_file,
+            details::read_details_fields,
+            details::write_project_details,
+            read_audio_file,
+            fonts::list_fonts,
+            fonts::read_font_file,
+            waveform::get_audio_waveform,
+            clipboard::copy_text_to_clipboard,
+            clipboard::copy_html_to_clipboard,
+            clipboard::copy_png_to_clipboard,
+            frame_ops::duplicate_frame,
+            frame_ops::delete_frame,
+            frame_ops::move_frame,
+            frame_ops::insert_blank_frame,
+            history::get_history,
+            history::undo,
+            history::redo,
+            library::scan_library,
+            remote::get_remote_status,
+            remote::report_playback_state,
+            rpc::finish_rpc_export,
+            stream::start_stream,
+            stream::stop_stream,
+            widget::enter_widget_mode,
+            widget::exit_widget_mode,
+            widget::resize_widget,
+            windows::open_viewer_window
         ])
-        .on_window_event(|window, event| {
-            if let tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) = event {
+        .on_window_event(|window, event| match event {
+            tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) => {
                 if let Some(path) = paths.first() {
                     let path_str = path.to_string_lossy().to_string();
-                    let _ = window.emit("file-drop", path_str);
+                    // Only the window the drop was made on opens it
+                    let _ = window.emit_to(window.label(), "file-drop", path_str);
                 }
             }
+            tauri::WindowEvent::Destroyed => {
+                // Streams and hidden viewers outlive a window closed by other means
+                stream::stop(window.app_handle(), window.label());
+                widget::forget(window.app_handle(), window.label());
+            }
+            _ => {}
         })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranges(toml: &str) -> Vec<(usize, usize, u32)> {
+        let mut ranges: Vec<_> = parse_timing(&toml.parse::<toml::Table>().unwrap())
+            .iter()
+            .map(|range| (range.start, range.end, range.duration_ms))
+            .collect();
+        ranges.sort();
+        ranges
+    }
+
+    #[test]
+    fn parse_timing_reads_frames_and_ranges() {
+        assert_eq!(ranges("[timing]\n1 = 500\n\"3-5\" = 120\n\" 7 - 8 \" = 40"), [(0, 0, 500), (2, 4, 120), (6, 7, 40)]);
+        assert!(ranges("fps = 12").is_empty());
+    }
+
+    #[test]
+    fn parse_timing_skips_invalid_entries() {
+        let toml = "[timing]\n0 = 100\n\"5-3\" = 100\n2 = 0\n3 = -10\n4 = \"slow\"\nx = 100\n\"1-\" = 100\n6 = 250";
+        assert_eq!(ranges(toml), [(5, 5, 250)]);
+    }
+}
@@ -0,0 +1,71 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How deep below the root to look for projects.
+const MAX_SCAN_DEPTH: usize = 6;
+
+/// A project folder found under a library root.
+#[derive(serde::Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryProject {
+    pub path: String,
+    /// Folder name, for display
+    pub name: String,
+    pub frame_count: usize,
+}
+
+fn collect_projects(dir: &Path, depth: usize, projects: &mut Vec<LibraryProject>) {
+    // A project's own subfolders (history and the like) are not projects
+    if let Ok(frames) = crate::scan_frames_in_dir(&dir.to_path_buf()) {
+        if !frames.is_empty() {
+            projects.push(LibraryProject {
+                path: dir.to_string_lossy().to_string(),
+                name: dir
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                frame_count: frames.len(),
+            });
+            return;
+        }
+    }
+    if depth >= MAX_SCAN_DEPTH {
+        return;
+    }
+
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut subdirs: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .filter(|path| {
+            !path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .map(|n| n.starts_with('.'))
+                .unwrap_or(true)
+        })
+        .collect();
+    subdirs.sort();
+    for subdir in subdirs {
+        collect_projects(&subdir, depth + 1, projects);
+    }
+}
+
+/// Every project folder under `root_path` (or the root itself when it is one), in path order.
+#[tauri::command]
+pub async fn scan_library(root_path: String) -> Result<Vec<LibraryProject>, String> {
+    let root = PathBuf::from(&root_path);
+    if !root.is_dir() {
+        return Err("Library folder does not exist".to_string());
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut projects = Vec::new();
+        collect_projects(&root, 0, &mut projects);
+        projects
+    })
+    .await
+    .map_err(|e| format!("Library scan failed: {}", e))
+}
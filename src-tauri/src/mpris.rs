@@ -0,0 +1,373 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast::error::RecvError;
+use zbus::fdo;
+use zbus::object_server::SignalEmitter;
+use zbus::zvariant::{ObjectPath, OwnedValue, Value};
+
+use crate::remote::{self, PlaybackState, RemoteCommand, RemoteState};
+
+const BUS_NAME: &str = "org.mpris.MediaPlayer2.decorator";
+const OBJECT_PATH: &str = "/org/mpris/MediaPlayer2";
+/// The project is the one "track"; MPRIS needs an object path to name it.
+const TRACK_ID: &str = "/org/decorator/project";
+
+/// Whether the media player interface is on the session bus.
+#[derive(Default)]
+pub struct MprisState {
+    running: AtomicBool,
+}
+
+impl MprisState {
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+}
+
+/// Position as MPRIS counts it: the shown frame's start on the timeline, in microseconds.
+fn position(playback: &PlaybackState) -> i64 {
+    (playback.position_ms * 1000.0) as i64
+}
+
+fn length(playback: &PlaybackState) -> i64 {
+    (playback.duration_ms * 1000.0) as i64
+}
+
+/// Has the viewer show the frame on screen at `position`, in microseconds.
+fn seek_to(position: i64) -> RemoteCommand {
+    RemoteCommand::SeekTime {
+        ms: position.max(0) as f64 / 1000.0,
+    }
+}
+
+fn has_project(playback: &PlaybackState) -> bool {
+    !playback.directory_path.is_empty() && playback.frame_count > 0
+}
+
+fn failed(e: String) -> fdo::Error {
+    fdo::Error::Failed(e)
+}
+
+struct Root {
+    app: AppHandle,
+}
+
+#[zbus::interface(name = "org.mpris.MediaPlayer2")]
+impl Root {
+    fn raise(&self) {
+        if let Some(window) = crate::windows::primary(&self.app) {
+            let _ = window.unminimize();
+            let _ = window.set_focus();
+        }
+    }
+
+    fn quit(&self) {
+        self.app.exit(0);
+    }
+
+    #[zbus(property)]
+    fn can_quit(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_raise(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn has_track_list(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn identity(&self) -> String {
+        "Decorator".to_string()
+    }
+
+    #[zbus(property)]
+    fn desktop_entry(&self) -> String {
+        "decorator".to_string()
+    }
+
+    #[zbus(property)]
+    fn supported_uri_schemes(&self) -> Vec<String> {
+        vec!["file".to_string()]
+    }
+
+    #[zbus(property)]
+    fn supported_mime_types(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+struct Player {
+    app: AppHandle,
+}
+
+impl Player {
+    fn playback(&self) -> PlaybackState {
+        self.app.state::<RemoteState>().playback()
+    }
+
+    fn send(&self, command: RemoteCommand) -> fdo::Result<()> {
+        remote::dispatch(&self.app, command).map_err(failed)
+    }
+}
+
+/// Next and Previous step through frames, as the arrow keys do.
+#[zbus::interface(name = "org.mpris.MediaPlayer2.Player")]
+impl Player {
+    fn next(&self) -> fdo::Result<()> {
+        self.send(RemoteCommand::Step { frames: 1 })
+    }
+
+    fn previous(&self) -> fdo::Result<()> {
+        self.send(RemoteCommand::Step { frames: -1 })
+    }
+
+    fn pause(&self) -> fdo::Result<()> {
+        self.send(RemoteCommand::Pause)
+    }
+
+    fn play_pause(&self) -> fdo::Result<()> {
+        self.send(RemoteCommand::Toggle)
+    }
+
+    /// Back to the first frame, paused
+    fn stop(&self) -> fdo::Result<()> {
+        self.send(RemoteCommand::Seek { frame: 0 })
+    }
+
+    fn play(&self) -> fdo::Result<()> {
+        self.send(RemoteCommand::Play)
+    }
+
+    fn seek(&self, offset: i64) -> fdo::Result<()> {
+        let playback = self.playback();
+        self.send(seek_to(position(&playback).saturating_add(offset)))
+    }
+
+    fn set_position(&self, track_id: ObjectPath<'_>, position: i64) -> fdo::Result<()> {
+        // Requests for a track that is no longer current are to be ignored
+        if track_id.as_str() != TRACK_ID {
+            return Ok(());
+        }
+        // Positions past the end are to be ignored too
+        if !(0..=length(&self.playback())).contains(&position) {
+            return Ok(());
+        }
+        self.send(seek_to(position))
+    }
+
+    fn open_uri(&self, uri: &str) -> fdo::Result<()> {
+        let path = uri
+            .strip_prefix("file://")
+            .ok_or_else(|| fdo::Error::NotSupported(format!("Only file:// URIs can be opened: {}", uri)))?;
+        self.send(RemoteCommand::Open {
+            path: percent_decode(path),
+        })
+    }
+
+    #[zbus(signal)]
+    async fn seeked(emitter: &SignalEmitter<'_>, position: i64) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn playback_status(&self) -> String {
+        let playback = self.playback();
+        match (has_project(&playback), playback.playing) {
+            (false, _) => "Stopped",
+            (true, true) => "Playing",
+            (true, false) => "Paused",
+        }
+        .to_string()
+    }
+
+    #[zbus(property)]
+    fn rate(&self) -> f64 {
+        1.0
+    }
+
+    #[zbus(property)]
+    fn minimum_rate(&self) -> f64 {
+        1.0
+    }
+
+    #[zbus(property)]
+    fn maximum_rate(&self) -> f64 {
+        1.0
+    }
+
+    #[zbus(property)]
+    fn metadata(&self) -> HashMap<String, OwnedValue> {
+        let playback = self.playback();
+        let mut metadata = HashMap::new();
+        if !has_project(&playback) {
+            return metadata;
+        }
+        let title = Path::new(&playback.directory_path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| playback.directory_path.clone());
+        let entries = [
+            ("mpris:trackid", Value::from(ObjectPath::from_static_str_unchecked(TRACK_ID))),
+            ("mpris:length", Value::from(length(&playback))),
+            ("xesam:title", Value::from(title)),
+            ("xesam:url", Value::from(format!("file://{}", playback.directory_path))),
+        ];
+        for (key, value) in entries {
+            if let Ok(value) = OwnedValue::try_from(value) {
+                metadata.insert(key.to_string(), value);
+            }
+        }
+        metadata
+    }
+
+    #[zbus(property)]
+    fn volume(&self) -> f64 {
+        let playback = self.playback();
+        if playback.muted {
+            0.0
+        } else {
+            playback.volume
+        }
+    }
+
+    #[zbus(property)]
+    fn set_volume(&self, volume: f64) -> zbus::Result<()> {
+        remote::dispatch(&self.app, RemoteCommand::Volume { volume: volume.clamp(0.0, 1.0) })
+            .map_err(zbus::Error::Failure)
+    }
+
+    /// Clients poll this; only jumps are announced, with Seeked
+    #[zbus(property(emits_changed_signal = "false"))]
+    fn position(&self) -> i64 {
+        position(&self.playback())
+    }
+
+    #[zbus(property)]
+    fn can_go_next(&self) -> bool {
+        has_project(&self.playback())
+    }
+
+    #[zbus(property)]
+    fn can_go_previous(&self) -> bool {
+        has_project(&self.playback())
+    }
+
+    #[zbus(property)]
+    fn can_play(&self) -> bool {
+        has_project(&self.playback())
+    }
+
+    #[zbus(property)]
+    fn can_pause(&self) -> bool {
+        has_project(&self.playback())
+    }
+
+    #[zbus(property)]
+    fn can_seek(&self) -> bool {
+        has_project(&self.playback())
+    }
+
+    #[zbus(property(emits_changed_signal = "const"))]
+    fn can_control(&self) -> bool {
+        true
+    }
+}
+
+/// `%20` and friends in file URIs.
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+async fn connect(app: &AppHandle, name: String) -> zbus::Result<zbus::Connection> {
+    zbus::connection::Builder::session()?
+        .name(name)?
+        .serve_at(OBJECT_PATH, Root { app: app.clone() })?
+        .serve_at(OBJECT_PATH, Player { app: app.clone() })?
+        .build()
+        .await
+}
+
+/// Put decorator on the session bus as a media player, so media keys, playerctl
+/// and desktop widgets can control it. Without a session bus this does nothing.
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = serve(&app).await {
+            eprintln!("Media controls unavailable: {}", e);
+        }
+        app.state::<MprisState>().running.store(false, Ordering::Relaxed);
+        remote::notify_status(&app);
+    });
+}
+
+async fn serve(app: &AppHandle) -> zbus::Result<()> {
+    // Instances started with --new-instance each need a name of their own
+    let connection = match connect(app, BUS_NAME.to_string()).await {
+        Ok(connection) => connection,
+        Err(_) => connect(app, format!("{}.instance{}", BUS_NAME, std::process::id())).await?,
+    };
+    let player = connection.object_server().interface::<_, Player>(OBJECT_PATH).await?;
+    app.state::<MprisState>().running.store(true, Ordering::Relaxed);
+    remote::notify_status(app);
+
+    // Announce what changed in the playback the viewer reports
+    let mut updates = app.state::<RemoteState>().subscribe();
+    let mut last = PlaybackState::default();
+    loop {
+        let playback = match updates.recv().await {
+            Ok(playback) => playback,
+            Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => return Ok(()),
+        };
+        let emitter = player.signal_emitter();
+        let iface = player.get().await;
+        let project_changed = playback.directory_path != last.directory_path
+            || playback.frame_count != last.frame_count
+            || playback.duration_ms != last.duration_ms;
+        if project_changed || playback.playing != last.playing {
+            iface.playback_status_changed(emitter).await?;
+        }
+        if project_changed {
+            iface.metadata_changed(emitter).await?;
+            iface.can_play_changed(emitter).await?;
+            iface.can_pause_changed(emitter).await?;
+            iface.can_seek_changed(emitter).await?;
+            iface.can_go_next_changed(emitter).await?;
+            iface.can_go_previous_changed(emitter).await?;
+        }
+        if playback.volume != last.volume || playback.muted != last.muted {
+            iface.volume_changed(emitter).await?;
+        }
+        // Anything but the next frame, or the loop back to the first, is a jump
+        let looped = playback.frame == 0 && last.frame + 1 == last.frame_count;
+        if !project_changed && playback.frame != last.frame && playback.frame != last.frame + 1 && !looped {
+            Player::seeked(emitter, position(&playback)).await?;
+        }
+        last = playback;
+    }
+}
@@ -0,0 +1,397 @@
+use std::io::ErrorKind;
+use std::net::{Ipv4Addr, SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::cli::COLOR_MODES;
+use crate::remote::{self, RemoteCommand, RemoteState};
+use crate::settings::OscSettings;
+
+/// Sent with the current frame index when OSC output is on.
+const FRAME_ADDRESS: &str = "/decorator/frame";
+/// How often the listener and output threads check whether they were stopped.
+const POLL: Duration = Duration::from_millis(250);
+
+#[derive(Clone, Debug, PartialEq)]
+enum OscArg {
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Bool(bool),
+    Other,
+}
+
+impl OscArg {
+    fn number(&self) -> Option<f64> {
+        match self {
+            OscArg::Int(n) => Some(*n as f64),
+            OscArg::Float(n) => Some(*n),
+            OscArg::Bool(b) => Some(if *b { 1.0 } else { 0.0 }),
+            _ => None,
+        }
+    }
+}
+
+/// Reads OSC 1.0 strings, numbers and blobs, which are all padded to four bytes.
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self.pos.checked_add(len).filter(|&end| end <= self.data.len()).ok_or("Truncated OSC packet")?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn word(&mut self) -> Result<[u8; 4], String> {
+        Ok(self.take(4)?.try_into().unwrap())
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let rest = &self.data[self.pos.min(self.data.len())..];
+        let len = rest.iter().position(|&b| b == 0).ok_or("Unterminated OSC string")?;
+        let text = String::from_utf8_lossy(&rest[..len]).to_string();
+        self.take((len + 4) & !3)?;
+        Ok(text)
+    }
+
+    fn blob(&mut self) -> Result<&'a [u8], String> {
+        let len = i32::from_be_bytes(self.word()?).max(0) as usize;
+        let bytes = self.take(len)?;
+        self.take((4 - len % 4) % 4)?;
+        Ok(bytes)
+    }
+}
+
+/// Messages in a packet, with those inside bundles flattened in order.
+fn parse_packet(data: &[u8], messages: &mut Vec<(String, Vec<OscArg>)>) -> Result<(), String> {
+    let mut reader = Reader { data, pos: 0 };
+    if data.starts_with(b"#bundle\0") {
+        reader.take(16)?; // "#bundle" and the time tag, which we don't schedule by
+        while reader.pos < data.len() {
+            let element = reader.blob()?;
+            parse_packet(element, messages)?;
+        }
+        return Ok(());
+    }
+
+    let address = reader.string()?;
+    // Some old senders leave out the type tags
+    let tags = if reader.pos < data.len() { reader.string()? } else { String::new() };
+    let mut args = Vec::new();
+    for tag in tags.chars().skip_while(|&c| c == ',') {
+        args.push(match tag {
+            'i' => OscArg::Int(i32::from_be_bytes(reader.word()?) as i64),
+            'h' => OscArg::Int(i64::from_be_bytes(reader.take(8)?.try_into().unwrap())),
+            'f' => OscArg::Float(f32::from_be_bytes(reader.word()?) as f64),
+            'd' => OscArg::Float(f64::from_be_bytes(reader.take(8)?.try_into().unwrap())),
+            's' | 'S' => OscArg::Str(reader.string()?),
+            'b' => {
+                reader.blob()?;
+                OscArg::Other
+            }
+            'T' => OscArg::Bool(true),
+            'F' => OscArg::Bool(false),
+            'c' | 'r' | 'm' => {
+                reader.word()?;
+                OscArg::Other
+            }
+            't' => {
+                reader.take(8)?;
+                OscArg::Other
+            }
+            'N' | 'I' => OscArg::Other,
+            other => return Err(format!("Unsupported OSC type tag: {}", other)),
+        });
+    }
+    messages.push((address, args));
+    Ok(())
+}
+
+fn push_string(packet: &mut Vec<u8>, text: &str) {
+    packet.extend_from_slice(text.as_bytes());
+    packet.resize((packet.len() + 4) & !3, 0);
+}
+
+/// An OSC message with a single int argument.
+fn encode_int_message(address: &str, value: i32) -> Vec<u8> {
+    let mut packet = Vec::new();
+    push_string(&mut packet, address);
+    push_string(&mut packet, ",i");
+    packet.extend_from_slice(&value.to_be_bytes());
+    packet
+}
+
+/// The command an OSC message stands for. Buttons send 1 when pressed and 0 when
+/// released, so play/pause/toggle with a zero argument are ignored.
+fn command(address: &str, args: &[OscArg]) -> Result<Option<RemoteCommand>, String> {
+    let number = || args.first().and_then(OscArg::number);
+    let needs_number = || number().ok_or_else(|| format!("{} needs a number", address));
+    let released = number() == Some(0.0);
+    let command = match address {
+        "/decorator/play" | "/decorator/pause" | "/decorator/toggle" if released => return Ok(None),
+        "/decorator/play" => RemoteCommand::Play,
+        "/decorator/pause" => RemoteCommand::Pause,
+        "/decorator/toggle" => RemoteCommand::Toggle,
+        "/decorator/seek" => RemoteCommand::SeekPosition {
+            position: needs_number()?.clamp(0.0, 1.0),
+        },
+        "/decorator/fps" => RemoteCommand::Fps {
+            fps: needs_number()?.round().max(0.0) as u32,
+        },
+        // Indexes the `--color-mode` names, so scripts can use either
+        "/decorator/color" => {
+            let index = needs_number()?.round();
+            let mode = COLOR_MODES
+                .get(index.max(0.0) as usize)
+                .ok_or_else(|| format!("Color mode {} out of range 0-{}", index, COLOR_MODES.len() - 1))?;
+            RemoteCommand::ColorMode { mode: mode.to_string() }
+        }
+        "/decorator/open" => match args.first() {
+            Some(OscArg::Str(path)) => RemoteCommand::Open { path: path.clone() },
+            _ => return Err(format!("{} needs a path", address)),
+        },
+        _ => return Err(format!("Unknown OSC address: {}", address)),
+    };
+    Ok(Some(command))
+}
+
+fn resolve(address: &str) -> Result<SocketAddr, String> {
+    address
+        .to_socket_addrs()
+        .map_err(|e| format!("Invalid OSC output address {}: {}", address, e))?
+        .next()
+        .ok_or_else(|| format!("Invalid OSC output address: {}", address))
+}
+
+struct Running {
+    settings: OscSettings,
+    /// Set to make the listener and output threads finish
+    stop: Arc<AtomicBool>,
+    listener: thread::JoinHandle<()>,
+    output: Option<thread::JoinHandle<()>>,
+}
+
+impl Running {
+    /// Returns once the port is free again, so it can be bound right away.
+    fn stop(self) {
+        self.stop.store(true, Ordering::Relaxed);
+        let _ = self.listener.join();
+        if let Some(output) = self.output {
+            let _ = output.join();
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct OscState {
+    running: Mutex<Option<Running>>,
+}
+
+impl OscState {
+    /// Whether frames are being sent out, so the viewer has to report them.
+    pub fn sends_output(&self) -> bool {
+        self.running.lock().unwrap().as_ref().is_some_and(|running| !running.settings.output.trim().is_empty())
+    }
+}
+
+/// Start, restart or stop the listener and output so they match `settings`.
+pub fn apply(app: &AppHandle, settings: &OscSettings) -> Result<(), String> {
+    let state = app.state::<OscState>();
+    {
+        let mut running = state.running.lock().unwrap();
+        let unchanged = match running.as_ref() {
+            Some(running) => settings.enabled && running.settings == *settings,
+            None => !settings.enabled,
+        };
+        if unchanged {
+            return Ok(());
+        }
+        if let Some(previous) = running.take() {
+            previous.stop();
+        }
+        if settings.enabled {
+            *running = Some(start(app, settings)?);
+        }
+    }
+    remote::notify_status(app);
+    Ok(())
+}
+
+fn start(app: &AppHandle, settings: &OscSettings) -> Result<Running, String> {
+    let host = if settings.allow_lan { Ipv4Addr::UNSPECIFIED } else { Ipv4Addr::LOCALHOST };
+    let socket = UdpSocket::bind((host, settings.port))
+        .map_err(|e| format!("Failed to listen for OSC on port {}: {}", settings.port, e))?;
+    socket
+        .set_read_timeout(Some(POLL))
+        .map_err(|e| format!("Failed to set up OSC: {}", e))?;
+    // Output goes out from a port of its own, so stopping never waits on it
+    let output = match settings.output.trim() {
+        "" => None,
+        address => {
+            let target = resolve(address)?;
+            let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).map_err(|e| format!("Failed to set up OSC output: {}", e))?;
+            Some((socket, target))
+        }
+    };
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let output = output.map(|(output, target)| {
+        let app = app.clone();
+        let stop = stop.clone();
+        thread::spawn(move || send_frames(app, output, target, stop))
+    });
+    let listener = {
+        let app = app.clone();
+        let stop = stop.clone();
+        thread::spawn(move || listen(app, socket, stop))
+    };
+
+    Ok(Running {
+        settings: settings.clone(),
+        stop,
+        listener,
+        output,
+    })
+}
+
+fn listen(app: AppHandle, socket: UdpSocket, stop: Arc<AtomicBool>) {
+    let mut buffer = [0u8; 65536];
+    while !stop.load(Ordering::Relaxed) {
+        let len = match socket.recv(&mut buffer) {
+            Ok(len) => len,
+            // Timed out: time to check the stop flag
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => continue,
+            Err(e) => {
+                eprintln!("OSC listener stopped: {}", e);
+                break;
+            }
+        };
+        let mut messages = Vec::new();
+        let parsed = parse_packet(&buffer[..len], &mut messages);
+        for (address, args) in messages {
+            match command(&address, &args) {
+                Ok(Some(command)) => {
+                    if let Err(e) = remote::dispatch(&app, command) {
+                        eprintln!("OSC {}: {}", address, e);
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => eprintln!("{}", e),
+            }
+        }
+        if let Err(e) = parsed {
+            eprintln!("Ignoring OSC packet: {}", e);
+        }
+    }
+}
+
+/// Send `/decorator/frame <index>` whenever the frame changes, until stopped.
+fn send_frames(app: AppHandle, socket: UdpSocket, target: SocketAddr, stop: Arc<AtomicBool>) {
+    let mut updates = app.state::<RemoteState>().subscribe();
+    let mut last_frame = None;
+    while !stop.load(Ordering::Relaxed) {
+        // Waits at most one poll, so a stop is noticed even when playback is idle
+        let received = tauri::async_runtime::block_on(tokio::time::timeout(POLL, updates.recv()));
+        let playback = match received {
+            Ok(Ok(playback)) => playback,
+            Err(_) | Ok(Err(RecvError::Lagged(_))) => continue,
+            Ok(Err(RecvError::Closed)) => break,
+        };
+        if last_frame == Some(playback.frame) || stop.load(Ordering::Relaxed) {
+            continue;
+        }
+        last_frame = Some(playback.frame);
+        let packet = encode_int_message(FRAME_ADDRESS, playback.frame.min(i32::MAX as usize) as i32);
+        if let Err(e) = socket.send_to(&packet, target) {
+            eprintln!("Failed to send OSC frame: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(data: &[u8]) -> Result<Vec<(String, Vec<OscArg>)>, String> {
+        let mut messages = Vec::new();
+        parse_packet(data, &mut messages)?;
+        Ok(messages)
+    }
+
+    #[test]
+    fn reads_what_we_send() {
+        let packet = encode_int_message(FRAME_ADDRESS, 42);
+        assert_eq!(parse(&packet).unwrap(), vec![(FRAME_ADDRESS.to_string(), vec![OscArg::Int(42)])]);
+    }
+
+    #[test]
+    fn reads_mixed_arguments() {
+        let mut packet = Vec::new();
+        push_string(&mut packet, "/decorator/open");
+        push_string(&mut packet, ",sfTb");
+        push_string(&mut packet, "/art/take");
+        packet.extend_from_slice(&0.5f32.to_be_bytes());
+        packet.extend_from_slice(&3i32.to_be_bytes());
+        packet.extend_from_slice(&[1, 2, 3, 0]);
+        let args = vec![
+            OscArg::Str("/art/take".to_string()),
+            OscArg::Float(0.5),
+            OscArg::Bool(true),
+            OscArg::Other,
+        ];
+        assert_eq!(parse(&packet).unwrap(), vec![("/decorator/open".to_string(), args)]);
+    }
+
+    #[test]
+    fn accepts_missing_type_tags() {
+        let mut packet = Vec::new();
+        push_string(&mut packet, "/decorator/play");
+        assert_eq!(parse(&packet).unwrap(), vec![("/decorator/play".to_string(), Vec::new())]);
+    }
+
+    #[test]
+    fn flattens_bundles_in_order() {
+        let first = encode_int_message("/decorator/fps", 30);
+        let second = encode_int_message("/decorator/color", 2);
+        let mut inner = Vec::new();
+        push_string(&mut inner, "#bundle");
+        inner.extend_from_slice(&[0; 8]);
+        inner.extend_from_slice(&(second.len() as i32).to_be_bytes());
+        inner.extend_from_slice(&second);
+        let mut packet = Vec::new();
+        push_string(&mut packet, "#bundle");
+        packet.extend_from_slice(&[0; 8]);
+        for element in [&first, &inner] {
+            packet.extend_from_slice(&(element.len() as i32).to_be_bytes());
+            packet.extend_from_slice(element);
+        }
+        let messages = parse(&packet).unwrap();
+        let addresses: Vec<&str> = messages.iter().map(|(address, _)| address.as_str()).collect();
+        assert_eq!(addresses, ["/decorator/fps", "/decorator/color"]);
+    }
+
+    #[test]
+    fn rejects_broken_packets() {
+        let packet = encode_int_message("/decorator/seek", 1);
+        assert!(parse(&packet[..packet.len() - 2]).is_err());
+        assert!(parse(b"/decorator/play").is_err());
+        let mut unknown = Vec::new();
+        push_string(&mut unknown, "/decorator/play");
+        push_string(&mut unknown, ",x");
+        assert!(parse(&unknown).is_err());
+        let mut bundle = Vec::new();
+        push_string(&mut bundle, "#bundle");
+        bundle.extend_from_slice(&[0; 8]);
+        bundle.extend_from_slice(&64i32.to_be_bytes());
+        assert!(parse(&bundle).is_err());
+    }
+}
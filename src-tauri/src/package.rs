@@ -0,0 +1,76 @@
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// A whole project zipped into one file, so it can be passed around and double-clicked.
+pub const EXTENSION: &str = "decorator";
+
+pub fn is_package(path: &Path) -> bool {
+    path.is_file() && path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case(EXTENSION))
+}
+
+/// Unpack a project package into the temp directory and return the project folder in it.
+/// A package that was unpacked before, unchanged since, is reused.
+pub fn unpack(package: &Path) -> Result<PathBuf, String> {
+    let meta = fs::metadata(package).map_err(|e| format!("Failed to open {}: {}", package.display(), e))?;
+    let modified = meta
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|since| since.as_secs())
+        .unwrap_or_default();
+    let stem = package.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+    let cache = std::env::temp_dir().join("decorator-packages");
+    let target = cache.join(format!("{}-{}-{}", stem, meta.len(), modified));
+    if !target.is_dir() {
+        // Unpacked next to the target first, so a half-written project is never opened
+        let partial = cache.join(format!(".{}-{}-{}.partial", stem, meta.len(), modified));
+        let _ = fs::remove_dir_all(&partial);
+        fs::create_dir_all(&partial).map_err(|e| format!("Failed to create {}: {}", partial.display(), e))?;
+        let extracted = extract(package, &partial).and_then(|()| {
+            fs::rename(&partial, &target).map_err(|e| format!("Failed to unpack {}: {}", package.display(), e))
+        });
+        if let Err(e) = extracted {
+            let _ = fs::remove_dir_all(&partial);
+            return Err(e);
+        }
+    }
+    Ok(project_root(target))
+}
+
+fn extract(package: &Path, into: &Path) -> Result<(), String> {
+    let file = File::open(package).map_err(|e| format!("Failed to open {}: {}", package.display(), e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Failed to read package {}: {}", package.display(), e))?;
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read package {}: {}", package.display(), e))?;
+        // Entries naming paths outside the package are skipped rather than trusted
+        let Some(name) = entry.enclosed_name() else {
+            continue;
+        };
+        let path = into.join(name);
+        if entry.is_dir() {
+            fs::create_dir_all(&path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let mut out = File::create(&path).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        std::io::copy(&mut entry, &mut out).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+/// Packages made by zipping a folder hold just that folder; the project is inside it.
+fn project_root(dir: PathBuf) -> PathBuf {
+    let entries: Vec<PathBuf> = fs::read_dir(&dir)
+        .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_default();
+    match entries.as_slice() {
+        [only] if only.is_dir() => only.clone(),
+        _ => dir,
+    }
+}
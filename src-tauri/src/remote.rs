@@ -0,0 +1,403 @@
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr, TcpListener};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State as Shared};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use tauri::{AppHandle, Emitter, Manager, State, WebviewWindow};
+use tokio::sync::{broadcast, watch};
+
+use crate::cli::{self, LaunchOptions};
+use crate::osc::OscState;
+use crate::rpc::RpcState;
+use crate::sync::SyncState;
+use crate::settings::RemoteSettings;
+use crate::single_instance::OPEN_EVENT;
+
+/// Sent to the controlled window, which applies it to its viewer.
+const COMMAND_EVENT: &str = "remote-command";
+/// Sent to every window when the server starts or stops.
+const STATUS_EVENT: &str = "remote-status";
+/// How long a stopping server gets to close its connections before the port is reused anyway.
+const STOP_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// What clients can ask for, as `{"command": "seek", "frame": 10}`.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(tag = "command", rename_all = "camelCase")]
+pub enum RemoteCommand {
+    Play,
+    Pause,
+    Toggle,
+    /// Zero-based frame index; pauses playback like the slider does
+    Seek { frame: usize },
+    /// Frames forward (or back, when negative) from the current one; pauses too
+    Step { frames: i64 },
+    /// 0.0 to 1.0 along the timeline, as the slider positions it
+    SeekPosition { position: f64 },
+    /// Milliseconds into the timeline; pauses too
+    SeekTime { ms: f64 },
+    Fps { fps: u32 },
+    /// A `--color-mode` name
+    ColorMode { mode: String },
+    /// 0.0 to 1.0
+    Volume { volume: f64 },
+    /// Frames folder or frame file, resolved like a command-line path
+    Open { path: String },
+}
+
+/// What the controlled viewer is showing, as reported by the viewer itself.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaybackState {
+    pub directory_path: String,
+    pub playing: bool,
+    pub frame: usize,
+    pub frame_count: usize,
+    pub fps: u32,
+    /// When the shown frame starts on the timeline, which held frames stretch
+    pub position_ms: f64,
+    pub duration_ms: f64,
+    pub color_mode: String,
+    pub volume: f64,
+    pub muted: bool,
+}
+
+#[derive(serde::Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteStatus {
+    pub running: bool,
+    pub address: Option<String>,
+    /// Something wants the playback state, so the viewer should report it
+    pub reporting: bool,
+    /// How leading or following is going, when this instance takes part in sync
+    pub sync: Option<String>,
+}
+
+struct Server {
+    settings: RemoteSettings,
+    address: SocketAddr,
+    /// Set to stop serving and close open WebSocket sessions
+    shutdown: watch::Sender<bool>,
+    task: tauri::async_runtime::JoinHandle<()>,
+}
+
+impl Server {
+    /// Returns once the port is free again, so it can be bound right away.
+    fn stop(self) {
+        let _ = self.shutdown.send(true);
+        let task = self.task;
+        tauri::async_runtime::block_on(async move {
+            let _ = tokio::time::timeout(STOP_TIMEOUT, task).await;
+        });
+    }
+}
+
+pub struct RemoteState {
+    server: Mutex<Option<Server>>,
+    playback: Mutex<PlaybackState>,
+    events: broadcast::Sender<PlaybackState>,
+}
+
+impl Default for RemoteState {
+    fn default() -> Self {
+        RemoteState {
+            server: Mutex::new(None),
+            playback: Mutex::new(PlaybackState::default()),
+            events: broadcast::channel(64).0,
+        }
+    }
+}
+
+impl RemoteState {
+    /// What the viewer last reported.
+    pub fn playback(&self) -> PlaybackState {
+        self.playback.lock().unwrap().clone()
+    }
+
+    /// Playback state updates, as reported by the viewer.
+    pub fn subscribe(&self) -> broadcast::Receiver<PlaybackState> {
+        self.events.subscribe()
+    }
+}
+
+fn status(app: &AppHandle) -> RemoteStatus {
+    let server = app.state::<RemoteState>();
+    let server = server.server.lock().unwrap();
+    let sync = app.state::<SyncState>();
+    RemoteStatus {
+        running: server.is_some(),
+        address: server.as_ref().map(|server| server.address.to_string()),
+        reporting: server.is_some()
+            || app.state::<OscState>().sends_output()
+            || sync.is_leading()
+            || app.state::<RpcState>().is_running()
+            || media_controls(app),
+        sync: sync.status(),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn media_controls(app: &AppHandle) -> bool {
+    app.state::<crate::mpris::MprisState>().is_running()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn media_controls(_app: &AppHandle) -> bool {
+    false
+}
+
+/// Tell every window what is listening now.
+pub fn notify_status(app: &AppHandle) {
+    let _ = app.emit(STATUS_EVENT, status(app));
+}
+
+/// A random token for new remote settings.
+pub fn generate_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect()
+}
+
+/// Start, restart or stop the HTTP server so it matches `settings`.
+pub fn apply(app: &AppHandle, settings: &RemoteSettings) -> Result<(), String> {
+    let state = app.state::<RemoteState>();
+    {
+        let mut server = state.server.lock().unwrap();
+        let unchanged = match server.as_ref() {
+            Some(running) => settings.enabled && running.settings == *settings,
+            None => !settings.enabled,
+        };
+        if unchanged {
+            return Ok(());
+        }
+        if let Some(running) = server.take() {
+            running.stop();
+        }
+        if settings.enabled {
+            *server = Some(start(app, settings)?);
+        }
+    }
+    notify_status(app);
+    Ok(())
+}
+
+fn start(app: &AppHandle, settings: &RemoteSettings) -> Result<Server, String> {
+    if settings.token.is_empty() {
+        return Err("The remote control needs a token".to_string());
+    }
+    let host = if settings.allow_lan { Ipv4Addr::UNSPECIFIED } else { Ipv4Addr::LOCALHOST };
+    // Bound here so a port in use is reported to whoever turned the server on
+    let listener = TcpListener::bind((host, settings.port))
+        .map_err(|e| format!("Failed to listen on port {}: {}", settings.port, e))?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| format!("Failed to set up remote server: {}", e))?;
+    let address = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to set up remote server: {}", e))?;
+
+    let (shutdown, stopped) = watch::channel(false);
+    let router = Router::new()
+        .route("/api/state", get(get_state))
+        .route("/api/command", post(post_command))
+        .route("/api/events", get(events))
+        .with_state(Client {
+            app: app.clone(),
+            token: settings.token.clone(),
+            stopped: stopped.clone(),
+        });
+    let task = tauri::async_runtime::spawn(async move {
+        let mut stopped = stopped;
+        let served = match tokio::net::TcpListener::from_std(listener) {
+            Ok(listener) => axum::serve(listener, router)
+                .with_graceful_shutdown(async move {
+                    let _ = stopped.wait_for(|&stop| stop).await;
+                })
+                .await
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        if let Err(e) = served {
+            eprintln!("Remote control server stopped: {}", e);
+        }
+    });
+
+    Ok(Server {
+        settings: settings.clone(),
+        address,
+        shutdown,
+        task,
+    })
+}
+
+/// Hand a command to the controlled window.
+pub fn dispatch(app: &AppHandle, command: RemoteCommand) -> Result<(), String> {
+    let window = crate::windows::primary(app).ok_or("No viewer window is open")?;
+    match command {
+        RemoteCommand::Fps { fps: 0 } => Err("fps must be at least 1".to_string()),
+        RemoteCommand::ColorMode { ref mode } if !cli::COLOR_MODES.contains(&mode.as_str()) => {
+            Err(format!("Unknown color mode: {}", mode))
+        }
+        RemoteCommand::Open { path } => {
+            // Relative paths have nothing sensible to be relative to but our own directory
+            let cwd = std::env::current_dir().unwrap_or_default();
+            let launch = LaunchOptions {
+                directory_path: Some(cli::project_path(&path, Path::new(&cwd))?),
+                ..LaunchOptions::default()
+            };
+            send(&window, OPEN_EVENT, &launch)
+        }
+        command => send(&window, COMMAND_EVENT, &command),
+    }
+}
+
+fn send(window: &WebviewWindow, event: &str, payload: &impl serde::Serialize) -> Result<(), String> {
+    window
+        .emit_to(window.label(), event, payload)
+        .map_err(|e| format!("Failed to send command: {}", e))
+}
+
+#[derive(Clone)]
+struct Client {
+    app: AppHandle,
+    token: String,
+    /// Turns true when the server stops; sessions end with it
+    stopped: watch::Receiver<bool>,
+}
+
+impl Client {
+    /// The token goes in an `Authorization: Bearer` header, or in `?token=` where
+    /// headers can't be set, as with browser WebSockets.
+    fn authorized(&self, headers: &HeaderMap, query: &HashMap<String, String>) -> bool {
+        let bearer = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        bearer
+            .or(query.get("token").map(String::as_str))
+            .is_some_and(|given| same_token(given, &self.token))
+    }
+
+    fn playback(&self) -> PlaybackState {
+        self.app.state::<RemoteState>().playback()
+    }
+}
+
+/// Compares every byte whatever the first difference, so timing doesn't give the token away.
+fn same_token(given: &str, token: &str) -> bool {
+    given.len() == token.len() && given.bytes().zip(token.bytes()).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+fn unauthorized() -> Response {
+    (StatusCode::UNAUTHORIZED, "Missing or wrong token").into_response()
+}
+
+async fn get_state(
+    Shared(client): Shared<Client>,
+    headers: HeaderMap,
+    Query(query): Query<HashMap<String, String>>,
+) -> Response {
+    if !client.authorized(&headers, &query) {
+        return unauthorized();
+    }
+    Json(client.playback()).into_response()
+}
+
+async fn post_command(
+    Shared(client): Shared<Client>,
+    headers: HeaderMap,
+    Query(query): Query<HashMap<String, String>>,
+    Json(command): Json<RemoteCommand>,
+) -> Response {
+    if !client.authorized(&headers, &query) {
+        return unauthorized();
+    }
+    match dispatch(&client.app, command) {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, e).into_response(),
+    }
+}
+
+async fn events(
+    Shared(client): Shared<Client>,
+    headers: HeaderMap,
+    Query(query): Query<HashMap<String, String>>,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    if !client.authorized(&headers, &query) {
+        return unauthorized();
+    }
+    upgrade.on_upgrade(move |socket| stream_events(client, socket))
+}
+
+/// Send the playback state now and on every change; commands can come back the same way.
+/// The session ends when the server stops, so a disabled server or old token can't linger.
+async fn stream_events(client: Client, mut socket: WebSocket) {
+    let mut updates = client.app.state::<RemoteState>().subscribe();
+    let mut stopped = client.stopped.clone();
+    let mut pending = Some(client.playback());
+    while !*stopped.borrow() {
+        if let Some(state) = pending.take() {
+            let Ok(json) = serde_json::to_string(&state) else {
+                continue;
+            };
+            if socket.send(Message::Text(json.into())).await.is_err() {
+                break;
+            }
+        }
+        tokio::select! {
+            _ = stopped.changed() => break,
+            update = updates.recv() => match update {
+                Ok(state) => pending = Some(state),
+                // Fell behind: the latest state is all that matters
+                Err(broadcast::error::RecvError::Lagged(_)) => pending = Some(client.playback()),
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    let result = serde_json::from_str::<RemoteCommand>(&text)
+                        .map_err(|e| format!("Invalid command: {}", e))
+                        .and_then(|command| dispatch(&client.app, command));
+                    if let Err(e) = result {
+                        let reply = serde_json::json!({ "error": e }).to_string();
+                        if socket.send(Message::Text(reply.into())).await.is_err() {
+                            break;
+                        }
+                    }
+                }
+                Some(Ok(_)) => {}
+                Some(Err(_)) | None => break,
+            },
+        }
+    }
+}
+
+/// Called by the viewer whenever what it shows changes, while the server runs.
+#[tauri::command]
+pub fn report_playback_state(window: WebviewWindow, state: State<'_, RemoteState>, playback: PlaybackState) {
+    // Only the window commands go to speaks for the app
+    let is_primary = crate::windows::primary(window.app_handle()).is_some_and(|primary| primary.label() == window.label());
+    if !is_primary {
+        return;
+    }
+    let mut current = state.playback.lock().unwrap();
+    if *current != playback {
+        *current = playback.clone();
+        let _ = state.events.send(playback);
+    }
+}
+
+#[tauri::command]
+pub fn get_remote_status(app: AppHandle) -> RemoteStatus {
+    status(&app)
+}
@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::remote::{self, RemoteCommand, RemoteState};
+
+/// Sent to the primary window, which renders the frame and calls `finish_rpc_export`.
+const EXPORT_EVENT: &str = "rpc-export";
+/// How long an export may take before the request fails.
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(30);
+/// `export` formats, the viewer's copy formats.
+const EXPORT_FORMATS: [&str; 4] = ["text", "ansi", "html", "png"];
+const PNG_PREFIX: &str = "data:image/png;base64,";
+
+// JSON-RPC 2.0 error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// Valid requests that couldn't be carried out
+const FAILED: i64 = -32000;
+
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        RpcError {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+fn failed(message: String) -> RpcError {
+    RpcError::new(FAILED, message)
+}
+
+#[derive(serde::Deserialize)]
+struct OpenParams {
+    path: String,
+}
+
+#[derive(serde::Deserialize)]
+struct SeekParams {
+    /// Zero-based frame index
+    frame: Option<usize>,
+    /// 0.0 to 1.0 along the timeline
+    position: Option<f64>,
+}
+
+#[derive(serde::Deserialize)]
+struct ExportParams {
+    format: String,
+    /// The current frame when left out
+    frame: Option<usize>,
+    /// Write the export here instead of returning it
+    path: Option<String>,
+}
+
+/// The exported frame index and data, or why the export failed.
+type ExportResult = Result<(usize, String), String>;
+
+#[derive(Default)]
+pub struct RpcState {
+    running: AtomicBool,
+    next_export: AtomicU64,
+    /// Exports waiting for the viewer, by request id
+    exports: Mutex<HashMap<u64, mpsc::Sender<ExportResult>>>,
+}
+
+impl RpcState {
+    /// Whether stdin is being read, so the viewer has to report its state.
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+}
+
+/// One message per line; holding the lock keeps responses and notifications apart.
+fn write_line(message: &Value) {
+    let mut stdout = std::io::stdout().lock();
+    let _ = writeln!(stdout, "{}", message);
+    let _ = stdout.flush();
+}
+
+/// Answer requests from stdin and announce state changes on stdout, for `--rpc-stdio`.
+/// Closing stdin quits, so decorator goes away with the process that spawned it.
+pub fn start(app: &AppHandle) {
+    app.state::<RpcState>().running.store(true, Ordering::Relaxed);
+    remote::notify_status(app);
+
+    {
+        let app = app.clone();
+        thread::spawn(move || {
+            let mut updates = app.state::<RemoteState>().subscribe();
+            loop {
+                let playback = match updates.blocking_recv() {
+                    Ok(playback) => playback,
+                    // Fell behind: the latest state is all that matters
+                    Err(RecvError::Lagged(_)) => app.state::<RemoteState>().playback(),
+                    Err(RecvError::Closed) => break,
+                };
+                write_line(&json!({ "jsonrpc": "2.0", "method": "state", "params": playback }));
+            }
+        });
+    }
+
+    let app = app.clone();
+    thread::spawn(move || {
+        for line in std::io::stdin().lock().lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(response) = handle_line(&app, &line) {
+                write_line(&response);
+            }
+        }
+        app.exit(0);
+    });
+}
+
+fn response(id: Value, result: Result<Value, RpcError>) -> Value {
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(e) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": e.code, "message": e.message },
+        }),
+    }
+}
+
+/// The response to one line, or `None` for a notification (a request without an id).
+fn handle_line(app: &AppHandle, line: &str) -> Option<Value> {
+    let request: Value = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(e) => return Some(response(Value::Null, Err(RpcError::new(PARSE_ERROR, format!("Parse error: {}", e))))),
+    };
+    let id = request.get("id").cloned();
+    let Some(method) = request.get("method").and_then(Value::as_str) else {
+        let error = RpcError::new(INVALID_REQUEST, "Expected a request object with a method");
+        return Some(response(id.unwrap_or(Value::Null), Err(error)));
+    };
+    let result = call(app, method, request.get("params").cloned().unwrap_or(Value::Null));
+    id.map(|id| response(id, result))
+}
+
+/// Named params; methods without any may leave them out.
+fn params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    let params = if params.is_null() { json!({}) } else { params };
+    serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, format!("Invalid params: {}", e)))
+}
+
+fn send(app: &AppHandle, command: RemoteCommand) -> Result<Value, RpcError> {
+    remote::dispatch(app, command).map(|()| Value::Null).map_err(failed)
+}
+
+fn call(app: &AppHandle, method: &str, params_value: Value) -> Result<Value, RpcError> {
+    match method {
+        "open" => {
+            let OpenParams { path } = params(params_value)?;
+            send(app, RemoteCommand::Open { path })
+        }
+        "play" => send(app, RemoteCommand::Play),
+        "pause" => send(app, RemoteCommand::Pause),
+        "seek" => {
+            let command = match params(params_value)? {
+                SeekParams { frame: Some(frame), position: None } => RemoteCommand::Seek { frame },
+                SeekParams { frame: None, position: Some(position) } => RemoteCommand::SeekPosition {
+                    position: position.clamp(0.0, 1.0),
+                },
+                _ => return Err(RpcError::new(INVALID_PARAMS, "seek takes either frame or position")),
+            };
+            send(app, command)
+        }
+        "export" => export(app, params(params_value)?),
+        "get_state" => serde_json::to_value(app.state::<RemoteState>().playback())
+            .map_err(|e| failed(format!("Failed to read state: {}", e))),
+        _ => Err(RpcError::new(METHOD_NOT_FOUND, format!("Unknown method: {}", method))),
+    }
+}
+
+/// Have the viewer render a frame, then return it or write it to `path`.
+fn export(app: &AppHandle, params: ExportParams) -> Result<Value, RpcError> {
+    let format = params.format.to_lowercase();
+    if !EXPORT_FORMATS.contains(&format.as_str()) {
+        let message = format!("Unknown export format: {} (text, ansi, html or png)", params.format);
+        return Err(RpcError::new(INVALID_PARAMS, message));
+    }
+    let window = crate::windows::primary(app).ok_or_else(|| failed("No viewer window is open".to_string()))?;
+    let state = app.state::<RpcState>();
+    let id = state.next_export.fetch_add(1, Ordering::Relaxed);
+    let (sender, receiver) = mpsc::channel();
+    state.exports.lock().unwrap().insert(id, sender);
+    let request = json!({ "id": id, "format": format, "frame": params.frame });
+    let result = match window.emit_to(window.label(), EXPORT_EVENT, request) {
+        Ok(()) => receiver
+            .recv_timeout(EXPORT_TIMEOUT)
+            .unwrap_or_else(|_| Err("The viewer didn't finish the export".to_string())),
+        Err(e) => Err(format!("Failed to request export: {}", e)),
+    };
+    state.exports.lock().unwrap().remove(&id);
+    let (frame, data) = result.map_err(failed)?;
+
+    // PNGs come back as a data URL; callers get the bare base64, or the file
+    let data = match format.as_str() {
+        "png" => data.strip_prefix(PNG_PREFIX).map(str::to_string).unwrap_or(data),
+        _ => data,
+    };
+    let Some(path) = params.path else {
+        return Ok(json!({ "format": format, "frame": frame, "data": data }));
+    };
+    let bytes = match format.as_str() {
+        "png" => STANDARD
+            .decode(&data)
+            .map_err(|e| failed(format!("Invalid PNG data: {}", e)))?,
+        _ => data.into_bytes(),
+    };
+    std::fs::write(&path, bytes).map_err(|e| failed(format!("Failed to write {}: {}", path, e)))?;
+    Ok(json!({ "format": format, "frame": frame, "path": path }))
+}
+
+/// The viewer's answer to an `rpc-export` event: the exported frame, or why it failed.
+#[tauri::command]
+pub fn finish_rpc_export(
+    state: State<'_, RpcState>,
+    id: u64,
+    frame: usize,
+    data: Option<String>,
+    error: Option<String>,
+) {
+    if let Some(sender) = state.exports.lock().unwrap().remove(&id) {
+        let result = data
+            .map(|data| (frame, data))
+            .ok_or_else(|| error.unwrap_or_else(|| "Export failed".to_string()));
+        let _ = sender.send(result);
+    }
+}
@@ -0,0 +1,190 @@
+use std::fs;
+use std::path::PathBuf;
+
+use tauri::{AppHandle, Manager};
+
+use crate::{osc, remote, sync};
+
+/// What a second launch does with the path it forwards to the running app.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum OpenBehavior {
+    /// Show it in place of the current project
+    #[default]
+    Replace,
+    /// Open it once the current project is closed
+    Enqueue,
+    /// Open it in a new viewer window
+    Window,
+}
+
+/// When the slideshow moves on to the next project.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(tag = "kind", content = "count", rename_all = "lowercase")]
+pub enum SlideAdvance {
+    /// After the animation played this many times
+    Loops(u32),
+    Seconds(u32),
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SlideshowSettings {
+    pub shuffle: bool,
+    pub advance: SlideAdvance,
+    pub crossfade_ms: u32,
+}
+
+impl Default for SlideshowSettings {
+    fn default() -> Self {
+        SlideshowSettings {
+            shuffle: false,
+            advance: SlideAdvance::Loops(2),
+            crossfade_ms: 1000,
+        }
+    }
+}
+
+/// The remote control server; off unless turned on in the settings.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RemoteSettings {
+    pub enabled: bool,
+    pub port: u16,
+    /// Listen on all interfaces instead of localhost only
+    pub allow_lan: bool,
+    /// Required from clients; generated when empty
+    pub token: String,
+}
+
+impl Default for RemoteSettings {
+    fn default() -> Self {
+        RemoteSettings {
+            enabled: false,
+            port: 7645,
+            allow_lan: false,
+            token: String::new(),
+        }
+    }
+}
+
+/// OSC input for VJ tools, and optionally frame index output.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OscSettings {
+    pub enabled: bool,
+    /// UDP port OSC messages are received on
+    pub port: u16,
+    /// Listen on all interfaces instead of localhost only
+    pub allow_lan: bool,
+    /// `host:port` the current frame index is sent to; empty for none
+    pub output: String,
+}
+
+impl Default for OscSettings {
+    fn default() -> Self {
+        OscSettings {
+            enabled: false,
+            port: 9000,
+            allow_lan: false,
+            output: String::new(),
+        }
+    }
+}
+
+/// This instance's part in synchronized multi-screen playback.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncRole {
+    #[default]
+    Off,
+    /// Sends its playback to followers
+    Leader,
+    /// Plays in step with a leader
+    Follower,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SyncSettings {
+    pub role: SyncRole,
+    /// TCP port a leader accepts followers on
+    pub port: u16,
+    /// `host:port` of the leader, for followers
+    pub leader: String,
+    /// Added to a follower's position, for displays that lag behind
+    pub latency_ms: i32,
+}
+
+impl Default for SyncSettings {
+    fn default() -> Self {
+        SyncSettings {
+            role: SyncRole::Off,
+            port: 7646,
+            leader: String::new(),
+            latency_ms: 0,
+        }
+    }
+}
+
+/// App-wide preferences, as opposed to the per-project details.toml.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppSettings {
+    pub open_behavior: OpenBehavior,
+    pub slideshow: SlideshowSettings,
+    pub remote: RemoteSettings,
+    pub osc: OscSettings,
+    pub sync: SyncSettings,
+}
+
+fn settings_file(app: &AppHandle) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join("settings.json"))
+}
+
+/// Saved settings, or the defaults when there are none yet.
+pub fn load(app: &AppHandle) -> AppSettings {
+    settings_file(app)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub fn get_app_settings(app: AppHandle) -> AppSettings {
+    load(&app)
+}
+
+/// Save the settings and apply the ones that take effect right away. Returns them
+/// as saved, since a remote token may have been generated.
+///
+/// Every service is applied even if an earlier one fails; the errors are reported together.
+/// Restarting waits for the old servers to let go of their ports, so it runs off the main thread.
+#[tauri::command]
+pub async fn set_app_settings(app: AppHandle, mut settings: AppSettings) -> Result<AppSettings, String> {
+    if settings.remote.token.is_empty() {
+        settings.remote.token = remote::generate_token();
+    }
+    let path = settings_file(&app).ok_or("Failed to resolve config directory")?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(&settings).map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    fs::write(&path, json).map_err(|e| format!("Failed to save settings: {}", e))?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let errors: Vec<String> = [
+            remote::apply(&app, &settings.remote),
+            osc::apply(&app, &settings.osc),
+            sync::apply(&app, &settings.sync),
+        ]
+        .into_iter()
+        .filter_map(Result::err)
+        .collect();
+        if !errors.is_empty() {
+            return Err(errors.join("; "));
+        }
+        Ok(settings)
+    })
+    .await
+    .map_err(|e| format!("Failed to apply settings: {}", e))?
+}
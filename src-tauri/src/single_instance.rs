@@ -0,0 +1,53 @@
+use std::path::Path;
+
+use tauri::{AppHandle, Emitter, WebviewWindow};
+
+use crate::cli::{self, Command};
+use crate::settings::{self, OpenBehavior};
+
+/// Events telling a window to open a forwarded path now, or after its current project.
+pub const OPEN_EVENT: &str = "open-path";
+const ENQUEUE_EVENT: &str = "enqueue-path";
+
+fn focus(window: &WebviewWindow) {
+    let _ = window.unminimize();
+    let _ = window.show();
+    let _ = window.set_focus();
+}
+
+/// Called in the running app when decorator is launched again: the new process exits
+/// and its arguments end up here, to be handled as the app settings say.
+pub fn forward(app: &AppHandle, argv: Vec<String>, cwd: String) {
+    let launch = match cli::parse(argv.into_iter().skip(1), Path::new(&cwd)) {
+        Ok(Command::Run(launch)) => launch,
+        Ok(Command::Exit(_)) => return,
+        Err(e) => {
+            eprintln!("Ignoring forwarded launch: {}", e);
+            return;
+        }
+    };
+    let Some(window) = crate::windows::primary(app) else {
+        return;
+    };
+    if launch.directory_path.is_none() && launch.stream.is_none() {
+        focus(&window);
+        return;
+    }
+
+    let result = match settings::load(app).open_behavior {
+        OpenBehavior::Window => crate::windows::open(app, &launch).map(|window| focus(&window)),
+        behavior => {
+            let event = if behavior == OpenBehavior::Enqueue { ENQUEUE_EVENT } else { OPEN_EVENT };
+            if launch.fullscreen {
+                let _ = window.set_fullscreen(true);
+            }
+            focus(&window);
+            window
+                .emit_to(window.label(), event, &launch)
+                .map_err(|e| format!("Failed to forward launch: {}", e))
+        }
+    };
+    if let Err(e) = result {
+        eprintln!("{}", e);
+    }
+}
@@ -0,0 +1,300 @@
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::net::{Ipv4Addr, SocketAddr, ToSocketAddrs};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Manager, State, WebviewWindow};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::sync::watch;
+
+use crate::ColorData;
+
+/// Sent to the window that started the stream, once per frame received.
+const FRAME_EVENT: &str = "stream-frame";
+/// Sent to that window when a sender connects or goes away.
+const STATUS_EVENT: &str = "stream-status";
+/// Anything bigger is a sender speaking some other protocol.
+const MAX_SECTION_BYTES: u32 = 16 * 1024 * 1024;
+/// Pause before reopening a FIFO whose writer went away.
+const PIPE_REOPEN: Duration = Duration::from_millis(250);
+/// How long a stopping stream gets to let go of its port or path before it is reused anyway.
+const STOP_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Where frames come from, as `tcp:7650`, `unix:/tmp/decorator.sock` or `pipe:/tmp/decorator`.
+#[derive(Clone, Debug, PartialEq)]
+enum Source {
+    Tcp(SocketAddr),
+    Unix(String),
+    /// A FIFO on Unix, `\\.\pipe\<name>` on Windows
+    Pipe(String),
+}
+
+impl Source {
+    fn parse(source: &str) -> Result<Self, String> {
+        let source = source.trim();
+        match source.split_once(':') {
+            Some(("tcp", address)) => {
+                // A bare port stays on this machine
+                let address = match address.parse::<u16>() {
+                    Ok(port) => SocketAddr::from((Ipv4Addr::LOCALHOST, port)),
+                    Err(_) => address
+                        .to_socket_addrs()
+                        .ok()
+                        .and_then(|mut addresses| addresses.next())
+                        .ok_or_else(|| format!("Invalid stream address: {}", address))?,
+                };
+                Ok(Source::Tcp(address))
+            }
+            Some(("unix", path)) if !path.is_empty() => Ok(Source::Unix(path.to_string())),
+            Some(("pipe", path)) if !path.is_empty() => Ok(Source::Pipe(path.to_string())),
+            _ => Err(format!(
+                "Unknown stream source: {} (use tcp:PORT, unix:PATH or pipe:PATH)",
+                source
+            )),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Source::Tcp(address) => format!("tcp {}", address),
+            Source::Unix(path) => format!("socket {}", path),
+            Source::Pipe(path) => format!("pipe {}", path),
+        }
+    }
+}
+
+/// One frame as the viewer gets it; `received_at` is in `Date.now()` milliseconds.
+#[derive(serde::Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+struct StreamFrame {
+    text: String,
+    colors: Option<ColorData>,
+    received_at: f64,
+}
+
+struct Running {
+    stop: watch::Sender<bool>,
+    task: tauri::async_runtime::JoinHandle<()>,
+}
+
+impl Running {
+    /// Returns once the listener is dropped, so the same source can be bound right away.
+    fn stop(self) {
+        let _ = self.stop.send(true);
+        let task = self.task;
+        tauri::async_runtime::block_on(async move {
+            let _ = tokio::time::timeout(STOP_TIMEOUT, task).await;
+        });
+    }
+}
+
+/// Streams by the label of the window showing them.
+#[derive(Default)]
+pub struct StreamState {
+    running: Mutex<HashMap<String, Running>>,
+}
+
+fn set_status(window: &WebviewWindow, status: String) {
+    let _ = window.emit_to(window.label(), STATUS_EVENT, status);
+}
+
+/// One length-prefixed section: a u32 LE byte count, then the bytes.
+async fn read_section<R: AsyncRead + Unpin>(reader: &mut R) -> std::io::Result<Vec<u8>> {
+    let len = reader.read_u32_le().await?;
+    if len > MAX_SECTION_BYTES {
+        let message = format!("Frame section of {} bytes is too large", len);
+        return Err(std::io::Error::new(ErrorKind::InvalidData, message));
+    }
+    let mut data = vec![0; len as usize];
+    reader.read_exact(&mut data).await?;
+    Ok(data)
+}
+
+/// Pass frames on to the window until the sender goes away, returning how many
+/// there were. Each frame is the text section followed by a colors section in
+/// `.colors` layout, empty for none.
+async fn receive<R: AsyncRead + Unpin>(window: &WebviewWindow, mut reader: R, sender: &str) -> std::io::Result<usize> {
+    let mut frames = 0;
+    loop {
+        let text = match read_section(&mut reader).await {
+            Ok(text) => text,
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(frames),
+            Err(e) => return Err(e),
+        };
+        let colors = read_section(&mut reader).await?;
+        let colors = if colors.is_empty() {
+            None
+        } else {
+            crate::parse_colors_data(&colors)
+                .map_err(|e| eprintln!("Ignoring colors of streamed frame: {}", e))
+                .ok()
+        };
+        let frame = StreamFrame {
+            text: String::from_utf8_lossy(&text).to_string(),
+            colors,
+            received_at: crate::sync::now_ms(),
+        };
+        if frames == 0 {
+            set_status(window, format!("Receiving from {}", sender));
+        }
+        frames += 1;
+        let _ = window.emit_to(window.label(), FRAME_EVENT, frame);
+    }
+}
+
+/// Bound up front, so a port or path in use is reported to whoever started the stream.
+enum Listener {
+    Tcp(std::net::TcpListener),
+    #[cfg(unix)]
+    Unix(std::os::unix::net::UnixListener),
+    Pipe,
+}
+
+impl Listener {
+    fn bind(source: &Source) -> Result<Self, String> {
+        match source {
+            Source::Tcp(address) => {
+                let listener = std::net::TcpListener::bind(address)
+                    .map_err(|e| format!("Failed to listen on {}: {}", address, e))?;
+                listener
+                    .set_nonblocking(true)
+                    .map_err(|e| format!("Failed to set up stream: {}", e))?;
+                Ok(Listener::Tcp(listener))
+            }
+            #[cfg(unix)]
+            Source::Unix(path) => {
+                use std::os::unix::fs::FileTypeExt;
+                // A socket left behind by an earlier run would make binding fail
+                if std::fs::metadata(path).is_ok_and(|meta| meta.file_type().is_socket()) {
+                    let _ = std::fs::remove_file(path);
+                }
+                let listener = std::os::unix::net::UnixListener::bind(path)
+                    .map_err(|e| format!("Failed to listen on {}: {}", path, e))?;
+                listener
+                    .set_nonblocking(true)
+                    .map_err(|e| format!("Failed to set up stream: {}", e))?;
+                Ok(Listener::Unix(listener))
+            }
+            #[cfg(not(unix))]
+            Source::Unix(_) => Err("Unix sockets aren't available here; use tcp: or pipe:".to_string()),
+            #[cfg(unix)]
+            Source::Pipe(path) => {
+                use std::os::unix::fs::FileTypeExt;
+                let is_fifo = std::fs::metadata(path).is_ok_and(|meta| meta.file_type().is_fifo());
+                if !is_fifo {
+                    return Err(format!("{} is not a named pipe; create it with mkfifo", path));
+                }
+                Ok(Listener::Pipe)
+            }
+            #[cfg(windows)]
+            Source::Pipe(_) => Ok(Listener::Pipe),
+        }
+    }
+}
+
+/// Take one sender at a time, for as long as the stream runs.
+async fn serve(window: WebviewWindow, source: Source, listener: Listener) -> std::io::Result<()> {
+    match listener {
+        Listener::Tcp(listener) => {
+            let listener = tokio::net::TcpListener::from_std(listener)?;
+            loop {
+                let (stream, address) = listener.accept().await?;
+                let sender = address.to_string();
+                finish_session(&window, &source, receive(&window, stream, &sender).await);
+            }
+        }
+        #[cfg(unix)]
+        Listener::Unix(listener) => {
+            let listener = tokio::net::UnixListener::from_std(listener)?;
+            loop {
+                let (stream, _) = listener.accept().await?;
+                finish_session(&window, &source, receive(&window, stream, &source.describe()).await);
+            }
+        }
+        Listener::Pipe => {
+            let Source::Pipe(path) = &source else {
+                return Ok(());
+            };
+            loop {
+                let reader = open_pipe(path).await?;
+                finish_session(&window, &source, receive(&window, reader, &source.describe()).await);
+                tokio::time::sleep(PIPE_REOPEN).await;
+            }
+        }
+    }
+}
+
+/// Reading side of a FIFO. On Linux it is also opened for writing, so it never
+/// reads as closed between senders.
+#[cfg(unix)]
+async fn open_pipe(path: &str) -> std::io::Result<tokio::net::unix::pipe::Receiver> {
+    let mut options = tokio::net::unix::pipe::OpenOptions::new();
+    #[cfg(target_os = "linux")]
+    options.read_write(true);
+    options.open_receiver(path)
+}
+
+/// A named pipe server instance, once a sender has connected to it.
+#[cfg(windows)]
+async fn open_pipe(name: &str) -> std::io::Result<tokio::net::windows::named_pipe::NamedPipeServer> {
+    let name = if name.starts_with(r"\\") {
+        name.to_string()
+    } else {
+        format!(r"\\.\pipe\{}", name)
+    };
+    let server = tokio::net::windows::named_pipe::ServerOptions::new().create(name)?;
+    server.connect().await?;
+    Ok(server)
+}
+
+fn finish_session(window: &WebviewWindow, source: &Source, result: std::io::Result<usize>) {
+    let waiting = format!("Waiting for frames on {}", source.describe());
+    match result {
+        // Nobody was there, as with a FIFO that has no writer yet
+        Ok(0) => {}
+        Ok(_) => set_status(window, waiting),
+        Err(e) => set_status(window, format!("{} (sender dropped: {})", waiting, e)),
+    }
+}
+
+/// Start receiving frames for the calling window, replacing its previous stream.
+/// Returns what the viewer should show until the first frame arrives.
+#[tauri::command]
+pub fn start_stream(window: WebviewWindow, state: State<'_, StreamState>, source: String) -> Result<String, String> {
+    let mut running = state.running.lock().unwrap();
+    if let Some(previous) = running.remove(window.label()) {
+        previous.stop();
+    }
+    let source = Source::parse(&source)?;
+    let listener = Listener::bind(&source)?;
+    let (stop, mut stopped) = watch::channel(false);
+    let label = window.label().to_string();
+
+    let waiting = format!("Waiting for frames on {}", source.describe());
+    let task = tauri::async_runtime::spawn(async move {
+        tokio::select! {
+            result = serve(window.clone(), source, listener) => {
+                if let Err(e) = result {
+                    set_status(&window, format!("Stream stopped: {}", e));
+                }
+            }
+            _ = stopped.changed() => {}
+        }
+    });
+    running.insert(label, Running { stop, task });
+    Ok(waiting)
+}
+
+/// Stop the stream of the window labelled `label`, if it has one.
+pub fn stop(app: &AppHandle, label: &str) {
+    let running = app.state::<StreamState>().running.lock().unwrap().remove(label);
+    if let Some(running) = running {
+        running.stop();
+    }
+}
+
+#[tauri::command]
+pub fn stop_stream(window: WebviewWindow) {
+    stop(window.app_handle(), window.label());
+}
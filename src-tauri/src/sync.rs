@@ -0,0 +1,370 @@
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, watch};
+
+use crate::remote::{self, RemoteState};
+use crate::settings::{SyncRole, SyncSettings};
+
+/// Sent to the primary window of a follower with the leader's state in local time.
+const STATE_EVENT: &str = "sync-state";
+/// The leader repeats its state this often, for followers that just connected or lost a packet.
+const HEARTBEAT: Duration = Duration::from_millis(500);
+/// Followers measure their clock offset to the leader this often.
+const PING_INTERVAL: Duration = Duration::from_secs(2);
+/// Offset samples kept; the one with the shortest round trip is the most accurate.
+const PING_SAMPLES: usize = 8;
+const RECONNECT: Duration = Duration::from_secs(1);
+/// How long a stopping leader or follower gets to wind down before its port is reused anyway.
+const STOP_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Milliseconds since the Unix epoch, the clock the viewer's `Date.now()` uses too.
+pub fn now_ms() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64() * 1000.0)
+        .unwrap_or_default()
+}
+
+/// One line of newline-delimited JSON between leader and follower.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum SyncMessage {
+    /// The leader's playback: `frame` has been showing since `frame_started_at`, leader time
+    #[serde(rename_all = "camelCase")]
+    State {
+        playing: bool,
+        frame: usize,
+        frame_started_at: f64,
+    },
+    Ping { sent: f64 },
+    /// `time` is the leader's clock when it answered the ping sent at `sent`
+    Pong { sent: f64, time: f64 },
+}
+
+impl SyncMessage {
+    fn line(&self) -> String {
+        let mut line = serde_json::to_string(self).unwrap_or_default();
+        line.push('\n');
+        line
+    }
+}
+
+/// The leader's state translated to this machine's clock, for the viewer.
+#[derive(serde::Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+struct FollowState {
+    playing: bool,
+    frame: usize,
+    frame_started_at: f64,
+}
+
+struct Running {
+    settings: SyncSettings,
+    stop: watch::Sender<bool>,
+    task: tauri::async_runtime::JoinHandle<()>,
+}
+
+impl Running {
+    /// Returns once the leader's port is free again, so it can be bound right away.
+    fn stop(self) {
+        let _ = self.stop.send(true);
+        let task = self.task;
+        tauri::async_runtime::block_on(async move {
+            let _ = tokio::time::timeout(STOP_TIMEOUT, task).await;
+        });
+    }
+}
+
+#[derive(Default)]
+pub struct SyncState {
+    running: Mutex<Option<Running>>,
+    /// Shown in the settings panel, e.g. "2 followers"
+    status: Mutex<Option<String>>,
+    /// Given with `--sync-*`; used instead of the saved settings for as long as this runs
+    cli_override: Option<SyncSettings>,
+}
+
+impl SyncState {
+    pub fn new(cli_override: Option<SyncSettings>) -> Self {
+        Self {
+            cli_override,
+            ..Default::default()
+        }
+    }
+
+    pub fn is_leading(&self) -> bool {
+        self.running
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|running| running.settings.role == SyncRole::Leader)
+    }
+
+    pub fn status(&self) -> Option<String> {
+        self.status.lock().unwrap().clone()
+    }
+}
+
+fn followers_status(count: usize) -> String {
+    match count {
+        0 => "No followers".to_string(),
+        1 => "1 follower".to_string(),
+        count => format!("{} followers", count),
+    }
+}
+
+fn set_status(app: &AppHandle, status: Option<String>) {
+    *app.state::<SyncState>().status.lock().unwrap() = status;
+    remote::notify_status(app);
+}
+
+/// Start, restart or stop leading or following so it matches `settings`, or the
+/// command-line override when there is one.
+pub fn apply(app: &AppHandle, settings: &SyncSettings) -> Result<(), String> {
+    let state = app.state::<SyncState>();
+    let settings = state.cli_override.as_ref().unwrap_or(settings);
+    let result = {
+        let mut running = state.running.lock().unwrap();
+        let unchanged = match running.as_ref() {
+            Some(running) => running.settings == *settings,
+            None => settings.role == SyncRole::Off,
+        };
+        if unchanged {
+            return Ok(());
+        }
+        if let Some(previous) = running.take() {
+            previous.stop();
+        }
+        let started = start(app, settings);
+        // Set before the tasks get to update it; a failed start shows why
+        *state.status.lock().unwrap() = match (&started, settings.role) {
+            (Err(e), _) => Some(e.clone()),
+            (Ok(_), SyncRole::Off) => None,
+            (Ok(_), SyncRole::Leader) => Some(followers_status(0)),
+            (Ok(_), SyncRole::Follower) => Some(format!("Connecting to {}", settings.leader.trim())),
+        };
+        started.map(|started| *running = started)
+    };
+    remote::notify_status(app);
+    result
+}
+
+fn start(app: &AppHandle, settings: &SyncSettings) -> Result<Option<Running>, String> {
+    let (stop, stopped) = watch::channel(false);
+    let task = match settings.role {
+        SyncRole::Off => return Ok(None),
+        SyncRole::Leader => {
+            // Followers are on other machines, so this listens on every interface
+            let listener = std::net::TcpListener::bind((Ipv4Addr::UNSPECIFIED, settings.port))
+                .map_err(|e| format!("Failed to listen for followers on port {}: {}", settings.port, e))?;
+            listener
+                .set_nonblocking(true)
+                .map_err(|e| format!("Failed to set up sync: {}", e))?;
+            tauri::async_runtime::spawn(lead(app.clone(), listener, stopped))
+        }
+        SyncRole::Follower => {
+            if settings.leader.trim().is_empty() {
+                return Err("Following needs the leader's host:port".to_string());
+            }
+            tauri::async_runtime::spawn(follow(app.clone(), settings.clone(), stopped))
+        }
+    };
+    Ok(Some(Running {
+        settings: settings.clone(),
+        stop,
+        task,
+    }))
+}
+
+/// Accept followers and send each of them the playback state.
+async fn lead(app: AppHandle, listener: std::net::TcpListener, mut stopped: watch::Receiver<bool>) {
+    let listener = match TcpListener::from_std(listener) {
+        Ok(listener) => listener,
+        Err(e) => {
+            set_status(&app, Some(format!("Failed to lead: {}", e)));
+            return;
+        }
+    };
+    let (lines, _) = broadcast::channel::<String>(16);
+    let latest = Arc::new(Mutex::new(None::<String>));
+    let followers = Arc::new(AtomicUsize::new(0));
+
+    // Stamp frame changes as they are reported and pass them on
+    {
+        let app = app.clone();
+        let lines = lines.clone();
+        let latest = latest.clone();
+        let mut stopped = stopped.clone();
+        tauri::async_runtime::spawn(async move {
+            let mut updates = app.state::<RemoteState>().subscribe();
+            let mut current: Option<SyncMessage> = None;
+            loop {
+                tokio::select! {
+                    update = updates.recv() => {
+                        let playback = match update {
+                            Ok(playback) => playback,
+                            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                            Err(broadcast::error::RecvError::Closed) => break,
+                        };
+                        let frame_started_at = match current {
+                            Some(SyncMessage::State { frame, frame_started_at, .. }) if frame == playback.frame => frame_started_at,
+                            _ => now_ms(),
+                        };
+                        let message = SyncMessage::State { playing: playback.playing, frame: playback.frame, frame_started_at };
+                        if current.as_ref() == Some(&message) {
+                            continue;
+                        }
+                        current = Some(message.clone());
+                    }
+                    _ = tokio::time::sleep(HEARTBEAT) => {}
+                    _ = stopped.changed() => break,
+                }
+                if let Some(message) = &current {
+                    let line = message.line();
+                    *latest.lock().unwrap() = Some(line.clone());
+                    let _ = lines.send(line);
+                }
+            }
+        });
+    }
+
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let Ok((stream, address)) = accepted else {
+                    continue;
+                };
+                let app = app.clone();
+                let lines = lines.subscribe();
+                let first = latest.lock().unwrap().clone();
+                let stopped = stopped.clone();
+                let followers = followers.clone();
+                let still_leading = stopped.clone();
+                tauri::async_runtime::spawn(async move {
+                    let count = followers.fetch_add(1, Ordering::Relaxed) + 1;
+                    set_status(&app, Some(followers_status(count)));
+                    if let Err(e) = serve_follower(stream, lines, first, stopped).await {
+                        eprintln!("Follower {} disconnected: {}", address, e);
+                    }
+                    let count = followers.fetch_sub(1, Ordering::Relaxed) - 1;
+                    // Once stopped, the status belongs to whatever replaced us
+                    if !*still_leading.borrow() {
+                        set_status(&app, Some(followers_status(count)));
+                    }
+                });
+            }
+            _ = stopped.changed() => break,
+        }
+    }
+}
+
+async fn serve_follower(
+    stream: TcpStream,
+    mut lines: broadcast::Receiver<String>,
+    first: Option<String>,
+    mut stopped: watch::Receiver<bool>,
+) -> std::io::Result<()> {
+    stream.set_nodelay(true)?;
+    let (read, mut write) = stream.into_split();
+    let mut incoming = BufReader::new(read).lines();
+    if let Some(line) = first {
+        write.write_all(line.as_bytes()).await?;
+    }
+    loop {
+        tokio::select! {
+            line = lines.recv() => match line {
+                Ok(line) => write.write_all(line.as_bytes()).await?,
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => return Ok(()),
+            },
+            line = incoming.next_line() => match line? {
+                Some(line) => {
+                    if let Ok(SyncMessage::Ping { sent }) = serde_json::from_str(&line) {
+                        let pong = SyncMessage::Pong { sent, time: now_ms() };
+                        write.write_all(pong.line().as_bytes()).await?;
+                    }
+                }
+                None => return Ok(()),
+            },
+            _ = stopped.changed() => return Ok(()),
+        }
+    }
+}
+
+/// Keep a connection to the leader, reconnecting when it drops.
+async fn follow(app: AppHandle, settings: SyncSettings, mut stopped: watch::Receiver<bool>) {
+    let leader = settings.leader.trim().to_string();
+    loop {
+        let session = async {
+            let stream = TcpStream::connect(&leader).await?;
+            let address = stream.peer_addr()?;
+            set_status(&app, Some(format!("Following {}", address)));
+            follow_session(&app, stream, address, settings.latency_ms).await
+        };
+        tokio::select! {
+            result = session => {
+                let reason = result.err().map(|e| e.to_string()).unwrap_or_else(|| "disconnected".to_string());
+                set_status(&app, Some(format!("Waiting for leader ({})", reason)));
+            }
+            _ = stopped.changed() => return,
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(RECONNECT) => {}
+            _ = stopped.changed() => return,
+        }
+    }
+}
+
+async fn follow_session(app: &AppHandle, stream: TcpStream, leader: SocketAddr, latency_ms: i32) -> std::io::Result<()> {
+    stream.set_nodelay(true)?;
+    let (read, mut write) = stream.into_split();
+    let mut incoming = BufReader::new(read).lines();
+    // (round trip, leader clock minus ours) per ping
+    let mut samples: Vec<(f64, f64)> = Vec::new();
+    let mut ping = tokio::time::interval(PING_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = ping.tick() => {
+                write.write_all(SyncMessage::Ping { sent: now_ms() }.line().as_bytes()).await?;
+            }
+            line = incoming.next_line() => {
+                let Some(line) = line? else {
+                    return Ok(());
+                };
+                match serde_json::from_str::<SyncMessage>(&line) {
+                    Ok(SyncMessage::Pong { sent, time }) => {
+                        let received = now_ms();
+                        samples.push((received - sent, time - (sent + received) / 2.0));
+                        if samples.len() > PING_SAMPLES {
+                            samples.remove(0);
+                        }
+                    }
+                    Ok(SyncMessage::State { playing, frame, frame_started_at }) => {
+                        let offset = samples
+                            .iter()
+                            .min_by(|a, b| a.0.total_cmp(&b.0))
+                            .map(|sample| sample.1)
+                            .unwrap_or_default();
+                        // A positive latency shows frames earlier, for displays that lag
+                        let state = FollowState {
+                            playing,
+                            frame,
+                            frame_started_at: frame_started_at - offset - latency_ms as f64,
+                        };
+                        if let Some(window) = crate::windows::primary(app) {
+                            let _ = window.emit_to(window.label(), STATE_EVENT, state);
+                        }
+                    }
+                    Ok(SyncMessage::Ping { .. }) => {}
+                    Err(e) => eprintln!("Ignoring message from leader {}: {}", leader, e),
+                }
+            }
+        }
+    }
+}
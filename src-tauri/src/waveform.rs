@@ -0,0 +1,143 @@
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+use tauri::Manager;
+
+/// Envelope resolution: one peak/RMS pair per this many milliseconds of audio.
+const BUCKET_MS: u32 = 20;
+
+/// Downsampled amplitude envelope of an audio file, values in 0.0..=1.0.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct Waveform {
+    pub bucket_ms: u32,
+    pub peaks: Vec<f32>,
+    pub rms: Vec<f32>,
+}
+
+/// Cache file name derived from the audio path, size and modification time,
+/// so a replaced soundtrack is decoded again.
+fn cache_key(path: &Path) -> Result<String, String> {
+    let meta = fs::metadata(path).map_err(|e| format!("Failed to read audio file: {}", e))?;
+    let mut hasher = DefaultHasher::new();
+    path.hash(&mut hasher);
+    meta.len().hash(&mut hasher);
+    if let Ok(modified) = meta.modified() {
+        modified.hash(&mut hasher);
+    }
+    Ok(format!("{:016x}.json", hasher.finish()))
+}
+
+fn decode_waveform(path: &Path) -> Result<Waveform, String> {
+    let file = fs::File::open(path).map_err(|e| format!("Failed to open audio file: {}", e))?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(ext);
+    }
+
+    let probed = symphonia::default::get_probe()
+        .format(&hint, stream, &FormatOptions::default(), &MetadataOptions::default())
+        .map_err(|e| format!("Unsupported audio file: {}", e))?;
+    let mut format = probed.format;
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or("Audio file has no audio track")?;
+    let track_id = track.id;
+    let sample_rate = track.codec_params.sample_rate.unwrap_or(44_100);
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| format!("Unsupported audio codec: {}", e))?;
+
+    let bucket_frames = (sample_rate as usize * BUCKET_MS as usize / 1000).max(1);
+    let mut peaks = Vec::new();
+    let mut rms = Vec::new();
+    let (mut peak, mut sum_sq, mut count) = (0f32, 0f64, 0usize);
+    let mut samples: Option<SampleBuffer<f32>> = None;
+
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(format!("Failed to read audio: {}", e)),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // A corrupt packet only costs a few milliseconds of envelope
+            Err(SymphoniaError::DecodeError(_)) => continue,
+            Err(e) => return Err(format!("Failed to decode audio: {}", e)),
+        };
+
+        let spec = *decoded.spec();
+        let channels = spec.channels.count().max(1);
+        let buffer = samples.get_or_insert_with(|| SampleBuffer::new(decoded.capacity() as u64, spec));
+        buffer.copy_interleaved_ref(decoded);
+
+        // Mix channels down by taking the loudest sample of each frame
+        for frame in buffer.samples().chunks(channels) {
+            let amplitude = frame.iter().fold(0f32, |max, s| max.max(s.abs()));
+            peak = peak.max(amplitude);
+            sum_sq += (amplitude as f64) * (amplitude as f64);
+            count += 1;
+            if count == bucket_frames {
+                peaks.push(peak.min(1.0));
+                rms.push(((sum_sq / count as f64).sqrt() as f32).min(1.0));
+                (peak, sum_sq, count) = (0.0, 0.0, 0);
+            }
+        }
+    }
+    if count > 0 {
+        peaks.push(peak.min(1.0));
+        rms.push(((sum_sq / count as f64).sqrt() as f32).min(1.0));
+    }
+
+    Ok(Waveform {
+        bucket_ms: BUCKET_MS,
+        peaks,
+        rms,
+    })
+}
+
+/// Decode the audio file into a peak/RMS envelope for the progress bar.
+/// Results are cached in the app cache directory; decoding runs off the main thread.
+#[tauri::command]
+pub async fn get_audio_waveform(app: tauri::AppHandle, audio_path: String) -> Result<Waveform, String> {
+    let path = PathBuf::from(&audio_path);
+    let cache_dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| format!("Failed to resolve cache directory: {}", e))?
+        .join("waveforms");
+    let cache_path = cache_dir.join(cache_key(&path)?);
+
+    if let Ok(cached) = fs::read_to_string(&cache_path) {
+        if let Ok(waveform) = serde_json::from_str::<Waveform>(&cached) {
+            return Ok(waveform);
+        }
+    }
+
+    let waveform = tauri::async_runtime::spawn_blocking(move || decode_waveform(&path))
+        .await
+        .map_err(|e| format!("Waveform task failed: {}", e))??;
+
+    // The cache is only an optimization; failing to write it is not an error
+    if fs::create_dir_all(&cache_dir).is_ok() {
+        if let Ok(json) = serde_json::to_string(&waveform) {
+            let _ = fs::write(&cache_path, json);
+        }
+    }
+    Ok(waveform)
+}
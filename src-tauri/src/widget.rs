@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Emitter, Manager, PhysicalPosition, PhysicalSize, State, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+use tauri_plugin_global_shortcut::GlobalShortcutExt;
+
+use crate::cli::LaunchOptions;
+
+/// Global shortcut that turns every widget back into a normal window. It has to be
+/// global: a click-through widget never receives keyboard focus.
+pub const EXIT_SHORTCUT: &str = "CmdOrCtrl+Shift+D";
+
+/// Smallest widget edge, in physical pixels.
+const MIN_EDGE: u32 = 48;
+
+/// Event sent to a window when its widget closed and it is shown again.
+const EXITED_EVENT: &str = "widget-mode-exited";
+/// Widget windows are labelled after the window they were started from, e.g. `widget-main`.
+const LABEL_PREFIX: &str = "widget-";
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug)]
+struct Geometry {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum WidgetLayer {
+    Top,
+    Bottom,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WidgetOptions {
+    pub layer: WidgetLayer,
+    /// Let clicks pass through to whatever is below the widget
+    pub click_through: bool,
+}
+
+/// Open widget windows, each with the label of the viewer window hidden behind it.
+#[derive(Default)]
+pub struct WidgetState {
+    sources: Mutex<HashMap<String, String>>,
+}
+
+/// Label of the widget window opened from the viewer labelled `source`.
+pub fn label_for(source: &str) -> String {
+    format!("{}{}", LABEL_PREFIX, source)
+}
+
+fn geometry_of(window: &WebviewWindow) -> Result<Geometry, String> {
+    let position = window
+        .outer_position()
+        .map_err(|e| format!("Failed to read window position: {}", e))?;
+    let size = window
+        .inner_size()
+        .map_err(|e| format!("Failed to read window size: {}", e))?;
+    Ok(Geometry {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    })
+}
+
+fn apply_geometry(window: &WebviewWindow, geometry: Geometry) -> Result<(), String> {
+    window
+        .set_size(PhysicalSize::new(geometry.width, geometry.height))
+        .map_err(|e| format!("Failed to resize window: {}", e))?;
+    window
+        .set_position(PhysicalPosition::new(geometry.x, geometry.y))
+        .map_err(|e| format!("Failed to move window: {}", e))
+}
+
+fn geometry_file(app: &AppHandle) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join("widget.json"))
+}
+
+/// Where the last widget was, so the next one opens in the same spot.
+fn saved_geometry(app: &AppHandle) -> Option<Geometry> {
+    let content = fs::read_to_string(geometry_file(app)?).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn save_geometry(app: &AppHandle, geometry: Geometry) -> Result<(), String> {
+    let path = geometry_file(app).ok_or("Failed to resolve config directory")?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let json = serde_json::to_string(&geometry).map_err(|e| format!("Failed to serialize widget geometry: {}", e))?;
+    fs::write(&path, json).map_err(|e| format!("Failed to save widget geometry: {}", e))
+}
+
+/// Show the animation in a frameless, transparent widget window sized to its
+/// `aspect_ratio` (width / height), and hide the calling window until the widget
+/// closes. Only widget windows are transparent; a window can't become so later.
+/// Async because creating a window from a sync command deadlocks on Windows.
+#[tauri::command]
+pub async fn enter_widget_mode(
+    app: AppHandle,
+    window: WebviewWindow,
+    options: WidgetOptions,
+    aspect_ratio: f64,
+    launch: LaunchOptions,
+) -> Result<(), String> {
+    let label = label_for(window.label());
+    if let Some(widget) = app.get_webview_window(&label) {
+        let _ = widget.set_focus();
+        return Ok(());
+    }
+
+    let normal = geometry_of(&window)?;
+    let aspect_ratio = aspect_ratio.clamp(0.05, 20.0);
+    let base = saved_geometry(&app).unwrap_or(Geometry {
+        width: normal.width.min(480),
+        ..normal
+    });
+    let geometry = Geometry {
+        height: (base.width as f64 / aspect_ratio).round().max(1.0) as u32,
+        ..base
+    };
+
+    // The widget's viewer starts where this one was, already in widget mode
+    let launch = LaunchOptions {
+        widget: Some(options),
+        fullscreen: false,
+        slideshow: false,
+        ..launch
+    };
+    let script = serde_json::to_string(&launch).map_err(|e| format!("Failed to serialize launch options: {}", e))?;
+    let builder = WebviewWindowBuilder::new(&app, &label, WebviewUrl::App("index.html".into()))
+        .title(window.title().unwrap_or_default())
+        .visible(false)
+        .decorations(false)
+        .shadow(false)
+        .skip_taskbar(true)
+        .always_on_top(options.layer == WidgetLayer::Top)
+        .always_on_bottom(options.layer == WidgetLayer::Bottom)
+        .initialization_script(format!("window.__LAUNCH_OPTIONS__ = {};", script));
+    // Transparent windows need private API on macOS; without it the widget keeps a background
+    #[cfg(any(not(target_os = "macos"), feature = "macos-private-api"))]
+    let builder = builder.transparent(true);
+    let widget = builder.build().map_err(|e| format!("Failed to open widget: {}", e))?;
+
+    let shown = apply_geometry(&widget, geometry).and_then(|()| {
+        let show = || -> tauri::Result<()> {
+            widget.set_ignore_cursor_events(options.click_through)?;
+            widget.show()?;
+            window.hide()
+        };
+        show().map_err(|e| format!("Failed to show widget: {}", e))
+    });
+    if let Err(e) = shown {
+        let _ = widget.destroy();
+        return Err(e);
+    }
+    app.state::<WidgetState>()
+        .sources
+        .lock()
+        .unwrap()
+        .insert(label, window.label().to_string());
+
+    let shortcuts = app.global_shortcut();
+    if !shortcuts.is_registered(EXIT_SHORTCUT) {
+        shortcuts
+            .register(EXIT_SHORTCUT)
+            .map_err(|e| format!("Failed to register {}: {}", EXIT_SHORTCUT, e))?;
+    }
+    Ok(())
+}
+
+/// Grow (`factor > 1`) or shrink the widget, keeping its aspect ratio and center.
+#[tauri::command]
+pub fn resize_widget(window: WebviewWindow, factor: f64) -> Result<(), String> {
+    let current = geometry_of(&window)?;
+    let factor = factor.clamp(0.5, 2.0);
+    let width = (current.width as f64 * factor).round().max(MIN_EDGE as f64);
+    let height = (width * current.height as f64 / current.width.max(1) as f64).round().max(MIN_EDGE as f64);
+    apply_geometry(
+        &window,
+        Geometry {
+            x: current.x + ((current.width as f64 - width) / 2.0).round() as i32,
+            y: current.y + ((current.height as f64 - height) / 2.0).round() as i32,
+            width: width as u32,
+            height: height as u32,
+        },
+    )
+}
+
+/// Bring back the window a widget was started from, once the widget is gone.
+fn restore_source(app: &AppHandle, state: &WidgetState, label: &str) {
+    let (source, remaining) = {
+        let mut sources = state.sources.lock().unwrap();
+        (sources.remove(label), sources.len())
+    };
+    if remaining == 0 {
+        let _ = app.global_shortcut().unregister(EXIT_SHORTCUT);
+    }
+    if let Some(window) = source.and_then(|source| app.get_webview_window(&source)) {
+        let _ = window.show();
+        let _ = window.set_focus();
+        let _ = window.emit_to(window.label(), EXITED_EVENT, ());
+    }
+}
+
+/// Close a widget window, remembering where it was for the next one.
+fn close_widget(app: &AppHandle, widget: &WebviewWindow) -> Result<(), String> {
+    // Remembering the spot is a convenience; closing the widget matters more
+    if let Ok(geometry) = geometry_of(widget) {
+        if let Err(e) = save_geometry(app, geometry) {
+            eprintln!("{}", e);
+        }
+    }
+    // Its stream goes first, so the source window can take the same port back
+    crate::stream::stop(app, widget.label());
+    restore_source(app, &app.state::<WidgetState>(), widget.label());
+    widget.destroy().map_err(|e| format!("Failed to close widget: {}", e))
+}
+
+/// Called by a widget window to close itself and bring its viewer back.
+#[tauri::command]
+pub fn exit_widget_mode(app: AppHandle, window: WebviewWindow, state: State<'_, WidgetState>) -> Result<(), String> {
+    if !state.sources.lock().unwrap().contains_key(window.label()) {
+        return Ok(());
+    }
+    close_widget(&app, &window)
+}
+
+/// Handler for [`EXIT_SHORTCUT`]: every widget closes and its viewer comes back.
+pub fn exit_all(app: &AppHandle) {
+    let labels: Vec<String> = app.state::<WidgetState>().sources.lock().unwrap().keys().cloned().collect();
+    for label in labels {
+        if let Some(widget) = app.get_webview_window(&label) {
+            if let Err(e) = close_widget(app, &widget) {
+                eprintln!("{}", e);
+            }
+        }
+    }
+}
+
+/// A widget closed some other way, e.g. by the window manager.
+pub fn forget(app: &AppHandle, label: &str) {
+    restore_source(app, &app.state::<WidgetState>(), label);
+}
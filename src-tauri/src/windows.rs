@@ -0,0 +1,64 @@
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+
+use crate::cli::LaunchOptions;
+
+/// Extra viewer windows are labelled `viewer-1`, `viewer-2`, …; the default
+/// capability covers them with a `viewer-*` pattern.
+const LABEL_PREFIX: &str = "viewer-";
+
+static NEXT_WINDOW: AtomicUsize = AtomicUsize::new(1);
+
+/// The window that forwarded launches and the remote control talk to: the main
+/// window, or the oldest other viewer when it was closed. While that viewer is
+/// shown as a widget, the widget window stands in for it.
+pub fn primary(app: &AppHandle) -> Option<WebviewWindow> {
+    let mut windows = app.webview_windows();
+    let viewer = windows
+        .keys()
+        .filter(|label| *label == "main" || label.starts_with(LABEL_PREFIX))
+        .min_by_key(|label| (viewer_number(label), label.as_str()))
+        .or_else(|| windows.keys().min())
+        .cloned()?;
+    windows
+        .remove(&crate::widget::label_for(&viewer))
+        .or_else(|| windows.remove(&viewer))
+}
+
+/// Order viewers are opened in: `main` is 0, `viewer-N` is N.
+fn viewer_number(label: &str) -> usize {
+    label
+        .strip_prefix(LABEL_PREFIX)
+        .and_then(|number| number.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Open another viewer window that starts with `launch` applied.
+pub fn open(app: &AppHandle, launch: &LaunchOptions) -> Result<WebviewWindow, String> {
+    let label = format!("{}{}", LABEL_PREFIX, NEXT_WINDOW.fetch_add(1, Ordering::Relaxed));
+    let title = launch
+        .directory_path
+        .as_deref()
+        .and_then(|path| Path::new(path).file_name())
+        .map(|name| format!("Decorator — {}", name.to_string_lossy()))
+        .unwrap_or_else(|| "Decorator".to_string());
+    // The new App picks this up instead of waiting for a drop
+    let options = serde_json::to_string(launch).map_err(|e| format!("Failed to serialize launch options: {}", e))?;
+
+    WebviewWindowBuilder::new(app, label, WebviewUrl::App("index.html".into()))
+        .title(title)
+        .inner_size(900.0, 700.0)
+        .fullscreen(launch.fullscreen)
+        .initialization_script(format!("window.__LAUNCH_OPTIONS__ = {};", options))
+        .build()
+        .map_err(|e| format!("Failed to open window: {}", e))
+}
+
+/// Open another viewer window, with `directory_path` already loaded when given.
+/// Async because creating a window from a sync command deadlocks on Windows.
+#[tauri::command]
+pub async fn open_viewer_window(app: AppHandle, directory_path: Option<String>) -> Result<(), String> {
+    open(&app, &LaunchOptions { directory_path, ..LaunchOptions::default() }).map(|_| ())
+}
@@ -1,53 +1,107 @@
+use crate::app_settings::{AppSettings, SlideAdvance, SlideshowSettings};
 use crate::ascii_frames_viewer::AsciiFramesViewer;
+use crate::color_mode::ColorMode;
+use crate::frame_stream::DEFAULT_BUFFER_MS;
+use crate::ipc::tauri_try_invoke;
+use crate::slideshow::{self, Slideshow};
+use crate::widget::WidgetOptions;
+use serde::Deserialize;
+use serde_json::json;
+use std::cell::RefCell;
+use std::collections::VecDeque;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::closure::Closure;
 use yew::prelude::*;
 
 #[wasm_bindgen(inline_js = r#"
+// Holding Alt (Option) while dropping opens the project in a new window. Key events
+// only arrive while this window is focused, e.g. when dragging between viewers.
+let newWindowModifier = false;
+for (const type of ['keydown', 'keyup']) {
+    window.addEventListener(type, (e) => { newWindowModifier = e.altKey; });
+}
+window.addEventListener('blur', () => { newWindowModifier = false; });
+
+// Events are listened to on this window only, so each viewer window gets its own drops
+function currentWindow() {
+    return window.__TAURI__?.webviewWindow?.getCurrentWebviewWindow?.();
+}
+
+// Set by the backend for windows it opens with a project
+export function initialLaunch() {
+    return window.__LAUNCH_OPTIONS__ ?? null;
+}
+
 export async function setupDropListener(callback) {
     // Wait briefly for Tauri to be ready
-    let tauri = window.__TAURI__;
-    if (!tauri) {
+    let current = currentWindow();
+    if (!current) {
         await new Promise(r => setTimeout(r, 500));
-        tauri = window.__TAURI__;
+        current = currentWindow();
     }
-    if (!tauri || !tauri.event) {
-        console.error('Tauri event API not available');
+    if (!current) {
+        console.error('Tauri window API not available');
         return;
     }
 
     // Listen for our custom 'file-drop' event emitted from Rust backend
-    await tauri.event.listen('file-drop', (event) => {
-        callback(event.payload);
+    await current.listen('file-drop', (event) => {
+        callback(event.payload, newWindowModifier);
+    });
+}
+
+// Paths forwarded by a second launch of decorator
+export async function setupLaunchListener(openCallback, enqueueCallback) {
+    let current = currentWindow();
+    if (!current) {
+        await new Promise(r => setTimeout(r, 500));
+        current = currentWindow();
+    }
+    if (!current) {
+        return;
+    }
+
+    await current.listen('open-path', (event) => {
+        openCallback(event.payload);
+    });
+
+    await current.listen('enqueue-path', (event) => {
+        enqueueCallback(event.payload);
     });
 }
 
 export async function setupDragOverListener(enterCallback, leaveCallback) {
-    let tauri = window.__TAURI__;
-    if (!tauri) {
+    let current = currentWindow();
+    if (!current) {
         await new Promise(r => setTimeout(r, 500));
-        tauri = window.__TAURI__;
+        current = currentWindow();
     }
-    if (!tauri || !tauri.event) {
+    if (!current) {
         return;
     }
 
-    await tauri.event.listen('tauri://drag-enter', () => {
+    await current.listen('tauri://drag-enter', () => {
         enterCallback();
     });
 
-    await tauri.event.listen('tauri://drag-leave', () => {
+    await current.listen('tauri://drag-leave', () => {
         leaveCallback();
     });
 
-    await tauri.event.listen('tauri://drop', () => {
+    await current.listen('tauri://drop', () => {
         leaveCallback();
     });
 }
 "#)]
 extern "C" {
+    #[wasm_bindgen(js_name = initialLaunch)]
+    fn initial_launch() -> JsValue;
+
+    #[wasm_bindgen(js_name = setupLaunchListener)]
+    fn setup_launch_listener(open_callback: &Closure<dyn Fn(JsValue)>, enqueue_callback: &Closure<dyn Fn(JsValue)>);
+
     #[wasm_bindgen(js_name = setupDropListener)]
-    fn setup_drop_listener(callback: &Closure<dyn Fn(String)>);
+    fn setup_drop_listener(callback: &Closure<dyn Fn(String, bool)>);
 
     #[wasm_bindgen(js_name = setupDragOverListener)]
     fn setup_drag_over_listener(
@@ -56,15 +110,240 @@ extern "C" {
     );
 }
 
+/// Command-line options (`decorator ./frames --fps 30 ...`) of this or a later launch.
+#[derive(Deserialize, Clone, PartialEq, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct LaunchOptions {
+    directory_path: Option<String>,
+    fps: Option<u32>,
+    fullscreen: bool,
+    no_loop: bool,
+    play: bool,
+    color_mode: Option<String>,
+    start: Option<usize>,
+    slideshow: bool,
+    shuffle: Option<bool>,
+    loops: Option<u32>,
+    seconds: Option<u32>,
+    crossfade_ms: Option<u32>,
+    stream: Option<String>,
+    stream_buffer_ms: Option<u32>,
+    widget: Option<WidgetOptions>,
+}
+
+impl LaunchOptions {
+    fn from_js(value: JsValue) -> Option<Self> {
+        serde_wasm_bindgen::from_value::<Option<LaunchOptions>>(value).ok().flatten()
+    }
+
+    /// The saved slideshow settings with this launch's overrides applied.
+    async fn slideshow_settings(&self) -> SlideshowSettings {
+        let args = serde_wasm_bindgen::to_value(&json!({})).unwrap();
+        let saved = tauri_try_invoke("get_app_settings", args)
+            .await
+            .ok()
+            .and_then(|v| serde_wasm_bindgen::from_value::<AppSettings>(v).ok())
+            .unwrap_or_default()
+            .slideshow;
+        SlideshowSettings {
+            shuffle: self.shuffle.unwrap_or(saved.shuffle),
+            advance: match (self.loops, self.seconds) {
+                (Some(loops), _) => SlideAdvance::Loops(loops),
+                (_, Some(seconds)) => SlideAdvance::Seconds(seconds),
+                _ => saved.advance,
+            },
+            crossfade_ms: self.crossfade_ms.unwrap_or(saved.crossfade_ms),
+        }
+    }
+}
+
+/// Opens `directory_path` in another viewer window, e.g. to compare takes side by side.
+#[hook]
+pub fn use_open_viewer_window(directory_path: String) -> Callback<MouseEvent> {
+    use_callback(directory_path, |_: MouseEvent, directory_path| {
+        let directory_path = directory_path.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let _ = open_viewer_window(&directory_path).await;
+        });
+    })
+}
+
+async fn open_viewer_window(directory_path: &str) -> Result<JsValue, JsValue> {
+    let args = serde_wasm_bindgen::to_value(&json!({ "directoryPath": directory_path })).unwrap();
+    tauri_try_invoke("open_viewer_window", args).await
+}
+
+/// Ask before a viewer with unsaved frame edits is replaced; a yes forgets them.
+fn confirm_discard(unsaved: &RefCell<bool>) -> bool {
+    if !*unsaved.borrow() {
+        return true;
+    }
+    let confirmed = web_sys::window()
+        .and_then(|w| w.confirm_with_message("This opens another project. Discard unsaved frame edits?").ok())
+        .unwrap_or(true);
+    if confirmed {
+        *unsaved.borrow_mut() = false;
+    }
+    confirmed
+}
+
 #[function_component(App)]
 pub fn app() -> Html {
-    let directory_path = use_state(|| String::new());
+    // Windows opened for a project start with it loaded
+    let launch = use_state(|| LaunchOptions::from_js(initial_launch()).unwrap_or_default());
+    let directory_path = use_state(|| {
+        (!launch.slideshow)
+            .then(|| launch.directory_path.clone())
+            .flatten()
+            .unwrap_or_default()
+    });
+    // Live frame source shown in place of a project
+    let stream = use_state(|| (!launch.slideshow).then(|| launch.stream.clone()).flatten());
+    // Library folder and settings of a running slideshow
+    let slideshow = use_state(|| None::<(String, SlideshowSettings)>);
     let is_drag_over = use_state(|| false);
+    // Projects forwarded by later launches, opened one by one as the current one is cleared
+    let queue: std::rc::Rc<std::cell::RefCell<VecDeque<LaunchOptions>>> = use_mut_ref(VecDeque::new);
+    let queue_len = use_state(|| 0usize);
+    // Bumped to start a fresh viewer, so launch options that only apply at startup take effect
+    let session = use_state(|| 0u64);
+    let session_counter = use_mut_ref(|| 0u64);
+    // Whether the viewer has frame edits that opening something else would lose
+    let unsaved = use_mut_ref(|| false);
+
+    // Show a project with its launch options in a fresh viewer, or start a slideshow
+    let open_launch = {
+        let launch = launch.clone();
+        let directory_path = directory_path.clone();
+        let stream = stream.clone();
+        let slideshow = slideshow.clone();
+        let session = session.clone();
+        let session_counter = session_counter.clone();
+        let unsaved = unsaved.clone();
+        Callback::from(move |options: LaunchOptions| {
+            // A stream takes the place of any project
+            if let Some(source) = options.stream.clone() {
+                if !confirm_discard(&unsaved) {
+                    return;
+                }
+                slideshow.set(None);
+                *session_counter.borrow_mut() += 1;
+                session.set(*session_counter.borrow());
+                launch.set(options);
+                directory_path.set(String::new());
+                stream.set(Some(source));
+                return;
+            }
+            let Some(path) = options.directory_path.clone() else {
+                return;
+            };
+            if options.slideshow {
+                let slideshow = slideshow.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    let settings = options.slideshow_settings().await;
+                    slideshow.set(Some((path, settings)));
+                });
+                return;
+            }
+            if !confirm_discard(&unsaved) {
+                return;
+            }
+            slideshow.set(None);
+            *session_counter.borrow_mut() += 1;
+            session.set(*session_counter.borrow());
+            launch.set(options);
+            stream.set(None);
+            directory_path.set(path);
+        })
+    };
+
+    // The listeners below outlive this render, so they read the current project from here
+    let showing_ref = use_mut_ref(|| false);
+    {
+        let showing_ref = showing_ref.clone();
+        use_effect_with(((*directory_path).clone(), stream.is_some()), move |(path, streaming)| {
+            *showing_ref.borrow_mut() = !path.is_empty() || *streaming;
+            || ()
+        });
+    }
+
+    // Forwarded launches: open now, or queue behind whatever is showing
+    {
+        let open_launch = open_launch.clone();
+        let queue = queue.clone();
+        let queue_len = queue_len.clone();
+        use_effect_with((), move |_| {
+            let open_now = open_launch.clone();
+            let open_closure = Closure::wrap(Box::new(move |payload: JsValue| {
+                if let Some(options) = LaunchOptions::from_js(payload) {
+                    open_now.emit(options);
+                }
+            }) as Box<dyn Fn(JsValue)>);
+
+            let enqueue_closure = Closure::wrap(Box::new(move |payload: JsValue| {
+                let Some(options) = LaunchOptions::from_js(payload) else {
+                    return;
+                };
+                // Nothing showing yet: there is nothing to wait for
+                if *showing_ref.borrow() {
+                    queue.borrow_mut().push_back(options);
+                    queue_len.set(queue.borrow().len());
+                } else {
+                    open_launch.emit(options);
+                }
+            }) as Box<dyn Fn(JsValue)>);
+
+            setup_launch_listener(&open_closure, &enqueue_closure);
+
+            // Keep closures alive
+            open_closure.forget();
+            enqueue_closure.forget();
+
+            || ()
+        });
+    }
+
+    // Apply the command line before showing the project it names
+    {
+        let directory_path = directory_path.clone();
+        let stream = stream.clone();
+        let launch = launch.clone();
+        let open_launch = open_launch.clone();
+        use_effect_with((), move |_| {
+            // A window opened for a slideshow starts it here
+            if launch.slideshow {
+                open_launch.emit((*launch).clone());
+            }
+            wasm_bindgen_futures::spawn_local(async move {
+                let args = serde_wasm_bindgen::to_value(&json!({})).unwrap();
+                let options = tauri_try_invoke("get_launch_options", args)
+                    .await
+                    .ok()
+                    .and_then(|v| serde_wasm_bindgen::from_value::<Option<LaunchOptions>>(v).ok())
+                    .flatten();
+                if let Some(options) = options {
+                    if options.slideshow {
+                        open_launch.emit(options.clone());
+                    } else if options.stream.is_some() {
+                        stream.set(options.stream.clone());
+                    } else if let Some(path) = options.directory_path.clone() {
+                        directory_path.set(path);
+                    }
+                    launch.set(options);
+                }
+            });
+            || ()
+        });
+    }
 
     // Setup Tauri drag-drop listener
     {
         let directory_path = directory_path.clone();
         let is_drag_over = is_drag_over.clone();
+        let launch = launch.clone();
+        let stream = stream.clone();
+        let slideshow = slideshow.clone();
+        let unsaved = unsaved.clone();
 
         use_effect_with((), move |_| {
             let directory_path_clone = directory_path.clone();
@@ -72,10 +351,44 @@ pub fn app() -> Html {
             let is_drag_over_clone2 = is_drag_over.clone();
 
             // Drop handler
-            let drop_closure = Closure::wrap(Box::new(move |path: String| {
-                directory_path_clone.set(path);
+            let drop_closure = Closure::wrap(Box::new(move |path: String, new_window: bool| {
+                if new_window {
+                    // Opening it here beats losing the drop
+                    let directory_path = directory_path_clone.clone();
+                    wasm_bindgen_futures::spawn_local(async move {
+                        if open_viewer_window(&path).await.is_err() {
+                            directory_path.set(path);
+                        }
+                    });
+                } else {
+                    // A folder of projects plays as a slideshow
+                    let directory_path = directory_path_clone.clone();
+                    let launch = launch.clone();
+                    let stream = stream.clone();
+                    let slideshow = slideshow.clone();
+                    let unsaved = unsaved.clone();
+                    wasm_bindgen_futures::spawn_local(async move {
+                        let is_library = slideshow::scan(&path)
+                            .await
+                            .is_ok_and(|projects| projects.len() > 1 || projects.iter().any(|p| p.path != path));
+                        // A slideshow plays over the viewer, so only a project replaces it
+                        if !is_library && !confirm_discard(&unsaved) {
+                            return;
+                        }
+                        // Command-line options only apply to the project they named
+                        launch.set(LaunchOptions::default());
+                        stream.set(None);
+                        if is_library {
+                            let settings = LaunchOptions::default().slideshow_settings().await;
+                            slideshow.set(Some((path, settings)));
+                        } else {
+                            slideshow.set(None);
+                            directory_path.set(path);
+                        }
+                    });
+                }
                 is_drag_over_clone.set(false);
-            }) as Box<dyn Fn(String)>);
+            }) as Box<dyn Fn(String, bool)>);
 
             // Drag enter handler
             let enter_closure = Closure::wrap(Box::new(move || {
@@ -102,31 +415,72 @@ pub fn app() -> Html {
 
     let on_clear = {
         let directory_path = directory_path.clone();
+        let stream = stream.clone();
+        let queue = queue.clone();
+        let queue_len = queue_len.clone();
+        let open_launch = open_launch.clone();
+        let unsaved = unsaved.clone();
         Callback::from(move |_| {
-            directory_path.set(String::new());
+            // The viewer asked before clearing
+            *unsaved.borrow_mut() = false;
+            let next = queue.borrow_mut().pop_front();
+            queue_len.set(queue.borrow().len());
+            match next {
+                Some(options) => open_launch.emit(options),
+                None => {
+                    stream.set(None);
+                    directory_path.set(String::new());
+                }
+            }
         })
     };
 
+    let on_unsaved = {
+        let unsaved = unsaved.clone();
+        Callback::from(move |value: bool| *unsaved.borrow_mut() = value)
+    };
+
+    let on_slideshow_exit = {
+        let slideshow = slideshow.clone();
+        Callback::from(move |_| slideshow.set(None))
+    };
+
     let drag_over_class = if *is_drag_over { "drag-over" } else { "" };
 
     html! {
         <main class="container">
             <div class="drop-zone">
-                if directory_path.is_empty() {
+                if directory_path.is_empty() && stream.is_none() {
                     <div class={classes!("drop-zone-hint", drag_over_class)}>
                         <div class="hint-icon">{"+"}</div>
                         <p>{"Drag and drop a folder with frames here"}</p>
                         <p style="font-size: 0.85rem; color: #666;">{"Supports folders with .txt frame files"}</p>
+                        <p style="font-size: 0.85rem; color: #666;">{"Drop a folder of projects to play them as a slideshow"}</p>
+                        <p style="font-size: 0.85rem; color: #666;">{"Hold Alt while dropping to open it in a new window"}</p>
                     </div>
                 } else {
                     <AsciiFramesViewer
+                        key={*session}
                         directory_path={(*directory_path).clone()}
                         fps={24}
-                        loop_enabled={true}
+                        loop_enabled={!launch.no_loop}
+                        fixed_fps={launch.fps}
+                        color_mode={launch.color_mode.as_deref().and_then(ColorMode::from_name)}
+                        start_frame={launch.start}
+                        autoplay={launch.play}
+                        fullscreen={launch.fullscreen}
+                        queued={*queue_len}
+                        stream={(*stream).clone()}
+                        stream_buffer_ms={launch.stream_buffer_ms.unwrap_or(DEFAULT_BUFFER_MS)}
+                        widget={launch.widget}
                         on_clear={on_clear}
+                        {on_unsaved}
                     />
                 }
             </div>
+            if let Some((root_path, settings)) = (*slideshow).clone() {
+                <Slideshow key={root_path.clone()} root_path={root_path.clone()} {settings} on_exit={on_slideshow_exit} />
+            }
         </main>
     }
 }
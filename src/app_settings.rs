@@ -0,0 +1,471 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use wasm_bindgen::JsCast;
+use yew::prelude::*;
+
+use crate::ipc::{invoke_error, tauri_try_invoke};
+use crate::remote::RemoteStatus;
+
+/// What a second launch of decorator does with the path it forwards.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OpenBehavior {
+    #[default]
+    Replace,
+    Enqueue,
+    Window,
+}
+
+impl OpenBehavior {
+    pub const ALL: [OpenBehavior; 3] = [OpenBehavior::Replace, OpenBehavior::Enqueue, OpenBehavior::Window];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            OpenBehavior::Replace => "Replace",
+            OpenBehavior::Enqueue => "Queue",
+            OpenBehavior::Window => "New window",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            OpenBehavior::Replace => "Show it instead of the current project",
+            OpenBehavior::Enqueue => "Open it when the current project is closed",
+            OpenBehavior::Window => "Open it in a new window",
+        }
+    }
+}
+
+/// When the slideshow moves on to the next project.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "count", rename_all = "lowercase")]
+pub enum SlideAdvance {
+    /// After the animation played this many times
+    Loops(u32),
+    Seconds(u32),
+}
+
+impl SlideAdvance {
+    pub fn count(self) -> u32 {
+        match self {
+            SlideAdvance::Loops(n) | SlideAdvance::Seconds(n) => n,
+        }
+    }
+
+    pub fn unit(self) -> &'static str {
+        match self {
+            SlideAdvance::Loops(_) => "loops",
+            SlideAdvance::Seconds(_) => "seconds",
+        }
+    }
+
+    /// The other unit, keeping the count.
+    pub fn toggled(self) -> Self {
+        match self {
+            SlideAdvance::Loops(n) => SlideAdvance::Seconds(n),
+            SlideAdvance::Seconds(n) => SlideAdvance::Loops(n),
+        }
+    }
+
+    pub fn with_count(self, count: u32) -> Self {
+        match self {
+            SlideAdvance::Loops(_) => SlideAdvance::Loops(count.max(1)),
+            SlideAdvance::Seconds(_) => SlideAdvance::Seconds(count.max(1)),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SlideshowSettings {
+    pub shuffle: bool,
+    pub advance: SlideAdvance,
+    pub crossfade_ms: u32,
+}
+
+impl Default for SlideshowSettings {
+    fn default() -> Self {
+        SlideshowSettings {
+            shuffle: false,
+            advance: SlideAdvance::Loops(2),
+            crossfade_ms: 1000,
+        }
+    }
+}
+
+/// The local remote control server; see the backend's remote module.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RemoteSettings {
+    pub enabled: bool,
+    pub port: u16,
+    /// Listen on all interfaces instead of localhost only
+    pub allow_lan: bool,
+    /// Generated by the backend when saved empty
+    pub token: String,
+}
+
+impl Default for RemoteSettings {
+    fn default() -> Self {
+        RemoteSettings {
+            enabled: false,
+            port: 7645,
+            allow_lan: false,
+            token: String::new(),
+        }
+    }
+}
+
+/// OSC input for VJ tools, and optionally frame index output.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OscSettings {
+    pub enabled: bool,
+    pub port: u16,
+    /// Listen on all interfaces instead of localhost only
+    pub allow_lan: bool,
+    /// `host:port` the current frame index is sent to; empty for none
+    pub output: String,
+}
+
+impl Default for OscSettings {
+    fn default() -> Self {
+        OscSettings {
+            enabled: false,
+            port: 9000,
+            allow_lan: false,
+            output: String::new(),
+        }
+    }
+}
+
+/// This instance's part in synchronized multi-screen playback.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncRole {
+    #[default]
+    Off,
+    Leader,
+    Follower,
+}
+
+impl SyncRole {
+    pub const ALL: [SyncRole; 3] = [SyncRole::Off, SyncRole::Leader, SyncRole::Follower];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SyncRole::Off => "Off",
+            SyncRole::Leader => "Leader",
+            SyncRole::Follower => "Follower",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            SyncRole::Off => "Play on its own",
+            SyncRole::Leader => "Other instances follow this one's playback",
+            SyncRole::Follower => "Play in step with a leader",
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SyncSettings {
+    pub role: SyncRole,
+    /// TCP port a leader accepts followers on
+    pub port: u16,
+    /// `host:port` of the leader, for followers
+    pub leader: String,
+    /// Added to a follower's position, for displays that lag behind
+    pub latency_ms: i32,
+}
+
+impl Default for SyncSettings {
+    fn default() -> Self {
+        SyncSettings {
+            role: SyncRole::Off,
+            port: 7646,
+            leader: String::new(),
+            latency_ms: 0,
+        }
+    }
+}
+
+/// App-wide preferences stored by the backend, as opposed to details.toml.
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppSettings {
+    pub open_behavior: OpenBehavior,
+    pub slideshow: SlideshowSettings,
+    pub remote: RemoteSettings,
+    pub osc: OscSettings,
+    pub sync: SyncSettings,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct AppSettingsPanelProps {
+    pub remote_status: RemoteStatus,
+    pub on_error: Callback<String>,
+}
+
+/// App-wide settings, shown below the project ones. They apply right away and
+/// don't belong to the project.
+#[function_component(AppSettingsPanel)]
+pub fn app_settings_panel(props: &AppSettingsPanelProps) -> Html {
+    let app_settings = use_state(AppSettings::default);
+
+    {
+        let app_settings = app_settings.clone();
+        use_effect_with((), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                let args = serde_wasm_bindgen::to_value(&json!({})).unwrap();
+                if let Ok(settings) = tauri_try_invoke("get_app_settings", args).await {
+                    if let Ok(settings) = serde_wasm_bindgen::from_value::<AppSettings>(settings) {
+                        app_settings.set(settings);
+                    }
+                }
+            });
+            || ()
+        });
+    }
+
+    let on_app_settings = {
+        let app_settings = app_settings.clone();
+        let on_error = props.on_error.clone();
+        Callback::from(move |settings: AppSettings| {
+            app_settings.set(settings.clone());
+            let on_error = on_error.clone();
+            let app_settings = app_settings.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let args = serde_wasm_bindgen::to_value(&json!({ "settings": settings })).unwrap();
+                match tauri_try_invoke("set_app_settings", args).await {
+                    // As saved, with a generated remote token
+                    Ok(saved) => {
+                        if let Ok(saved) = serde_wasm_bindgen::from_value::<AppSettings>(saved) {
+                            app_settings.set(saved);
+                        }
+                    }
+                    Err(e) => on_error.emit(invoke_error(e)),
+                }
+            });
+        })
+    };
+
+    html! {
+        <>
+            <div class="control-row settings-field">
+                <label class="settings-key" title="What launching decorator with a path does while it is already running">{"second launch"}</label>
+                { for OpenBehavior::ALL.iter().map(|&behavior| {
+                    let on_app_settings = on_app_settings.clone();
+                    let settings = AppSettings { open_behavior: behavior, ..(*app_settings).clone() };
+                    html! {
+                        <button class={if app_settings.open_behavior == behavior { "ctrl-btn copy-format-btn active" } else { "ctrl-btn copy-format-btn" }} type="button" onclick={Callback::from(move |_| on_app_settings.emit(settings.clone()))} title={behavior.description()}>{behavior.label()}</button>
+                    }
+                }) }
+            </div>
+            {{
+                let slideshow = app_settings.slideshow.clone();
+                let with_slideshow = {
+                    let app_settings = (*app_settings).clone();
+                    move |slideshow: SlideshowSettings| AppSettings { slideshow, ..app_settings.clone() }
+                };
+                let on_shuffle = {
+                    let on_app_settings = on_app_settings.clone();
+                    let settings = with_slideshow(SlideshowSettings { shuffle: !slideshow.shuffle, ..slideshow.clone() });
+                    Callback::from(move |_| on_app_settings.emit(settings.clone()))
+                };
+                let on_advance_unit = {
+                    let on_app_settings = on_app_settings.clone();
+                    let settings = with_slideshow(SlideshowSettings { advance: slideshow.advance.toggled(), ..slideshow.clone() });
+                    Callback::from(move |_| on_app_settings.emit(settings.clone()))
+                };
+                let on_advance_count = {
+                    let on_app_settings = on_app_settings.clone();
+                    let with_slideshow = with_slideshow.clone();
+                    let slideshow = slideshow.clone();
+                    Callback::from(move |e: Event| {
+                        let Some(input) = e.target().and_then(|t| t.dyn_into::<web_sys::HtmlInputElement>().ok()) else {
+                            return;
+                        };
+                        if let Ok(count) = input.value().trim().parse::<u32>() {
+                            on_app_settings.emit(with_slideshow(SlideshowSettings { advance: slideshow.advance.with_count(count), ..slideshow.clone() }));
+                        }
+                    })
+                };
+                let on_crossfade = {
+                    let on_app_settings = on_app_settings.clone();
+                    let slideshow = slideshow.clone();
+                    Callback::from(move |e: Event| {
+                        let Some(input) = e.target().and_then(|t| t.dyn_into::<web_sys::HtmlInputElement>().ok()) else {
+                            return;
+                        };
+                        if let Ok(crossfade_ms) = input.value().trim().parse::<u32>() {
+                            on_app_settings.emit(with_slideshow(SlideshowSettings { crossfade_ms, ..slideshow.clone() }));
+                        }
+                    })
+                };
+                html! {
+                    <div class="control-row settings-field">
+                        <label class="settings-key" title="Playing a folder of projects, e.g. decorator ~/art --slideshow">{"slideshow"}</label>
+                        <button class={if slideshow.shuffle { "ctrl-btn copy-format-btn active" } else { "ctrl-btn copy-format-btn" }} type="button" onclick={on_shuffle} title="Play the projects in random order">{"Shuffle"}</button>
+                        <input class="settings-input settings-number" type="number" min="1" value={slideshow.advance.count().to_string()} onchange={on_advance_count} title="How long each project stays up" />
+                        <button class="ctrl-btn copy-format-btn" type="button" onclick={on_advance_unit} title="Move on after a number of loops or of seconds">{slideshow.advance.unit()}</button>
+                        <input class="settings-input settings-number" type="number" min="0" step="100" value={slideshow.crossfade_ms.to_string()} onchange={on_crossfade} title="Crossfade between projects" />
+                        <span class="info-text">{"ms fade"}</span>
+                    </div>
+                }
+            }}
+            {{
+                let remote = app_settings.remote.clone();
+                let with_remote = {
+                    let app_settings = (*app_settings).clone();
+                    move |remote: RemoteSettings| AppSettings { remote, ..app_settings.clone() }
+                };
+                let on_remote_enabled = {
+                    let on_app_settings = on_app_settings.clone();
+                    let settings = with_remote(RemoteSettings { enabled: !remote.enabled, ..remote.clone() });
+                    Callback::from(move |_| on_app_settings.emit(settings.clone()))
+                };
+                let on_remote_lan = {
+                    let on_app_settings = on_app_settings.clone();
+                    let settings = with_remote(RemoteSettings { allow_lan: !remote.allow_lan, ..remote.clone() });
+                    Callback::from(move |_| on_app_settings.emit(settings.clone()))
+                };
+                // Saved empty, the backend generates a new one
+                let on_remote_token = {
+                    let on_app_settings = on_app_settings.clone();
+                    let settings = with_remote(RemoteSettings { token: String::new(), ..remote.clone() });
+                    Callback::from(move |_| on_app_settings.emit(settings.clone()))
+                };
+                let on_remote_port = {
+                    let on_app_settings = on_app_settings.clone();
+                    let remote = remote.clone();
+                    Callback::from(move |e: Event| {
+                        let Some(input) = e.target().and_then(|t| t.dyn_into::<web_sys::HtmlInputElement>().ok()) else {
+                            return;
+                        };
+                        if let Ok(port) = input.value().trim().parse::<u16>() {
+                            on_app_settings.emit(with_remote(RemoteSettings { port, ..remote.clone() }));
+                        }
+                    })
+                };
+                html! {
+                    <div class="control-row settings-field">
+                        <label class="settings-key" title="HTTP and WebSocket control of playback from other programs">{"remote control"}</label>
+                        <button class={if remote.enabled { "ctrl-btn copy-format-btn active" } else { "ctrl-btn copy-format-btn" }} type="button" onclick={on_remote_enabled} title="Run the remote control server">{if remote.enabled { "On" } else { "Off" }}</button>
+                        <input class="settings-input settings-number" type="number" min="1" max="65535" value={remote.port.to_string()} onchange={on_remote_port} title="Port" />
+                        <button class={if remote.allow_lan { "ctrl-btn copy-format-btn active" } else { "ctrl-btn copy-format-btn" }} type="button" onclick={on_remote_lan} title="Accept connections from other machines, not just this one">{"LAN"}</button>
+                        <input class="settings-input" type="text" readonly=true value={remote.token.clone()} placeholder="generated when turned on" title="Token clients send as Authorization: Bearer or ?token=" />
+                        <button class="ctrl-btn copy-format-btn" type="button" onclick={on_remote_token} title="Replace the token; clients using the old one are locked out">{"New token"}</button>
+                        <span class="info-text">{props.remote_status.address.clone().filter(|_| props.remote_status.running).unwrap_or_else(|| "stopped".to_string())}</span>
+                    </div>
+                }
+            }}
+            {{
+                let osc = app_settings.osc.clone();
+                let with_osc = {
+                    let app_settings = (*app_settings).clone();
+                    move |osc: OscSettings| AppSettings { osc, ..app_settings.clone() }
+                };
+                let on_osc_enabled = {
+                    let on_app_settings = on_app_settings.clone();
+                    let settings = with_osc(OscSettings { enabled: !osc.enabled, ..osc.clone() });
+                    Callback::from(move |_| on_app_settings.emit(settings.clone()))
+                };
+                let on_osc_lan = {
+                    let on_app_settings = on_app_settings.clone();
+                    let settings = with_osc(OscSettings { allow_lan: !osc.allow_lan, ..osc.clone() });
+                    Callback::from(move |_| on_app_settings.emit(settings.clone()))
+                };
+                let on_osc_port = {
+                    let on_app_settings = on_app_settings.clone();
+                    let with_osc = with_osc.clone();
+                    let osc = osc.clone();
+                    Callback::from(move |e: Event| {
+                        let Some(input) = e.target().and_then(|t| t.dyn_into::<web_sys::HtmlInputElement>().ok()) else {
+                            return;
+                        };
+                        if let Ok(port) = input.value().trim().parse::<u16>() {
+                            on_app_settings.emit(with_osc(OscSettings { port, ..osc.clone() }));
+                        }
+                    })
+                };
+                let on_osc_output = {
+                    let on_app_settings = on_app_settings.clone();
+                    let osc = osc.clone();
+                    Callback::from(move |e: Event| {
+                        let Some(input) = e.target().and_then(|t| t.dyn_into::<web_sys::HtmlInputElement>().ok()) else {
+                            return;
+                        };
+                        on_app_settings.emit(with_osc(OscSettings { output: input.value().trim().to_string(), ..osc.clone() }));
+                    })
+                };
+                html! {
+                    <div class="control-row settings-field">
+                        <label class="settings-key" title="/decorator/play, pause, toggle, seek <0-1>, fps <int>, color <int>, open <path>">{"osc"}</label>
+                        <button class={if osc.enabled { "ctrl-btn copy-format-btn active" } else { "ctrl-btn copy-format-btn" }} type="button" onclick={on_osc_enabled} title="Listen for OSC messages over UDP">{if osc.enabled { "On" } else { "Off" }}</button>
+                        <input class="settings-input settings-number" type="number" min="1" max="65535" value={osc.port.to_string()} onchange={on_osc_port} title="UDP port to listen on" />
+                        <button class={if osc.allow_lan { "ctrl-btn copy-format-btn active" } else { "ctrl-btn copy-format-btn" }} type="button" onclick={on_osc_lan} title="Accept messages from other machines, not just this one">{"LAN"}</button>
+                        <input class="settings-input" type="text" value={osc.output.clone()} onchange={on_osc_output} placeholder="output host:port" title="Sends /decorator/frame <index> here on every frame" />
+                    </div>
+                }
+            }}
+            {{
+                let sync = app_settings.sync.clone();
+                let with_sync = {
+                    let app_settings = (*app_settings).clone();
+                    move |sync: SyncSettings| AppSettings { sync, ..app_settings.clone() }
+                };
+                // Text and number fields save when they lose focus
+                let on_sync_field = {
+                    let on_app_settings = on_app_settings.clone();
+                    let with_sync = with_sync.clone();
+                    let sync = sync.clone();
+                    move |update: fn(&mut SyncSettings, &str) -> bool| {
+                        let on_app_settings = on_app_settings.clone();
+                        let with_sync = with_sync.clone();
+                        let sync = sync.clone();
+                        Callback::from(move |e: Event| {
+                            let Some(input) = e.target().and_then(|t| t.dyn_into::<web_sys::HtmlInputElement>().ok()) else {
+                                return;
+                            };
+                            let mut sync = sync.clone();
+                            if update(&mut sync, input.value().trim()) {
+                                on_app_settings.emit(with_sync(sync));
+                            }
+                        })
+                    }
+                };
+                let on_sync_port = on_sync_field(|sync, value| value.parse().map(|port| sync.port = port).is_ok());
+                let on_sync_leader = on_sync_field(|sync, value| {
+                    sync.leader = value.to_string();
+                    true
+                });
+                let on_sync_latency = on_sync_field(|sync, value| value.parse().map(|latency| sync.latency_ms = latency).is_ok());
+                html! {
+                    <div class="control-row settings-field">
+                        <label class="settings-key" title="Several decorators playing in lockstep, e.g. one per screen">{"sync"}</label>
+                        { for SyncRole::ALL.iter().map(|&role| {
+                            let on_app_settings = on_app_settings.clone();
+                            let settings = with_sync(SyncSettings { role, ..sync.clone() });
+                            html! {
+                                <button class={if sync.role == role { "ctrl-btn copy-format-btn active" } else { "ctrl-btn copy-format-btn" }} type="button" onclick={Callback::from(move |_| on_app_settings.emit(settings.clone()))} title={role.description()}>{role.label()}</button>
+                            }
+                        }) }
+                        if sync.role == SyncRole::Leader {
+                            <input class="settings-input settings-number" type="number" min="1" max="65535" value={sync.port.to_string()} onchange={on_sync_port} title="TCP port followers connect to" />
+                        }
+                        if sync.role == SyncRole::Follower {
+                            <input class="settings-input" type="text" value={sync.leader.clone()} onchange={on_sync_leader} placeholder="leader host:port" title="Where the leader runs, e.g. 192.168.1.20:7646" />
+                            <input class="settings-input settings-number" type="number" step="5" value={sync.latency_ms.to_string()} onchange={on_sync_latency} title="Milliseconds to run ahead of the leader, for a display that lags" />
+                        }
+                        if let Some(status) = props.remote_status.sync.clone() {
+                            <span class="info-text">{status}</span>
+                        }
+                    </div>
+                }
+            }}
+        </>
+    }
+}
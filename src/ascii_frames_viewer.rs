@@ -1,7 +1,6 @@
-use gloo_timers::callback::Interval;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::collections::VecDeque;
+use std::collections::{BTreeSet, VecDeque};
 use std::cell::RefCell;
 use std::rc::Rc;
 use wasm_bindgen::prelude::*;
@@ -9,21 +8,35 @@ use wasm_bindgen::closure::Closure;
 use yew::prelude::*;
 use yew_icons::{Icon, IconId};
 
+use crate::app::use_open_viewer_window;
+use crate::ipc::{invoke_error, listen_window_event, tauri_invoke, tauri_try_invoke, unlisten_window_event};
+use crate::app_settings::AppSettingsPanel;
+use crate::remote::{ExportRequest, PlaybackState, RemoteCommand, RemoteStatus, SyncFollow};
+use crate::frame_editor::{EditTool, TextGrid};
+use crate::color_grid::{css_rgb, parse_hex_rgb, ColorGrid, Rgb};
+use crate::frame_export::{
+    draw_cells, render_cells, to_ansi, to_html, to_plain_text, to_png_data_url, CellRect,
+    CopyFormat, RasterStyle,
+};
+use crate::frame_timing::{format_time, FrameTiming, TimingRange};
+use crate::view_scale::{use_fitted_font_size, use_view_scale, ViewControls};
+use crate::glyph_metrics::{css_font_family, load_project_font, use_font_list, use_glyph_metrics};
+use crate::color_mode::{ColorMode, ColorSettings, Gradient, GradientControls, Recolor};
+use crate::palette::{read_project_palette, use_palettes, Palette, PaletteControls};
+use crate::waveform::{draw_waveform, Waveform};
+use crate::widget::{use_widget_mode, WidgetOptions, WidgetPanel, RESIZE_STEP};
+use crate::frame_search::{search_frames, SearchMatch, SearchMode, SearchOptions};
+use crate::project_settings::{is_valid_color, validate_fields, with_known_fields, DetailsField};
+use crate::frame_stream::{JitterBuffer, StreamFrame, StreamFrameProvider, DEFAULT_BUFFER_MS};
+
 // Use shared types from cascii-core-view
 use cascii_core_view::{
     draw_cached_canvas, draw_frame_from_cache, load_color_frames, load_text_frames,
-    render_to_offscreen_canvas, yield_to_event_loop, FontSizing, Frame, FrameCanvasCache,
+    render_to_offscreen_canvas, yield_to_event_loop, Frame, FrameCanvasCache,
     FrameColors, FrameDataProvider, FrameFile, LoadResult, LoadingPhase, RenderConfig,
 };
 
 #[wasm_bindgen(inline_js = r#"
-export async function tauriInvoke(cmd, args) {
-  const g = globalThis.__TAURI__;
-  if (g?.core?.invoke) return g.core.invoke(cmd, args);
-  if (g?.tauri?.invoke) return g.tauri.invoke(cmd, args);
-  throw new Error('Tauri invoke is not available');
-}
-
 export function observeResize(element, callback) {
   const observer = new ResizeObserver((entries) => {
     for (const entry of entries) {
@@ -39,17 +52,21 @@ export function disconnectObserver(observer) {
   observer.disconnect();
 }
 
+export function startWindowDrag() {
+  globalThis.__TAURI__?.webviewWindow?.getCurrentWebviewWindow?.().startDragging();
+}
+
 "#)]
 extern "C" {
-    #[wasm_bindgen(js_name = tauriInvoke)]
-    async fn tauri_invoke(cmd: &str, args: JsValue) -> JsValue;
-
     #[wasm_bindgen(js_name = observeResize)]
     fn observe_resize(element: &web_sys::Element, callback: &Closure<dyn Fn(f64, f64)>) -> JsValue;
 
     #[wasm_bindgen(js_name = disconnectObserver)]
     fn disconnect_observer(observer: &JsValue);
 
+    #[wasm_bindgen(js_name = startWindowDrag)]
+    fn start_window_drag();
+
 }
 
 async fn sleep_ms(ms: i32) {
@@ -65,6 +82,127 @@ async fn sleep_ms(ms: i32) {
 }
 
 const BW_PLAYBACK_BACKGROUND_SLEEP_MS: i32 = 12;
+/// Longest wait between checks for a due streamed frame.
+const STREAM_POLL_MS: f64 = 16.0;
+/// A follower further off its leader than this jumps instead of easing back.
+const SYNC_JUMP_MS: f64 = 250.0;
+/// Share of a follower's drift corrected per leader update.
+const SYNC_DRIFT_GAIN: f64 = 0.5;
+
+/// Map a pointer position to the (col, row) cell of the rendered frame element.
+fn cell_at_point(element: &web_sys::Element, x: f64, y: f64, cols: usize, rows: usize) -> Option<(usize, usize)> {
+    let rect = element.get_bounding_client_rect();
+    if cols == 0 || rows == 0 || rect.width() <= 0.0 || rect.height() <= 0.0 {
+        return None;
+    }
+    let col = ((x - rect.left()) / rect.width() * cols as f64).floor();
+    let row = ((y - rect.top()) / rect.height() * rows as f64).floor();
+    Some((
+        col.clamp(0.0, (cols - 1) as f64) as usize,
+        row.clamp(0.0, (rows - 1) as f64) as usize,
+    ))
+}
+
+/// Pixel box (left, top, width, height) of a cell rectangle, relative to `container`.
+fn cell_rect_box(
+    element: &web_sys::Element,
+    container: &web_sys::Element,
+    sel: CellRect,
+    cols: usize,
+    rows: usize,
+) -> (f64, f64, f64, f64) {
+    let rect = element.get_bounding_client_rect();
+    let outer = container.get_bounding_client_rect();
+    let cell_w = rect.width() / cols.max(1) as f64;
+    let cell_h = rect.height() / rows.max(1) as f64;
+    (
+        rect.left() - outer.left() + sel.col as f64 * cell_w,
+        rect.top() - outer.top() + sel.row as f64 * cell_h,
+        sel.cols as f64 * cell_w,
+        sel.rows as f64 * cell_h,
+    )
+}
+
+/// Local date and time for a Unix timestamp in seconds.
+fn format_timestamp(secs: u64) -> String {
+    let date = js_sys::Date::new(&JsValue::from_f64(secs as f64 * 1000.0));
+    String::from(date.to_locale_string("default", &JsValue::UNDEFINED))
+}
+
+/// A frame and the viewer settings that color it, captured when an export starts.
+struct FrameExport {
+    content: String,
+    size: (usize, usize),
+    /// Painted or loaded colors, if any
+    colors: Option<ColorGrid>,
+    /// Source file, for a .colors sidecar that isn't loaded yet
+    txt_path: Option<String>,
+    rect: Option<CellRect>,
+    fg: Rgb,
+    bg: Rgb,
+    color_mode: ColorMode,
+    recolor: Option<Recolor>,
+    gradient: Gradient,
+    seconds: f64,
+    font_size: f64,
+    font_family: String,
+    cell_size: (f64, f64),
+}
+
+/// An exported frame, in the shape its format takes.
+enum Exported {
+    Text(String),
+    Html { html: String, alt_text: String },
+    /// `data:image/png;base64,...`
+    Png(String),
+}
+
+impl FrameExport {
+    /// Render the frame as `format`, matching what the active color mode shows.
+    async fn render(self, format: CopyFormat) -> Result<Exported, String> {
+        let FrameExport { content, size: (cols, rows), mut colors, rect, mut fg, mut bg, .. } = self;
+        // .colors sidecars may not have been loaded into a color layer yet
+        if colors.is_none() && format != CopyFormat::Text {
+            if let Some(path) = self.txt_path {
+                let args = serde_wasm_bindgen::to_value(&json!({ "txtFilePath": path })).unwrap();
+                colors = serde_wasm_bindgen::from_value::<Option<ColorGrid>>(tauri_invoke("read_colors_file", args).await)
+                    .ok()
+                    .flatten();
+            }
+        }
+        match self.color_mode {
+            ColorMode::Gradient => colors = Some(self.gradient.grid(cols, rows, self.seconds)),
+            ColorMode::Inverted if colors.is_none() => colors = Some(ColorGrid::filled(cols, rows, fg)),
+            _ => {}
+        }
+        if let Some(r) = self.recolor.as_ref() {
+            colors = colors.map(|grid| r.grid(&grid));
+            (fg, bg) = (r.color(fg), r.color(bg));
+        }
+
+        match format {
+            CopyFormat::Text => Ok(Exported::Text(to_plain_text(&content, rect))),
+            CopyFormat::Ansi => Ok(Exported::Text(to_ansi(&content, colors.as_ref(), rect))),
+            CopyFormat::Html => Ok(Exported::Html {
+                html: to_html(&content, colors.as_ref(), rect, fg, bg),
+                alt_text: to_plain_text(&content, rect),
+            }),
+            CopyFormat::Png => to_png_data_url(
+                &content,
+                colors.as_ref(),
+                rect,
+                &RasterStyle {
+                    fg,
+                    bg: Some(bg),
+                    font_size: self.font_size,
+                    font_family: &self.font_family,
+                    cell_size: Some(self.cell_size),
+                },
+            )
+            .map(Exported::Png),
+        }
+    }
+}
 
 struct TauriFrameProvider;
 
@@ -113,6 +251,27 @@ struct ProjectDetails {
     audio_path: Option<String>,
     background_color: Option<String>,
     color: Option<String>,
+    #[serde(default)]
+    timing: Vec<TimingRange>,
+    #[serde(default)]
+    audio_offset_ms: i32,
+    #[serde(default)]
+    font: Option<String>,
+}
+
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+struct HistoryItem {
+    label: String,
+    timestamp: u64,
+    files: usize,
+}
+
+/// Project undo journal as reported by the backend.
+#[derive(Deserialize, Clone, PartialEq, Debug, Default)]
+struct HistoryState {
+    entries: Vec<HistoryItem>,
+    /// Entries before the cursor are applied, the rest can be redone
+    cursor: usize,
 }
 
 #[derive(Properties, PartialEq, Clone)]
@@ -122,14 +281,53 @@ pub struct AsciiFramesViewerProps {
     pub fps: u32,
     #[prop_or(true)]
     pub loop_enabled: bool,
+    /// FPS that wins over details.toml, e.g. from the command line
+    #[prop_or_default]
+    pub fixed_fps: Option<u32>,
+    #[prop_or_default]
+    pub color_mode: Option<ColorMode>,
+    /// Frame shown once the first project has loaded
+    #[prop_or_default]
+    pub start_frame: Option<usize>,
+    /// Start playing as soon as the frames are loaded
+    #[prop_or_default]
+    pub autoplay: bool,
+    /// Start with the overlay hidden
+    #[prop_or_default]
+    pub fullscreen: bool,
+    /// Projects waiting to open after this one is cleared
+    #[prop_or_default]
+    pub queued: usize,
+    /// Never show the controls, not even on hover
+    #[prop_or_default]
+    pub hide_controls: bool,
+    /// Live frame source (`tcp:PORT`, `unix:PATH` or `pipe:PATH`), shown instead of a project
+    #[prop_or_default]
+    pub stream: Option<String>,
+    /// How long streamed frames are held to even out their timing
+    #[prop_or(DEFAULT_BUFFER_MS)]
+    pub stream_buffer_ms: u32,
+    /// Set in widget windows, which show only the frame from the start
+    #[prop_or_default]
+    pub widget: Option<WidgetOptions>,
+    /// Called whenever playback passes the last frame
+    #[prop_or_default]
+    pub on_loop: Callback<()>,
     #[prop_or_default]
     pub on_clear: Callback<()>,
+    /// Told whether there are frame edits that haven't been saved yet
+    #[prop_or_default]
+    pub on_unsaved: Callback<bool>,
 }
 
 #[function_component(AsciiFramesViewer)]
 pub fn ascii_frames_viewer(props: &AsciiFramesViewerProps) -> Html {
     // Frame storage - use RefCell to avoid re-renders during color loading
     let frames_ref: Rc<RefCell<Vec<Frame>>> = use_mut_ref(Vec::new);
+    // Source files of the loaded frames, index-aligned with frames_ref
+    let frame_files_ref: Rc<RefCell<Vec<FrameFile>>> = use_mut_ref(Vec::new);
+    // Colors from .colors sidecars or painting; these take precedence over .cframe colors
+    let color_layers: Rc<RefCell<Vec<Option<ColorGrid>>>> = use_mut_ref(Vec::new);
 
     // Reactive state for UI updates (phase, progress, errors)
     let loading_phase = use_state(|| LoadingPhase::Idle);
@@ -146,44 +344,148 @@ pub fn ascii_frames_viewer(props: &AsciiFramesViewerProps) -> Html {
     let color_cache_refresh = use_state(|| 0u64);
     let color_cache_worker_id: Rc<RefCell<u64>> = use_mut_ref(|| 0u64);
     let is_playing_ref = use_mut_ref(|| false);
-    let color_mode_ref: Rc<RefCell<u8>> = use_mut_ref(|| 0u8);
+    let color_mode_ref: Rc<RefCell<ColorMode>> = use_mut_ref(ColorMode::default);
     let loading_phase_ref: Rc<RefCell<LoadingPhase>> = use_mut_ref(|| LoadingPhase::Idle);
+    // Live stream: what the backend says about it, and a tick per frame shown
+    let stream_status = use_state(|| None::<String>);
+    let stream_tick = use_state(|| 0u64);
+    let stream_serial: Rc<RefCell<u64>> = use_mut_ref(|| 0u64);
+    // Bumped by every stream start, so a stopping cleanup can tell it was replaced
+    let stream_session: Rc<RefCell<u64>> = use_mut_ref(|| 0u64);
+    // Size of the streamed frame, so the font is re-fitted when the sender changes it
+    let stream_size = use_state(|| (0usize, 0usize));
+    // Handed to a widget window for as long as it is open
+    let stream_paused = use_state(|| false);
 
     let current_index = use_state(|| 0usize);
     let current_index_ref = use_mut_ref(|| 0usize);
+    // When the frame on screen started (Date.now() time); sync followers shift it
+    // to stay in step, and can set where playback starts
+    let frame_started_ref = use_mut_ref(|| 0.0f64);
+    let sync_start: Rc<RefCell<Option<f64>>> = use_mut_ref(|| None);
     let is_playing = use_state(|| false);
-    let interval_handle: Rc<RefCell<Option<Interval>>> = use_mut_ref(|| None);
+    // Bumped to stop the running playback loop
+    let playback_worker_id: Rc<RefCell<u64>> = use_mut_ref(|| 0u64);
+    // Bumped to cancel a pending delayed audio start
+    let audio_start_id: Rc<RefCell<u64>> = use_mut_ref(|| 0u64);
 
-    // Color display mode: 0 = B&W, 1 = details.toml colors, 2 = colored frames
-    let color_mode = use_state(|| 0u8);
+    let color_mode = use_state(|| props.color_mode.unwrap_or_default());
+    let color_settings = use_state(ColorSettings::default);
     // Parsed CSS color strings from details.toml
     let details_fg_css = use_state(|| None::<String>);
     let details_bg_css = use_state(|| None::<String>);
     // True when details.toml has non-default colors (not white-on-black)
     let has_custom_colors = use_state(|| false);
+    // details.toml colors as RGB (foreground, background), used for exports
+    let details_rgb = use_state(|| ((255u8, 255u8, 255u8), (0u8, 0u8, 0u8)));
+    // Palette mode: built-in presets plus the project's palette file, if any
+    let custom_palette = use_state(|| None::<Palette>);
+    let palettes = use_palettes((*custom_palette).clone());
+    let recolor = Recolor::for_mode(*color_mode, &color_settings, &palettes);
+    // Recolored frames are cached apart from the original colors
+    let recolor_canvas_cache: Rc<RefCell<FrameCanvasCache>> = use_mut_ref(FrameCanvasCache::default);
 
     // Auto-sizing state
     let container_ref = use_node_ref();
     let content_ref = use_node_ref();
     let canvas_ref = use_node_ref();
-    let calculated_font_size = use_state(|| 10.0f64);
+    // Font family from details.toml (project font files are registered under their path)
+    let project_font = use_state(|| None::<String>);
+    let font_family = css_font_family(project_font.as_deref());
+    let glyph_metrics = use_glyph_metrics(font_family.clone());
     let container_size = use_state(|| (0.0f64, 0.0f64));
+    // Sizing mode, zoom and pan of the frame within the display
+    let view_scale = use_view_scale();
 
     // FPS control
-    let current_fps = use_state(|| props.fps);
+    let current_fps = use_state(|| props.fixed_fps.unwrap_or(props.fps));
+    // Per-frame durations from details.toml; frames not covered last 1/fps seconds
+    let timing_ranges = use_state(Vec::<TimingRange>::new);
+    let frame_timing = use_memo(
+        (*frame_count, *current_fps, (*timing_ranges).clone()),
+        |(total, fps, ranges)| FrameTiming::new(*total, *fps, ranges),
+    );
 
     // Audio state
     let audio_ref = use_node_ref();
     let audio_src = use_state(|| None::<String>);
     let audio_volume = use_state(|| 0.5f64);
     let audio_muted = use_state(|| false);
+    // Audio delay relative to the frames, from details.toml and nudged live
+    let audio_offset_ms = use_state(|| 0i32);
+    let audio_offset_status = use_state(|| None::<String>);
+    // Soundtrack envelope drawn behind the progress slider
+    let waveform = use_state(|| None::<Rc<Waveform>>);
+    let waveform_ref = use_node_ref();
 
     // Overlay visibility toggle
-    let overlay_hidden = use_state(|| false);
+    let overlay_hidden = use_state(|| props.fullscreen);
+
+    // Desktop widget mode: a frameless, transparent window showing only the frame
+    let widget_open = use_state(|| false);
 
     // Hover state for showing controls when overlay is hidden
     let is_hovering = use_state(|| false);
 
+    // Frame content search
+    let search_open = use_state(|| false);
+    let search_query = use_state(String::new);
+    let search_options = use_state(SearchOptions::default);
+    let search_results = use_state(Vec::<SearchMatch>::new);
+    let search_error = use_state(|| None::<String>);
+    // Position of the selected match within search_results
+    let search_cursor = use_state(|| None::<usize>);
+
+    // Clipboard copy and rectangular cell selection
+    let copy_open = use_state(|| false);
+    let copy_status = use_state(|| None::<String>);
+    let selecting = use_state(|| false);
+    let selection = use_state(|| None::<CellRect>);
+    let selection_box = use_state(|| None::<(f64, f64, f64, f64)>);
+    let drag_anchor: Rc<RefCell<Option<(usize, usize)>>> = use_mut_ref(|| None);
+
+    // Frame editor
+    let editing = use_state(|| false);
+    let edit_tool = use_state(EditTool::default);
+    let edit_cursor = use_state(|| None::<(usize, usize)>);
+    let brush_char = use_state(|| '#');
+    let edit_clipboard: Rc<RefCell<Option<TextGrid>>> = use_mut_ref(|| None);
+    // Indices of frames whose in-memory text differs from the file on disk
+    let dirty_frames: Rc<RefCell<BTreeSet<usize>>> = use_mut_ref(BTreeSet::new);
+    // Indices of frames whose color layer differs from the .colors file on disk
+    let dirty_colors: Rc<RefCell<BTreeSet<usize>>> = use_mut_ref(BTreeSet::new);
+    let paint_color = use_state(|| (255u8, 255u8, 255u8));
+    // Bumped after every edit so the frame content is redrawn
+    let edit_revision = use_state(|| 0u64);
+    let edit_revision_ref: Rc<RefCell<u64>> = use_mut_ref(|| 0u64);
+    let edit_status = use_state(|| None::<String>);
+    // Bumped to reload the project after frame files were rewritten on disk
+    let reload_tick = use_state(|| 0u64);
+    // Frame to show once the reload has finished
+    let pending_index: Rc<RefCell<Option<usize>>> = use_mut_ref(|| props.start_frame);
+
+    // Undo/redo history panel
+    let history_open = use_state(|| false);
+    let history = use_state(HistoryState::default);
+    let history_status = use_state(|| None::<String>);
+    // Bumped when a save adds a history entry without reloading the frames
+    let history_refresh = use_state(|| 0u64);
+
+    // details.toml settings panel
+    let settings_open = use_state(|| false);
+    let settings_fields = use_state(Vec::<DetailsField>::new);
+    let settings_status = use_state(|| None::<String>);
+    let font_list = use_font_list(*settings_open, props.directory_path.clone());
+    // Remote control: whether the server runs (playback is only reported then),
+    // and the latest command with a serial so repeats still apply
+    let remote_status = use_state(RemoteStatus::default);
+    let remote_command = use_state(|| None::<(u64, RemoteCommand)>);
+    let remote_serial = use_mut_ref(|| 0u64);
+    // Frame export asked for over JSON-RPC, answered by the effect below on_copy
+    let rpc_export = use_state(|| None::<ExportRequest>);
+    // Latest state from the sync leader, when following one
+    let sync_follow = use_state(|| None::<SyncFollow>);
+
     // Sync ref when current_index state changes
     {
         let current_index_ref = current_index_ref.clone();
@@ -223,15 +525,22 @@ pub fn ascii_frames_viewer(props: &AsciiFramesViewerProps) -> Html {
     // Phase 2: Load color data in background (no re-renders during this phase)
     {
         let directory_path = props.directory_path.clone();
+        let fixed_fps = props.fixed_fps;
+        let autoplay = props.autoplay;
         let frames_ref = frames_ref.clone();
+        let frame_files_ref = frame_files_ref.clone();
+        let color_layers = color_layers.clone();
         let loading_phase = loading_phase.clone();
         let loading_error = loading_error.clone();
         let frame_count = frame_count.clone();
         let color_progress = color_progress.clone();
         let current_index = current_index.clone();
-        let interval_handle = interval_handle.clone();
+        let playback_worker_id = playback_worker_id.clone();
         let is_playing = is_playing.clone();
         let current_fps = current_fps.clone();
+        let timing_ranges = timing_ranges.clone();
+        let audio_offset_ms = audio_offset_ms.clone();
+        let waveform = waveform.clone();
         let audio_src = audio_src.clone();
         let frame_canvas_cache = frame_canvas_cache.clone();
         let color_cache_queue = color_cache_queue.clone();
@@ -247,15 +556,36 @@ pub fn ascii_frames_viewer(props: &AsciiFramesViewerProps) -> Html {
         let details_fg_css = details_fg_css.clone();
         let details_bg_css = details_bg_css.clone();
         let has_custom_colors = has_custom_colors.clone();
-
-        use_effect_with(directory_path.clone(), move |_| {
+        let details_rgb = details_rgb.clone();
+        let custom_palette = custom_palette.clone();
+        let project_font = project_font.clone();
+        let color_settings = color_settings.clone();
+        let recolor_canvas_cache = recolor_canvas_cache.clone();
+        let selection = selection.clone();
+        let selection_box = selection_box.clone();
+        let dirty_frames = dirty_frames.clone();
+        let dirty_colors = dirty_colors.clone();
+        let edit_cursor = edit_cursor.clone();
+        let pending_index = pending_index.clone();
+
+        use_effect_with((directory_path.clone(), *reload_tick), move |_| {
             // Reset state
             frames_ref.borrow_mut().clear();
+            frame_files_ref.borrow_mut().clear();
+            color_layers.borrow_mut().clear();
+            dirty_frames.borrow_mut().clear();
+            dirty_colors.borrow_mut().clear();
+            edit_cursor.set(None);
+            selection.set(None);
+            selection_box.set(None);
             frame_count.set(0);
             loading_phase.set(LoadingPhase::Idle);
             loading_error.set(None);
             *color_progress.borrow_mut() = (0, 0);
             frame_canvas_cache.borrow_mut().clear();
+            recolor_canvas_cache.borrow_mut().clear();
+            custom_palette.set(None);
+            project_font.set(None);
             color_cache_queue.borrow_mut().clear();
             color_loaded_flags.borrow_mut().clear();
             has_any_color.set(false);
@@ -265,7 +595,11 @@ pub fn ascii_frames_viewer(props: &AsciiFramesViewerProps) -> Html {
             current_index.set(0);
             is_playing.set(false);
             audio_src.set(None);
-            interval_handle.borrow_mut().take();
+            timing_ranges.set(Vec::new());
+            audio_offset_ms.set(0);
+            waveform.set(None);
+            let next_playback_id = playback_worker_id.borrow().wrapping_add(1);
+            *playback_worker_id.borrow_mut() = next_playback_id;
 
             if !directory_path.is_empty() {
                 loading_phase.set(LoadingPhase::LoadingText);
@@ -278,9 +612,11 @@ pub fn ascii_frames_viewer(props: &AsciiFramesViewerProps) -> Html {
                 if let Ok(details) = serde_wasm_bindgen::from_value::<ProjectDetails>(
                     tauri_invoke("read_project_details", details_args).await
                 ) {
-                    if let Some(fps) = details.fps {
+                    if let Some(fps) = details.fps.filter(|_| fixed_fps.is_none()) {
                         current_fps.set(fps);
                     }
+                    timing_ranges.set(details.timing.clone());
+                    audio_offset_ms.set(details.audio_offset_ms);
                     if let Some(audio_path) = details.audio_path {
                         let audio_args =
                             serde_wasm_bindgen::to_value(&json!({ "audioPath": audio_path }))
@@ -290,6 +626,17 @@ pub fn ascii_frames_viewer(props: &AsciiFramesViewerProps) -> Html {
                         ) {
                             audio_src.set(Some(data_url));
                         }
+                        // Decoding can take a while; don't hold up the frames for it
+                        let waveform = waveform.clone();
+                        wasm_bindgen_futures::spawn_local(async move {
+                            let args =
+                                serde_wasm_bindgen::to_value(&json!({ "audioPath": audio_path })).unwrap();
+                            if let Ok(envelope) = tauri_try_invoke("get_audio_waveform", args).await {
+                                if let Ok(envelope) = serde_wasm_bindgen::from_value::<Waveform>(envelope) {
+                                    waveform.set(Some(Rc::new(envelope)));
+                                }
+                            }
+                        });
                     }
                     // Parse details.toml colors for mode 1
                     let fg = details.color.as_deref().unwrap_or("white");
@@ -298,8 +645,24 @@ pub fn ascii_frames_viewer(props: &AsciiFramesViewerProps) -> Html {
                     // Only enable details color mode if colors differ from default
                     let is_custom = colors.foreground != (255, 255, 255) || colors.background != (0, 0, 0);
                     has_custom_colors.set(is_custom);
+                    details_rgb.set((colors.foreground, colors.background));
                     details_fg_css.set(Some(colors.foreground_css()));
                     details_bg_css.set(Some(colors.background_css()));
+
+                    if let Some(font) = details.font {
+                        if let Some(family) = load_project_font(&directory_path, font).await {
+                            project_font.set(Some(family));
+                        }
+                    }
+                }
+
+                // A palette file in the project joins the presets and is selected
+                if let Some(palette) = read_project_palette(&directory_path).await {
+                    custom_palette.set(Some(palette));
+                    color_settings.set(ColorSettings {
+                        palette: Palette::builtin().len(),
+                        ..(*color_settings).clone()
+                    });
                 }
 
                 // Two-phase loading via cascii-core-view orchestrators
@@ -308,8 +671,16 @@ pub fn ascii_frames_viewer(props: &AsciiFramesViewerProps) -> Html {
                     Ok((loaded_frames, frame_files)) => {
                         let total = loaded_frames.len();
                         *frames_ref.borrow_mut() = loaded_frames;
+                        *frame_files_ref.borrow_mut() = frame_files.clone();
+                        *color_layers.borrow_mut() = vec![None; total];
                         *color_loaded_flags.borrow_mut() = vec![false; total];
                         frame_count.set(total);
+                        if let Some(idx) = pending_index.borrow_mut().take() {
+                            current_index.set(idx.min(total.saturating_sub(1)));
+                        }
+                        if autoplay && total > 0 {
+                            is_playing.set(true);
+                        }
                         *color_progress.borrow_mut() = (0, total);
                         frame_canvas_cache.borrow_mut().resize(total);
                         loading_phase.set(LoadingPhase::LoadingColors);
@@ -336,7 +707,7 @@ pub fn ascii_frames_viewer(props: &AsciiFramesViewerProps) -> Html {
                                         *has_any_color_flag.borrow_mut() = true;
                                         has_any_color.set(true);
                                     }
-                                    if *color_mode_for_color == 2
+                                    if color_mode_for_color.uses_frame_colors()
                                         && i == *current_index_ref_for_color.borrow()
                                     {
                                         color_cache_refresh_for_color
@@ -349,7 +720,7 @@ pub fn ascii_frames_viewer(props: &AsciiFramesViewerProps) -> Html {
                                 let is_playing_ref = is_playing_ref.clone();
                                 let color_mode_ref = color_mode_ref.clone();
                                 async move {
-                                    if *is_playing_ref.borrow() && *color_mode_ref.borrow() != 2 {
+                                    if *is_playing_ref.borrow() && !color_mode_ref.borrow().uses_frame_colors() {
                                         sleep_ms(BW_PLAYBACK_BACKGROUND_SLEEP_MS).await;
                                     } else {
                                         yield_to_event_loop().await;
@@ -358,6 +729,29 @@ pub fn ascii_frames_viewer(props: &AsciiFramesViewerProps) -> Html {
                             },
                         )
                         .await;
+
+                        // .colors sidecars (written by older cascii versions or the
+                        // paint tools) override the colors embedded in .cframe files
+                        for (i, file) in frame_files.iter().enumerate() {
+                            let args = serde_wasm_bindgen::to_value(
+                                &json!({ "txtFilePath": file.path }),
+                            )
+                            .unwrap();
+                            let layer = tauri_try_invoke("read_colors_file", args)
+                                .await
+                                .ok()
+                                .and_then(|v| serde_wasm_bindgen::from_value::<Option<ColorGrid>>(v).ok())
+                                .flatten();
+                            if let Some(layer) = layer {
+                                if let Some(slot) = color_layers.borrow_mut().get_mut(i) {
+                                    *slot = Some(layer);
+                                }
+                                if !*has_any_color_flag.borrow() {
+                                    *has_any_color_flag.borrow_mut() = true;
+                                    has_any_color.set(true);
+                                }
+                            }
+                        }
                         loading_phase.set(LoadingPhase::Complete);
                     }
                     Err(e) => {
@@ -372,50 +766,205 @@ pub fn ascii_frames_viewer(props: &AsciiFramesViewerProps) -> Html {
         });
     }
 
-    // Animation effect
+    // Live stream: each frame pushed by the sender replaces the one on screen, once
+    // the jitter buffer says it's due. The backend listens; this only plays frames out.
+    {
+        let stream = props.stream.clone();
+        let buffer_ms = props.stream_buffer_ms;
+        let frames_ref = frames_ref.clone();
+        let color_layers = color_layers.clone();
+        let loading_phase = loading_phase.clone();
+        let loading_error = loading_error.clone();
+        let frame_count = frame_count.clone();
+        let current_index = current_index.clone();
+        let has_any_color = has_any_color.clone();
+        let has_any_color_flag = has_any_color_flag.clone();
+        let stream_status = stream_status.clone();
+        let stream_tick = stream_tick.clone();
+        let stream_serial = stream_serial.clone();
+        let stream_size = stream_size.clone();
+        let stream_session = stream_session.clone();
+
+        use_effect_with((stream, buffer_ms, *stream_paused), move |(stream, buffer_ms, paused)| {
+            let running = Rc::new(RefCell::new(true));
+            let mut listeners = Vec::new();
+            stream_status.set(None);
+
+            if let Some(source) = stream.clone().filter(|_| !*paused) {
+                // Every start is a fresh session, also when taking the stream back from a widget
+                let next_session = stream_session.borrow().wrapping_add(1);
+                *stream_session.borrow_mut() = next_session;
+                frames_ref.borrow_mut().clear();
+                color_layers.borrow_mut().clear();
+                frame_count.set(0);
+                loading_error.set(None);
+                loading_phase.set(LoadingPhase::LoadingText);
+                {
+                    let stream_status = stream_status.clone();
+                    let loading_error = loading_error.clone();
+                    let loading_phase = loading_phase.clone();
+                    wasm_bindgen_futures::spawn_local(async move {
+                        let args = serde_wasm_bindgen::to_value(&json!({ "source": source })).unwrap();
+                        match tauri_try_invoke("start_stream", args).await {
+                            Ok(status) => stream_status.set(status.as_string()),
+                            Err(e) => {
+                                loading_error.set(Some(invoke_error(e)));
+                                loading_phase.set(LoadingPhase::Idle);
+                            }
+                        }
+                    });
+                }
+
+                let buffer = Rc::new(RefCell::new(JitterBuffer::new(*buffer_ms)));
+                let on_frame = {
+                    let buffer = buffer.clone();
+                    Closure::<dyn Fn(JsValue)>::new(move |payload| {
+                        if let Ok(frame) = serde_wasm_bindgen::from_value::<StreamFrame>(payload) {
+                            buffer.borrow_mut().push(frame);
+                        }
+                    })
+                };
+                let on_status = {
+                    let stream_status = stream_status.clone();
+                    Closure::<dyn Fn(JsValue)>::new(move |payload: JsValue| {
+                        stream_status.set(payload.as_string());
+                    })
+                };
+                listeners.push((listen_window_event("stream-frame", &on_frame), on_frame));
+                listeners.push((listen_window_event("stream-status", &on_status), on_status));
+
+                let running = running.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    let mut shown_size = (0, 0);
+                    while *running.borrow() {
+                        let due = buffer.borrow_mut().pop_due(js_sys::Date::now());
+                        if let Some(StreamFrame { text, colors, .. }) = due {
+                            // Frames that don't parse are skipped; the next one replaces them anyway
+                            if let Ok(frame) = StreamFrameProvider::frame(text).await {
+                                if !*running.borrow() {
+                                    break;
+                                }
+                                let size = frame.dimensions();
+                                // Empty on the first frame, or after a reload cleared it
+                                let first = frames_ref.borrow().is_empty();
+                                *frames_ref.borrow_mut() = vec![frame];
+                                if colors.is_some() && !*has_any_color_flag.borrow() {
+                                    *has_any_color_flag.borrow_mut() = true;
+                                    has_any_color.set(true);
+                                }
+                                *color_layers.borrow_mut() = vec![colors];
+                                if first {
+                                    frame_count.set(1);
+                                    current_index.set(0);
+                                    loading_phase.set(LoadingPhase::Complete);
+                                }
+                                if size != shown_size {
+                                    shown_size = size;
+                                    stream_size.set(size);
+                                }
+                                *stream_serial.borrow_mut() += 1;
+                                stream_tick.set(*stream_serial.borrow());
+                            }
+                        }
+                        let now = js_sys::Date::now();
+                        let wait = buffer.borrow().next_due().map_or(STREAM_POLL_MS, |due| (due - now).clamp(1.0, STREAM_POLL_MS));
+                        sleep_ms(wait as i32).await;
+                    }
+                });
+            }
+
+            let streaming = !listeners.is_empty();
+            let session = *stream_session.borrow();
+            move || {
+                *running.borrow_mut() = false;
+                for (handle, callback) in listeners {
+                    unlisten_window_event(&handle);
+                    drop(callback);
+                }
+                // Runs after the next effect, if any. A stream it started already replaced
+                // this one, and stopping now would end that instead; only pausing, clearing
+                // the source or unmounting stops it.
+                if streaming {
+                    wasm_bindgen_futures::spawn_local(async move {
+                        if *stream_session.borrow() != session {
+                            return;
+                        }
+                        let args = serde_wasm_bindgen::to_value(&json!({})).unwrap();
+                        let _ = tauri_try_invoke("stop_stream", args).await;
+                    });
+                }
+            }
+        });
+    }
+
+    // Animation effect: every frame stays on screen for its own duration.
+    // The loop stops as soon as playback_worker_id changes, which the cleanup does.
     {
         let current_index = current_index.clone();
         let current_index_ref = current_index_ref.clone();
+        let frame_started_ref = frame_started_ref.clone();
+        let sync_start = sync_start.clone();
         let is_playing_state = is_playing.clone();
-        let interval_handle = interval_handle.clone();
+        let playback_worker_id = playback_worker_id.clone();
         let loop_enabled = props.loop_enabled;
+        let on_loop = props.on_loop.clone();
         let playing = *is_playing;
         let total_frames = *frame_count;
-        let fps = *current_fps;
+        let frame_timing = frame_timing.clone();
 
-        use_effect_with((playing, fps, total_frames), move |_| {
-            interval_handle.borrow_mut().take();
+        use_effect_with((playing, frame_timing, total_frames), move |(_, frame_timing, _)| {
+            let worker_id = playback_worker_id.borrow().wrapping_add(1);
+            *playback_worker_id.borrow_mut() = worker_id;
 
             if playing && total_frames > 0 {
-                let interval_ms = (1000.0 / fps as f64).max(1.0) as u32;
-                let current_index_clone = current_index.clone();
-                let current_index_ref_clone = current_index_ref.clone();
-                let is_playing_clone = is_playing_state.clone();
-                let interval_handle_clone = interval_handle.clone();
-
-                let interval = Interval::new(interval_ms, move || {
-                    let mut current = *current_index_ref_clone.borrow();
-
-                    if current >= total_frames - 1 {
-                        if loop_enabled {
-                            current = 0;
-                            *current_index_ref_clone.borrow_mut() = current;
-                            current_index_clone.set(current);
+                let frame_timing = frame_timing.clone();
+                let playback_worker_id = playback_worker_id.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    // Deadlines are absolute so timer overshoot doesn't add up
+                    let started = sync_start.borrow_mut().take();
+                    *frame_started_ref.borrow_mut() = started.unwrap_or_else(js_sys::Date::now);
+                    loop {
+                        let current = *current_index_ref.borrow();
+                        let due = *frame_started_ref.borrow() + frame_timing.duration_ms(current).max(1.0);
+                        let now = js_sys::Date::now();
+                        if now - due > 1000.0 {
+                            // Timers were throttled (hidden window): resync instead of racing ahead
+                            *frame_started_ref.borrow_mut() = now;
+                            continue;
+                        }
+                        sleep_ms((due - now).max(0.0) as i32).await;
+                        if *playback_worker_id.borrow() != worker_id {
+                            break;
+                        }
+                        // Sync may have moved the frame, or its start later, while we slept
+                        let due = *frame_started_ref.borrow() + frame_timing.duration_ms(current).max(1.0);
+                        if *current_index_ref.borrow() != current || due > js_sys::Date::now() + 1.0 {
+                            continue;
+                        }
+                        *frame_started_ref.borrow_mut() = due;
+
+                        if current >= total_frames - 1 {
+                            on_loop.emit(());
+                            if loop_enabled {
+                                *current_index_ref.borrow_mut() = 0;
+                                current_index.set(0);
+                            } else {
+                                is_playing_state.set(false);
+                                break;
+                            }
                         } else {
-                            interval_handle_clone.borrow_mut().take();
-                            is_playing_clone.set(false);
+                            *current_index_ref.borrow_mut() = current + 1;
+                            current_index.set(current + 1);
                         }
-                    } else {
-                        current += 1;
-                        *current_index_ref_clone.borrow_mut() = current;
-                        current_index_clone.set(current);
                     }
                 });
-
-                *interval_handle.borrow_mut() = Some(interval);
             }
 
-            || ()
+            move || {
+                // Also stops the loop when the viewer goes away, e.g. a slideshow advancing
+                let next_id = playback_worker_id.borrow().wrapping_add(1);
+                *playback_worker_id.borrow_mut() = next_id;
+            }
         });
     }
 
@@ -425,32 +974,76 @@ pub fn ascii_frames_viewer(props: &AsciiFramesViewerProps) -> Html {
         let playing = *is_playing;
         let current_frame_idx = *current_index;
         let total_frames = *frame_count;
-        let fps = *current_fps;
+        let frame_timing = frame_timing.clone();
         let has_audio = audio_src.is_some();
+        let offset_ms = *audio_offset_ms;
+        let is_playing_ref = is_playing_ref.clone();
+        let audio_start_id = audio_start_id.clone();
 
-        use_effect_with((playing, has_audio), move |_| {
+        use_effect_with((playing, has_audio, offset_ms), move |_| {
+            let start_id = audio_start_id.borrow().wrapping_add(1);
+            *audio_start_id.borrow_mut() = start_id;
             if has_audio {
                 if let Some(audio) = audio_ref.cast::<web_sys::HtmlAudioElement>() {
                     if playing {
                         // Calculate the time position based on current frame
-                        if total_frames > 0 && fps > 0 {
-                            let target_time = current_frame_idx as f64 / fps as f64;
+                        let target_time = if total_frames > 0 {
+                            frame_timing.audio_seconds(current_frame_idx, offset_ms)
+                        } else {
+                            0.0
+                        };
+                        if target_time < 0.0 {
+                            // The soundtrack starts later than the frames: hold it back
+                            audio.pause().ok();
+                            audio.set_current_time(0.0);
+                            let delay_ms = (-target_time * 1000.0).round() as i32;
+                            let audio_start_id = audio_start_id.clone();
+                            wasm_bindgen_futures::spawn_local(async move {
+                                sleep_ms(delay_ms).await;
+                                // Skipped if playback, the offset or the audio changed meanwhile
+                                if *audio_start_id.borrow() == start_id && *is_playing_ref.borrow() {
+                                    let _ = audio.play();
+                                }
+                            });
+                        } else {
                             // Only seek if we're significantly out of sync (> 0.1s)
                             let current_time = audio.current_time();
                             if (current_time - target_time).abs() > 0.1 {
                                 audio.set_current_time(target_time);
                             }
+                            let _ = audio.play();
                         }
-                        let _ = audio.play();
                     } else {
                         audio.pause().ok();
                     }
                 }
             }
-            || ()
+            move || {
+                // Also cancels the delayed start when the viewer goes away
+                let next_id = audio_start_id.borrow().wrapping_add(1);
+                *audio_start_id.borrow_mut() = next_id;
+            }
         });
     }
 
+    // Redraw the waveform when the audio, timeline, offset or layout changes
+    {
+        let waveform_ref = waveform_ref.clone();
+        let total_frames = *frame_count;
+        let timeline_seconds = frame_timing.start_seconds(total_frames.saturating_sub(1));
+        let timeline_key = (timeline_seconds * 1000.0) as i64;
+        let container_key = (container_size.0 as i32, container_size.1 as i32);
+        use_effect_with(
+            ((*waveform).clone(), timeline_key, *audio_offset_ms, container_key),
+            move |(waveform, _, offset_ms, _)| {
+                if let (Some(waveform), Some(canvas)) = (waveform, waveform_ref.cast::<web_sys::HtmlCanvasElement>()) {
+                    draw_waveform(&canvas, waveform, timeline_seconds, *offset_ms);
+                }
+                || ()
+            },
+        );
+    }
+
     // Volume and mute control effect
     {
         let audio_ref = audio_ref.clone();
@@ -496,42 +1089,59 @@ pub fn ascii_frames_viewer(props: &AsciiFramesViewerProps) -> Html {
         });
     }
 
-    // Auto-size font to fit container using cascii-core-view
+    // Canvases drawn with the previous font are stale
     {
-        let frames_ref = frames_ref.clone();
-        let calculated_font_size = calculated_font_size.clone();
-        let container_width = container_size.0;
-        let container_height = container_size.1;
-        let total_frames = *frame_count;
-        let phase = *loading_phase;
+        let frame_canvas_cache = frame_canvas_cache.clone();
+        let recolor_canvas_cache = recolor_canvas_cache.clone();
+        use_effect_with(font_family.clone(), move |_| {
+            frame_canvas_cache.borrow_mut().clear();
+            recolor_canvas_cache.borrow_mut().clear();
+        });
+    }
 
-        use_effect_with(
-            (
-                total_frames,
-                phase,
-                container_width as i32,
-                container_height as i32,
-            ),
-            move |_| {
-                let frames = frames_ref.borrow();
-                if frames.is_empty() {
-                    return;
-                }
+    // Told by the backend when this window's widget has closed and it is shown again
+    let widget_mode = {
+        let stream_paused = stream_paused.clone();
+        use_widget_mode(props.widget.is_some(), Callback::from(move |_| stream_paused.set(false)))
+    };
+
+    // Widgets draw without a background, so canvases cached with one are stale
+    {
+        let frame_canvas_cache = frame_canvas_cache.clone();
+        let recolor_canvas_cache = recolor_canvas_cache.clone();
+        use_effect_with(widget_mode.active, move |_| {
+            frame_canvas_cache.borrow_mut().clear();
+            recolor_canvas_cache.borrow_mut().clear();
+        });
+    }
 
-                if let Some(first_frame) = frames.first() {
-                    let (cols, rows) = first_frame.dimensions();
+    // Size the font for the scale mode and zoom using the measured glyph metrics
+    let frame_size = frames_ref.borrow().first().map(|frame| frame.dimensions());
+    let calculated_font_size = use_fitted_font_size(frame_size, glyph_metrics, *view_scale, *container_size);
 
-                    if rows == 0 || cols == 0 {
-                        return;
-                    }
+    // Re-run the frame search whenever the query, options or loaded frames change
+    {
+        let frames_ref = frames_ref.clone();
+        let search_results = search_results.clone();
+        let search_error = search_error.clone();
+        let search_cursor = search_cursor.clone();
+        let query = (*search_query).clone();
+        let options = (*search_options).clone();
+        let total_frames = *frame_count;
 
-                    // Use FontSizing from cascii-core-view
-                    let optimal_font_size =
-                        FontSizing::calculate(cols, rows, container_width, container_height);
-                    calculated_font_size.set(optimal_font_size);
+        use_effect_with((query, options, total_frames), move |(query, options, _)| {
+            match search_frames(&frames_ref.borrow(), query, options) {
+                Ok(results) => {
+                    search_error.set(None);
+                    search_results.set(results);
                 }
-            },
-        );
+                Err(e) => {
+                    search_error.set(Some(e));
+                    search_results.set(Vec::new());
+                }
+            }
+            search_cursor.set(None);
+        });
     }
 
     // Keep the color cache warm in background without hurting B/W playback.
@@ -548,10 +1158,14 @@ pub fn ascii_frames_viewer(props: &AsciiFramesViewerProps) -> Html {
         let loading_phase_ref = loading_phase_ref.clone();
         let total_frames = *frame_count;
         let has_any_color_val = *has_any_color;
-        let font_size = *calculated_font_size;
+        let font_size = calculated_font_size;
         let font_size_key = (font_size * 100.0) as i32;
+        let custom_font = project_font.is_some();
+        let font_family = font_family.clone();
+        let metrics = glyph_metrics;
+        let widget = widget_mode.active;
 
-        use_effect_with((total_frames, has_any_color_val, font_size_key), move |_| {
+        use_effect_with((total_frames, has_any_color_val, font_size_key, font_family.clone(), metrics, widget), move |_| {
             if total_frames == 0 || !has_any_color_val {
                 return;
             }
@@ -591,7 +1205,7 @@ pub fn ascii_frames_viewer(props: &AsciiFramesViewerProps) -> Html {
                         return;
                     }
 
-                    if *is_playing_ref.borrow() && *color_mode_ref.borrow() != 2 {
+                    if *is_playing_ref.borrow() && *color_mode_ref.borrow() != ColorMode::Frames {
                         sleep_ms(BW_PLAYBACK_BACKGROUND_SLEEP_MS).await;
                         continue;
                     }
@@ -612,13 +1226,24 @@ pub fn ascii_frames_viewer(props: &AsciiFramesViewerProps) -> Html {
 
                     let offscreen = {
                         let frames = frames_for_cache.borrow();
-                        frames
-                            .get(i)
-                            .and_then(|f| f.cframe.as_ref())
-                            .and_then(|cframe| {
-                                render_to_offscreen_canvas(cframe, &RenderConfig::new(font_size))
-                                    .ok()
-                            })
+                        frames.get(i).and_then(|f| {
+                            // cascii's renderer only knows the default monospace font
+                            // and always fills the background
+                            if custom_font || widget {
+                                let style = RasterStyle {
+                                    fg: (255, 255, 255),
+                                    bg: (!widget).then_some((0, 0, 0)),
+                                    font_size,
+                                    font_family: &font_family,
+                                    cell_size: Some(metrics.cell_size(font_size)),
+                                };
+                                let colors = ColorGrid::from_frame(f)?;
+                                render_cells(&f.content, Some(&colors), None, &style).ok()
+                            } else {
+                                let cframe = f.cframe.as_ref()?;
+                                render_to_offscreen_canvas(cframe, &RenderConfig::new(font_size)).ok()
+                            }
+                        })
                     };
 
                     if let Some(canvas) = offscreen {
@@ -644,24 +1269,81 @@ pub fn ascii_frames_viewer(props: &AsciiFramesViewerProps) -> Html {
         let color_mode = *color_mode;
         let total_frames = *frame_count;
         let current_frame_idx = (*current_index).min(total_frames.saturating_sub(1));
-        let font_size = *calculated_font_size;
-        let font_size_key = (*calculated_font_size * 100.0) as i32;
+        let font_size = calculated_font_size;
+        let font_size_key = (calculated_font_size * 100.0) as i32;
         let cache_refresh_tick = *color_cache_refresh;
-
-        use_effect_with((current_frame_idx, color_mode, total_frames, font_size_key, cache_refresh_tick), move |_| {
+        let editing = *editing;
+        let paint_mode = editing && edit_tool.is_color_tool();
+        let edit_tick = *edit_revision;
+        let color_layers = color_layers.clone();
+        let (default_fg, default_bg) = *details_rgb;
+        let recolor_canvas_cache = recolor_canvas_cache.clone();
+        let recolor = recolor.clone();
+        let gradient = color_settings.gradient;
+        let frame_seconds = frame_timing.start_seconds(current_frame_idx);
+        let custom_font = project_font.is_some();
+        let font_family = font_family.clone();
+        let metrics = glyph_metrics;
+        let widget = widget_mode.active;
+        let stream_tick = *stream_tick;
+
+        use_effect_with((current_frame_idx, color_mode, total_frames, font_size_key, cache_refresh_tick, editing, paint_mode, edit_tick, recolor.clone(), gradient, (font_family.clone(), metrics, widget, stream_tick)), move |_| {
             let frames = frames_ref.borrow();
             if let Some(frame) = frames.get(current_frame_idx) {
-                if color_mode == 2 {
+                // The text editor works on plain text; the paint tools always show colors
+                if (color_mode.draws_cells() && !editing) || paint_mode {
+                    // Painting happens on the real colors, never recolored ones
+                    let recolor = if paint_mode { None } else { recolor.as_ref() };
+                    let layer = color_layers.borrow().get(current_frame_idx).cloned().flatten();
+                    let (cols, rows) = frame.dimensions();
+                    let own_colors = if paint_mode || color_mode == ColorMode::Inverted {
+                        // Inverting covers B&W frames too, so give every frame a grid
+                        Some(layer.or_else(|| ColorGrid::from_frame(frame)).unwrap_or_else(|| {
+                            ColorGrid::filled(cols, rows, default_fg)
+                        }))
+                    } else if color_mode == ColorMode::Gradient {
+                        Some(gradient.grid(cols, rows, frame_seconds))
+                    } else {
+                        layer
+                    };
+                    let own_colors = match recolor {
+                        Some(r) => own_colors.map(|colors| r.grid(&colors)),
+                        None => own_colors,
+                    };
+
+                    let style = RasterStyle {
+                        fg: recolor.map(|r| r.color(default_fg)).unwrap_or(default_fg),
+                        // Widgets show the desktop behind the cells
+                        bg: (!widget).then(|| recolor.map(|r| r.color(default_bg)).unwrap_or(default_bg)),
+                        font_size,
+                        font_family: &font_family,
+                        cell_size: Some(metrics.cell_size(font_size)),
+                    };
+
+                    // Painted, generated and .colors-backed frames are rasterized by the viewer itself
+                    if let (Some(colors), Some(canvas)) =
+                        (own_colors, canvas_ref.cast::<web_sys::HtmlCanvasElement>())
+                    {
+                        if draw_cells(&canvas, &frame.content, Some(&colors), None, &style).is_ok() {
+                            return;
+                        }
+                    }
+
                     if let Some(cframe) = frame.cframe.as_ref() {
                         if let Some(canvas) = canvas_ref.cast::<web_sys::HtmlCanvasElement>() {
+                            let canvas_cache = if recolor.is_some() {
+                                &recolor_canvas_cache
+                            } else {
+                                &frame_canvas_cache
+                            };
                             {
-                                let mut cache = frame_canvas_cache.borrow_mut();
+                                let mut cache = canvas_cache.borrow_mut();
                                 cache.resize(total_frames);
                                 cache.invalidate_for_font_size_key(font_size_key);
                             }
 
                             let drawn = {
-                                let cache = frame_canvas_cache.borrow();
+                                let cache = canvas_cache.borrow();
                                 draw_frame_from_cache(&canvas, &cache, current_frame_idx)
                                     .unwrap_or(false)
                             };
@@ -669,11 +1351,25 @@ pub fn ascii_frames_viewer(props: &AsciiFramesViewerProps) -> Html {
                                 return;
                             }
 
-                            if let Ok(offscreen) =
-                                render_to_offscreen_canvas(cframe, &RenderConfig::new(font_size))
-                            {
+                            let config = RenderConfig::new(font_size);
+                            let rendered = match recolor {
+                                // cascii's renderer only knows the default monospace font
+                                // and always fills the background
+                                _ if custom_font || widget => {
+                                    let colors = ColorGrid::from_frame(frame)
+                                        .map(|grid| recolor.map(|r| r.grid(&grid)).unwrap_or(grid));
+                                    render_cells(&frame.content, colors.as_ref(), None, &style)
+                                }
+                                Some(r) => {
+                                    let mut recolored = cframe.clone();
+                                    r.apply(&mut recolored.rgb, recolored.width as usize);
+                                    render_to_offscreen_canvas(&recolored, &config)
+                                }
+                                None => render_to_offscreen_canvas(cframe, &config),
+                            };
+                            if let Ok(offscreen) = rendered {
                                 let draw_ok = draw_cached_canvas(&canvas, &offscreen).is_ok();
-                                frame_canvas_cache
+                                canvas_cache
                                     .borrow_mut()
                                     .store(current_frame_idx, offscreen);
                                 if draw_ok {
@@ -698,80 +1394,1291 @@ pub fn ascii_frames_viewer(props: &AsciiFramesViewerProps) -> Html {
         })
     };
 
-    let on_seek = {
+    // Jump to a frame: pause playback and move audio to the matching time
+    let seek_to_frame = {
         let current_index = current_index.clone();
         let is_playing = is_playing.clone();
         let frame_count = frame_count.clone();
         let audio_ref = audio_ref.clone();
-        let fps = *current_fps;
-        Callback::from(move |e: web_sys::InputEvent| {
-            if let Some(target) = e.target() {
-                if let Ok(input) = target.dyn_into::<web_sys::HtmlInputElement>() {
-                    let slider_val = input.value_as_number();
-                    if slider_val.is_finite() {
-                        let total_frames = *frame_count;
-                        if total_frames > 0 {
-                            let target_frame =
-                                (slider_val.clamp(0.0, 1.0) * (total_frames - 1) as f64).round()
-                                    as usize;
-                            is_playing.set(false);
-                            current_index.set(target_frame);
-
-                            // Seek audio to match frame
-                            if let Some(audio) = audio_ref.cast::<web_sys::HtmlAudioElement>() {
-                                let target_time = target_frame as f64 / fps as f64;
-                                audio.set_current_time(target_time);
-                            }
-                        }
-                    }
-                }
+        let frame_timing = frame_timing.clone();
+        let audio_offset_ms = *audio_offset_ms;
+        Callback::from(move |target_frame: usize| {
+            let total_frames = *frame_count;
+            if total_frames == 0 {
+                return;
+            }
+            let target_frame = target_frame.min(total_frames - 1);
+            is_playing.set(false);
+            current_index.set(target_frame);
+
+            // Seek audio to match frame
+            if let Some(audio) = audio_ref.cast::<web_sys::HtmlAudioElement>() {
+                audio.set_current_time(frame_timing.audio_seconds(target_frame, audio_offset_ms).max(0.0));
             }
         })
     };
 
-    let on_fps_change = {
-        let current_fps = current_fps.clone();
-        Callback::from(move |e: web_sys::InputEvent| {
-            if let Some(target) = e.target() {
-                if let Ok(input) = target.dyn_into::<web_sys::HtmlInputElement>() {
-                    if let Ok(fps) = input.value().parse::<u32>() {
-                        if fps > 0 {
-                            current_fps.set(fps);
-                        }
+    // Remote control server status, at startup and whenever it starts or stops
+    {
+        let remote_status = remote_status.clone();
+        use_effect_with((), move |_| {
+            {
+                let remote_status = remote_status.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    let args = serde_wasm_bindgen::to_value(&json!({})).unwrap();
+                    if let Ok(status) = tauri_try_invoke("get_remote_status", args).await {
+                        remote_status.set(serde_wasm_bindgen::from_value(status).unwrap_or_default());
                     }
+                });
+            }
+            let callback = Closure::<dyn Fn(JsValue)>::new(move |payload| {
+                remote_status.set(serde_wasm_bindgen::from_value(payload).unwrap_or_default());
+            });
+            let handle = listen_window_event("remote-status", &callback);
+            move || {
+                unlisten_window_event(&handle);
+                drop(callback);
+            }
+        });
+    }
+
+    // Commands only reach the window the backend controls. The listener outlives
+    // this render, so it just stores the command for the effect below to apply.
+    {
+        let remote_command = remote_command.clone();
+        let remote_serial = remote_serial.clone();
+        use_effect_with((), move |_| {
+            let callback = Closure::<dyn Fn(JsValue)>::new(move |payload| {
+                if let Ok(command) = serde_wasm_bindgen::from_value::<RemoteCommand>(payload) {
+                    *remote_serial.borrow_mut() += 1;
+                    remote_command.set(Some((*remote_serial.borrow(), command)));
                 }
+            });
+            let handle = listen_window_event("remote-command", &callback);
+            move || {
+                unlisten_window_event(&handle);
+                drop(callback);
             }
-        })
-    };
+        });
+    }
 
-    let on_volume_change = {
+    {
+        let is_playing = is_playing.clone();
+        let seek_to_frame = seek_to_frame.clone();
+        let current_fps = current_fps.clone();
+        let color_mode = color_mode.clone();
         let audio_volume = audio_volume.clone();
-        Callback::from(move |e: web_sys::InputEvent| {
-            if let Some(target) = e.target() {
-                if let Ok(input) = target.dyn_into::<web_sys::HtmlInputElement>() {
-                    let vol = input.value_as_number();
-                    if vol.is_finite() {
-                        audio_volume.set(vol.clamp(0.0, 1.0));
+        let frame_timing = frame_timing.clone();
+        let current_index_ref = current_index_ref.clone();
+        let total_frames = *frame_count;
+        use_effect_with((*remote_command).clone(), move |command| {
+            match command.as_ref().map(|(_, command)| command) {
+                Some(RemoteCommand::Play) => is_playing.set(total_frames > 0),
+                Some(RemoteCommand::Pause) => is_playing.set(false),
+                Some(RemoteCommand::Toggle) => is_playing.set(!*is_playing && total_frames > 0),
+                Some(RemoteCommand::Seek { frame }) => seek_to_frame.emit(*frame),
+                Some(RemoteCommand::Step { frames }) => {
+                    let current = *current_index_ref.borrow() as i64;
+                    seek_to_frame.emit((current + frames).max(0) as usize);
+                }
+                Some(RemoteCommand::SeekPosition { position }) if total_frames > 0 => {
+                    let last_start = frame_timing.start_seconds(total_frames - 1);
+                    seek_to_frame.emit(frame_timing.frame_at(position.clamp(0.0, 1.0) * last_start));
+                }
+                Some(RemoteCommand::SeekTime { ms }) if total_frames > 0 => {
+                    seek_to_frame.emit(frame_timing.frame_at(ms.max(0.0) / 1000.0));
+                }
+                Some(RemoteCommand::Fps { fps }) => current_fps.set((*fps).max(1)),
+                Some(RemoteCommand::ColorMode { mode }) => {
+                    if let Some(mode) = ColorMode::from_name(mode) {
+                        color_mode.set(mode);
                     }
                 }
+                Some(RemoteCommand::Volume { volume }) => audio_volume.set(volume.clamp(0.0, 1.0)),
+                Some(RemoteCommand::SeekPosition { .. }) | Some(RemoteCommand::SeekTime { .. }) | None => {}
             }
-        })
-    };
+        });
+    }
 
-    let on_toggle_mute = {
-        let audio_muted = audio_muted.clone();
-        Callback::from(move |_| {
-            audio_muted.set(!*audio_muted);
-        })
-    };
+    // Following a sync leader: the backend forwards its state in our clock
+    {
+        let sync_follow = sync_follow.clone();
+        use_effect_with((), move |_| {
+            let callback = Closure::<dyn Fn(JsValue)>::new(move |payload| {
+                if let Ok(state) = serde_wasm_bindgen::from_value::<SyncFollow>(payload) {
+                    sync_follow.set(Some(state));
+                }
+            });
+            let handle = listen_window_event("sync-state", &callback);
+            move || {
+                unlisten_window_event(&handle);
+                drop(callback);
+            }
+        });
+    }
 
-    let on_step_backward = {
-        let current_index = current_index.clone();
-        let frame_count = frame_count.clone();
+    // Small drift eases the frame start toward the leader; anything bigger jumps
+    {
         let is_playing = is_playing.clone();
-        Callback::from(move |_| {
-            if *is_playing {
-                is_playing.set(false);
+        let current_index = current_index.clone();
+        let current_index_ref = current_index_ref.clone();
+        let frame_started_ref = frame_started_ref.clone();
+        let sync_start = sync_start.clone();
+        let frame_timing = frame_timing.clone();
+        let total_frames = *frame_count;
+        let playing = *is_playing;
+        let loop_enabled = props.loop_enabled;
+        use_effect_with((*sync_follow).clone(), move |sync| {
+            let Some(sync) = sync.as_ref().filter(|_| total_frames > 0) else {
+                return;
+            };
+            let frame = sync.frame.min(total_frames - 1);
+            if !sync.playing {
+                is_playing.set(false);
+                if *current_index_ref.borrow() != frame {
+                    *current_index_ref.borrow_mut() = frame;
+                    current_index.set(frame);
+                }
+                return;
+            }
+
+            let now = js_sys::Date::now();
+            let start_ms = |frame: usize| frame_timing.start_seconds(frame) * 1000.0;
+            let total_ms = frame_timing.total_seconds() * 1000.0;
+            let looping = loop_enabled && total_ms > 0.0;
+            let mut expected = start_ms(frame) + (now - sync.frame_started_at);
+            expected = if looping { expected.rem_euclid(total_ms) } else { expected.clamp(0.0, total_ms) };
+
+            let current = *current_index_ref.borrow();
+            let local = start_ms(current) + if playing { now - *frame_started_ref.borrow() } else { 0.0 };
+            let mut error = expected - local;
+            // Either side of the loop point is close, not a whole animation apart
+            if looping && error.abs() > total_ms / 2.0 {
+                error -= total_ms * error.signum();
+            }
+
+            if !playing || error.abs() > SYNC_JUMP_MS {
+                let target = frame_timing.frame_at(expected / 1000.0).min(total_frames - 1);
+                let started = now - (expected - start_ms(target));
+                *current_index_ref.borrow_mut() = target;
+                current_index.set(target);
+                if playing {
+                    *frame_started_ref.borrow_mut() = started;
+                } else {
+                    *sync_start.borrow_mut() = Some(started);
+                    is_playing.set(true);
+                }
+            } else {
+                *frame_started_ref.borrow_mut() -= error * SYNC_DRIFT_GAIN;
+            }
+        });
+    }
+
+    // Report what is showing to remote clients
+    {
+        let playback = PlaybackState {
+            directory_path: props.directory_path.clone(),
+            playing: *is_playing,
+            frame: *current_index,
+            frame_count: *frame_count,
+            fps: *current_fps,
+            position_ms: frame_timing.start_seconds(*current_index) * 1000.0,
+            duration_ms: frame_timing.total_seconds() * 1000.0,
+            color_mode: color_mode.name().to_string(),
+            volume: *audio_volume,
+            muted: *audio_muted,
+        };
+        use_effect_with((remote_status.reporting, playback), move |(reporting, playback)| {
+            if *reporting {
+                let args = serde_wasm_bindgen::to_value(&json!({ "playback": playback })).unwrap();
+                wasm_bindgen_futures::spawn_local(async move {
+                    let _ = tauri_try_invoke("report_playback_state", args).await;
+                });
+            }
+        });
+    }
+
+    // The slider spans the animation's timeline, so held frames take up more of it
+    let on_seek = {
+        let seek_to_frame = seek_to_frame.clone();
+        let frame_count = frame_count.clone();
+        let frame_timing = frame_timing.clone();
+        Callback::from(move |e: web_sys::InputEvent| {
+            if let Some(target) = e.target() {
+                if let Ok(input) = target.dyn_into::<web_sys::HtmlInputElement>() {
+                    let slider_val = input.value_as_number();
+                    if slider_val.is_finite() {
+                        let total_frames = *frame_count;
+                        if total_frames > 0 {
+                            let last_start = frame_timing.start_seconds(total_frames - 1);
+                            let target_frame = frame_timing.frame_at(slider_val.clamp(0.0, 1.0) * last_start);
+                            seek_to_frame.emit(target_frame);
+                        }
+                    }
+                }
+            }
+        })
+    };
+
+    let on_toggle_copy = {
+        let copy_open = copy_open.clone();
+        let copy_status = copy_status.clone();
+        Callback::from(move |_| {
+            copy_open.set(!*copy_open);
+            copy_status.set(None);
+        })
+    };
+
+    let on_toggle_selecting = {
+        let selecting = selecting.clone();
+        let selection = selection.clone();
+        let selection_box = selection_box.clone();
+        Callback::from(move |_| {
+            if *selecting {
+                selection.set(None);
+                selection_box.set(None);
+            }
+            selecting.set(!*selecting);
+        })
+    };
+
+    // Rendered frame element, its container and the frame size in cells
+    let frame_geometry = {
+        let frames_ref = frames_ref.clone();
+        let current_index = current_index.clone();
+        let container_ref = container_ref.clone();
+        let content_ref = content_ref.clone();
+        let canvas_ref = canvas_ref.clone();
+        Rc::new(move || -> Option<(web_sys::Element, web_sys::Element, usize, usize)> {
+            let (cols, rows) = frames_ref.borrow().get(*current_index)?.dimensions();
+            let element = canvas_ref
+                .cast::<web_sys::Element>()
+                .or_else(|| content_ref.cast::<web_sys::Element>())?;
+            Some((element, container_ref.cast::<web_sys::Element>()?, cols, rows))
+        })
+    };
+
+    // Drag over the frame to select a rectangle of cells.
+    // Returns the cell under the pointer.
+    let update_selection = {
+        let frame_geometry = frame_geometry.clone();
+        let selection = selection.clone();
+        let selection_box = selection_box.clone();
+        let drag_anchor = drag_anchor.clone();
+        Rc::new(move |e: &web_sys::MouseEvent, start: bool| -> Option<(usize, usize)> {
+            let (element, container, cols, rows) = frame_geometry()?;
+            let cell = cell_at_point(&element, e.client_x() as f64, e.client_y() as f64, cols, rows)?;
+            if start {
+                *drag_anchor.borrow_mut() = Some(cell);
+            }
+            let anchor = (*drag_anchor.borrow())?;
+            let sel = CellRect::from_corners(anchor, cell);
+            selection.set(Some(sel));
+            selection_box.set(Some(cell_rect_box(&element, &container, sel, cols, rows)));
+            Some(cell)
+        })
+    };
+
+    // Cell under the pointer, without touching the selection
+    let pointer_cell = {
+        let frame_geometry = frame_geometry.clone();
+        Rc::new(move |e: &web_sys::MouseEvent| -> Option<(usize, usize)> {
+            let (element, _, cols, rows) = frame_geometry()?;
+            cell_at_point(&element, e.client_x() as f64, e.client_y() as f64, cols, rows)
+        })
+    };
+
+    // Redraw the current frame after an in-memory edit
+    let bump_edit_revision = {
+        let edit_revision = edit_revision.clone();
+        let edit_revision_ref = edit_revision_ref.clone();
+        Rc::new(move || {
+            let next = edit_revision_ref.borrow().wrapping_add(1);
+            *edit_revision_ref.borrow_mut() = next;
+            edit_revision.set(next);
+        })
+    };
+
+    // Every edit, save and reload rerenders, so this is current
+    {
+        let unsaved = !dirty_frames.borrow().is_empty() || !dirty_colors.borrow().is_empty();
+        let on_unsaved = props.on_unsaved.clone();
+        use_effect_with(unsaved, move |unsaved| {
+            on_unsaved.emit(*unsaved);
+            || ()
+        });
+    }
+
+    // Edit the current frame's text through a TextGrid and mark it dirty
+    let apply_edit = {
+        let frames_ref = frames_ref.clone();
+        let current_index = current_index.clone();
+        let dirty_frames = dirty_frames.clone();
+        let bump_edit_revision = bump_edit_revision.clone();
+        let edit_status = edit_status.clone();
+        Rc::new(move |edit: &dyn Fn(&mut TextGrid)| {
+            let idx = *current_index;
+            {
+                let mut frames = frames_ref.borrow_mut();
+                let Some(frame) = frames.get_mut(idx) else {
+                    return;
+                };
+                let mut grid = TextGrid::parse(&frame.content);
+                edit(&mut grid);
+                let content = grid.to_content();
+                if content == frame.content {
+                    return;
+                }
+                frame.content = content;
+            }
+            dirty_frames.borrow_mut().insert(idx);
+            bump_edit_revision();
+            edit_status.set(None);
+        })
+    };
+
+    // Recolor the current frame's color layer and mark it dirty.
+    // The layer starts from the .cframe colors (or plain foreground) on first paint.
+    let apply_paint = {
+        let frames_ref = frames_ref.clone();
+        let color_layers = color_layers.clone();
+        let current_index = current_index.clone();
+        let dirty_colors = dirty_colors.clone();
+        let bump_edit_revision = bump_edit_revision.clone();
+        let edit_status = edit_status.clone();
+        let (default_fg, _) = *details_rgb;
+        Rc::new(move |edit: &dyn Fn(&mut ColorGrid)| {
+            let idx = *current_index;
+            {
+                let frames = frames_ref.borrow();
+                let Some(frame) = frames.get(idx) else {
+                    return;
+                };
+                let (cols, rows) = frame.dimensions();
+                let mut layers = color_layers.borrow_mut();
+                let Some(slot) = layers.get_mut(idx) else {
+                    return;
+                };
+                let layer = slot.get_or_insert_with(|| {
+                    ColorGrid::from_frame(frame).unwrap_or_else(|| ColorGrid::filled(cols, rows, default_fg))
+                });
+                let before = layer.rgb.clone();
+                edit(layer);
+                if layer.rgb == before {
+                    return;
+                }
+            }
+            dirty_colors.borrow_mut().insert(idx);
+            bump_edit_revision();
+            edit_status.set(None);
+        })
+    };
+
+    // Apply the active color tool at a cell
+    let apply_color_tool = {
+        let frames_ref = frames_ref.clone();
+        let color_layers = color_layers.clone();
+        let current_index = current_index.clone();
+        let edit_tool = edit_tool.clone();
+        let paint_color = paint_color.clone();
+        let apply_paint = apply_paint.clone();
+        let (default_fg, _) = *details_rgb;
+        Rc::new(move |(col, row): (usize, usize)| {
+            let color = *paint_color;
+            match *edit_tool {
+                EditTool::Paint => apply_paint(&|layer: &mut ColorGrid| layer.set(col, row, color)),
+                EditTool::FloodFill => {
+                    apply_paint(&|layer: &mut ColorGrid| layer.flood_fill(col, row, color))
+                }
+                EditTool::Eyedropper => {
+                    let idx = *current_index;
+                    let picked = color_layers
+                        .borrow()
+                        .get(idx)
+                        .and_then(|layer| layer.as_ref()?.get(col, row))
+                        .or_else(|| {
+                            frames_ref
+                                .borrow()
+                                .get(idx)
+                                .and_then(ColorGrid::from_frame)
+                                .and_then(|grid| grid.get(col, row))
+                        })
+                        .unwrap_or(default_fg);
+                    paint_color.set(picked);
+                    edit_tool.set(EditTool::Paint);
+                }
+                EditTool::Cursor | EditTool::Line => {}
+            }
+        })
+    };
+
+    let on_select_down = {
+        let active = *selecting || *editing;
+        let color_tool = *editing && edit_tool.is_color_tool();
+        let editing = *editing;
+        let update_selection = update_selection.clone();
+        let pointer_cell = pointer_cell.clone();
+        let apply_color_tool = apply_color_tool.clone();
+        let drag_anchor = drag_anchor.clone();
+        let edit_cursor = edit_cursor.clone();
+        let container_ref = container_ref.clone();
+        let view_scale = view_scale.clone();
+        let widget = widget_mode.active;
+        Callback::from(move |e: web_sys::MouseEvent| {
+            // A frameless widget is moved by dragging the frame itself
+            if widget {
+                if e.button() == 0 {
+                    e.prevent_default();
+                    if let Some(element) = container_ref.cast::<web_sys::HtmlElement>() {
+                        let _ = element.focus();
+                    }
+                    start_window_drag();
+                }
+                return;
+            }
+            // Middle button always pans; the left button pans when no tool wants it
+            if view_scale.pannable() && (e.button() == 1 || (!active && e.button() == 0)) {
+                e.prevent_default();
+                view_scale.start_pan(&e);
+                return;
+            }
+            if active && e.button() == 0 {
+                e.prevent_default();
+                if color_tool {
+                    if let Some(cell) = pointer_cell(&e) {
+                        *drag_anchor.borrow_mut() = Some(cell);
+                        apply_color_tool(cell);
+                    }
+                } else {
+                    let cell = update_selection(&e, true);
+                    if editing {
+                        if let Some(cell) = cell {
+                            edit_cursor.set(Some(cell));
+                        }
+                    }
+                }
+                // prevent_default stops the click from focusing the display
+                if editing {
+                    if let Some(element) = container_ref.cast::<web_sys::HtmlElement>() {
+                        let _ = element.focus();
+                    }
+                }
+            }
+        })
+    };
+
+    let on_select_move = {
+        let active = *selecting || *editing;
+        let tool = *edit_tool;
+        let color_tool = *editing && tool.is_color_tool();
+        let drag_anchor = drag_anchor.clone();
+        let update_selection = update_selection.clone();
+        let pointer_cell = pointer_cell.clone();
+        let apply_color_tool = apply_color_tool.clone();
+        let view_scale = view_scale.clone();
+        Callback::from(move |e: web_sys::MouseEvent| {
+            if view_scale.drag_pan(&e) {
+                return;
+            }
+            if !active || drag_anchor.borrow().is_none() {
+                return;
+            }
+            if !color_tool {
+                update_selection(&e, false);
+            } else if tool == EditTool::Paint {
+                if let Some(cell) = pointer_cell(&e) {
+                    apply_color_tool(cell);
+                }
+            }
+        })
+    };
+
+    let on_select_up = {
+        let drag_anchor = drag_anchor.clone();
+        let editing = *editing;
+        let edit_tool = *edit_tool;
+        let brush = *brush_char;
+        let selection = selection.clone();
+        let selection_box = selection_box.clone();
+        let update_selection = update_selection.clone();
+        let apply_edit = apply_edit.clone();
+        let view_scale = view_scale.clone();
+        Callback::from(move |e: web_sys::MouseEvent| {
+            view_scale.end_pan();
+            let Some(anchor) = *drag_anchor.borrow() else {
+                return;
+            };
+            if editing && !edit_tool.is_color_tool() {
+                let cell = update_selection(&e, false).unwrap_or(anchor);
+                if edit_tool == EditTool::Line {
+                    apply_edit(&|grid: &mut TextGrid| grid.line(anchor, cell, brush));
+                }
+                // A plain click (or a drawn line) leaves no selection behind
+                if edit_tool == EditTool::Line || cell == anchor {
+                    selection.set(None);
+                    selection_box.set(None);
+                }
+            }
+            drag_anchor.borrow_mut().take();
+        })
+    };
+
+    // Wheel (and trackpad pinch, which arrives as a ctrl+wheel) zooms around the pointer
+    let on_display_wheel = {
+        let view_scale = view_scale.clone();
+        let container_ref = container_ref.clone();
+        let widget = widget_mode.active;
+        Callback::from(move |e: web_sys::WheelEvent| {
+            // Yew registers wheel listeners as passive, so the event can't be cancelled;
+            // the display doesn't scroll anyway
+            if e.delta_y() == 0.0 {
+                return;
+            }
+            // A widget has no window border to drag, so the wheel resizes it instead
+            if widget {
+                let factor = if e.delta_y() < 0.0 { RESIZE_STEP } else { 1.0 / RESIZE_STEP };
+                wasm_bindgen_futures::spawn_local(async move {
+                    let args = serde_wasm_bindgen::to_value(&json!({ "factor": factor })).unwrap();
+                    let _ = tauri_try_invoke("resize_widget", args).await;
+                });
+                return;
+            }
+            match container_ref.cast::<web_sys::Element>() {
+                Some(container) => view_scale.zoom_at_pointer(&container, &e),
+                None => view_scale.zoom(if e.delta_y() < 0.0 { 1 } else { -1 }, (0.0, 0.0)),
+            }
+        })
+    };
+
+    let on_view_scale = {
+        let view_scale = view_scale.clone();
+        Callback::from(move |view| view_scale.set(view))
+    };
+
+    let on_toggle_widget = {
+        let widget_open = widget_open.clone();
+        Callback::from(move |_| {
+            widget_open.set(!*widget_open);
+        })
+    };
+
+    // The widget window is sized to the frame's aspect ratio and starts where this viewer is
+    let widget_aspect_ratio = {
+        let (cols, rows) = frame_size.unwrap_or((1, 1));
+        let (width, height) = glyph_metrics.canvas_dimensions(cols.max(1), rows.max(1), 1.0);
+        width / height
+    };
+    let widget_launch = json!({
+        "directoryPath": (!props.directory_path.is_empty()).then(|| props.directory_path.clone()),
+        "stream": props.stream.clone(),
+        "streamBufferMs": props.stream_buffer_ms,
+        "fps": props.fixed_fps,
+        "noLoop": !props.loop_enabled,
+        "play": *is_playing,
+        "colorMode": color_mode.name(),
+        "start": *current_index,
+    });
+
+    let on_widget_started = {
+        let is_playing = is_playing.clone();
+        let widget_open = widget_open.clone();
+        Callback::from(move |_: ()| {
+            // The widget plays now; two of them would play the audio twice
+            is_playing.set(false);
+            widget_open.set(false);
+        })
+    };
+
+    let on_stream_paused = {
+        let stream_paused = stream_paused.clone();
+        Callback::from(move |paused: bool| stream_paused.set(paused))
+    };
+
+    let on_paint_color = {
+        let paint_color = paint_color.clone();
+        Callback::from(move |e: web_sys::InputEvent| {
+            if let Some(target) = e.target() {
+                if let Ok(input) = target.dyn_into::<web_sys::HtmlInputElement>() {
+                    if let Some(color) = parse_hex_rgb(&input.value()) {
+                        paint_color.set(color);
+                    }
+                }
+            }
+        })
+    };
+
+    let on_toggle_editing = {
+        let editing = editing.clone();
+        let is_playing = is_playing.clone();
+        let edit_cursor = edit_cursor.clone();
+        let selecting = selecting.clone();
+        let selection = selection.clone();
+        let selection_box = selection_box.clone();
+        let container_ref = container_ref.clone();
+        Callback::from(move |_| {
+            let enable = !*editing;
+            if enable {
+                is_playing.set(false);
+                selecting.set(false);
+                edit_cursor.set(Some((0, 0)));
+                if let Some(element) = container_ref.cast::<web_sys::HtmlElement>() {
+                    let _ = element.focus();
+                }
+            } else {
+                edit_cursor.set(None);
+            }
+            selection.set(None);
+            selection_box.set(None);
+            editing.set(enable);
+        })
+    };
+
+    let on_edit_tool = {
+        let edit_tool = edit_tool.clone();
+        Callback::from(move |tool: EditTool| {
+            edit_tool.set(tool);
+        })
+    };
+
+    let on_brush_input = {
+        let brush_char = brush_char.clone();
+        Callback::from(move |e: web_sys::InputEvent| {
+            if let Some(target) = e.target() {
+                if let Ok(input) = target.dyn_into::<web_sys::HtmlInputElement>() {
+                    if let Some(ch) = input.value().chars().last() {
+                        brush_char.set(ch);
+                    }
+                }
+            }
+        })
+    };
+
+    let on_edit_fill = {
+        let selection = selection.clone();
+        let brush = *brush_char;
+        let apply_edit = apply_edit.clone();
+        Callback::from(move |_| {
+            if let Some(rect) = *selection {
+                apply_edit(&|grid: &mut TextGrid| grid.fill(rect, brush));
+            }
+        })
+    };
+
+    // Copy the selection (or the cell under the cursor) into the editor clipboard
+    let on_edit_copy = {
+        let frames_ref = frames_ref.clone();
+        let current_index = current_index.clone();
+        let selection = selection.clone();
+        let edit_cursor = edit_cursor.clone();
+        let edit_clipboard = edit_clipboard.clone();
+        let edit_status = edit_status.clone();
+        Callback::from(move |_: ()| {
+            let rect = (*selection).or_else(|| {
+                edit_cursor.map(|(col, row)| CellRect { col, row, cols: 1, rows: 1 })
+            });
+            let (Some(rect), Some(frame)) = (rect, frames_ref.borrow().get(*current_index).cloned()) else {
+                return;
+            };
+            *edit_clipboard.borrow_mut() = Some(TextGrid::parse(&frame.content).copy(rect));
+            edit_status.set(Some(format!("Copied {}×{}", rect.cols, rect.rows)));
+        })
+    };
+
+    let on_edit_paste = {
+        let frames_ref = frames_ref.clone();
+        let current_index = current_index.clone();
+        let selection = selection.clone();
+        let edit_cursor = edit_cursor.clone();
+        let edit_clipboard = edit_clipboard.clone();
+        let apply_edit = apply_edit.clone();
+        Callback::from(move |_: ()| {
+            let Some(block) = edit_clipboard.borrow().clone() else {
+                return;
+            };
+            let target = (*selection)
+                .map(|rect| (rect.col, rect.row))
+                .or(*edit_cursor);
+            let bounds = frames_ref
+                .borrow()
+                .get(*current_index)
+                .map(|f| f.dimensions());
+            if let (Some((col, row)), Some(bounds)) = (target, bounds) {
+                apply_edit(&|grid: &mut TextGrid| grid.paste(col, row, &block, bounds));
+            }
+        })
+    };
+
+    // Write every modified frame back to its .txt file and every repainted
+    // color layer to its .colors file, as one undoable change
+    let on_edit_save = {
+        let directory_path = props.directory_path.clone();
+        let frames_ref = frames_ref.clone();
+        let frame_files_ref = frame_files_ref.clone();
+        let color_layers = color_layers.clone();
+        let dirty_frames = dirty_frames.clone();
+        let dirty_colors = dirty_colors.clone();
+        let edit_status = edit_status.clone();
+        let history_refresh = history_refresh.clone();
+        let bump_edit_revision = bump_edit_revision.clone();
+        Callback::from(move |_: ()| {
+            let (texts, colors, saved) = {
+                let frames = frames_ref.borrow();
+                let files = frame_files_ref.borrow();
+                let layers = color_layers.borrow();
+                let texts: Vec<serde_json::Value> = dirty_frames
+                    .borrow()
+                    .iter()
+                    .filter_map(|&i| {
+                        Some(json!({ "file_path": files.get(i)?.path, "content": frames.get(i)?.content }))
+                    })
+                    .collect();
+                let colors: Vec<serde_json::Value> = dirty_colors
+                    .borrow()
+                    .iter()
+                    .filter_map(|&i| {
+                        Some(json!({ "txt_file_path": files.get(i)?.path, "colors": layers.get(i)?.as_ref()? }))
+                    })
+                    .collect();
+                let saved = dirty_frames.borrow().union(&dirty_colors.borrow()).count();
+                (texts, colors, saved)
+            };
+            if texts.is_empty() && colors.is_empty() {
+                return;
+            }
+
+            let args = serde_wasm_bindgen::to_value(
+                &json!({ "directoryPath": directory_path, "texts": texts, "colors": colors }),
+            )
+            .unwrap();
+            let dirty_frames = dirty_frames.clone();
+            let dirty_colors = dirty_colors.clone();
+            let edit_status = edit_status.clone();
+            let history_refresh = history_refresh.clone();
+            let bump_edit_revision = bump_edit_revision.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match tauri_try_invoke("save_frame_edits", args).await {
+                    Ok(_) => {
+                        dirty_frames.borrow_mut().clear();
+                        dirty_colors.borrow_mut().clear();
+                        edit_status.set(Some(format!("Saved {} frame{}", saved, if saved == 1 { "" } else { "s" })));
+                        history_refresh.set((*history_refresh).wrapping_add(1));
+                    }
+                    Err(e) => edit_status.set(Some(invoke_error(e))),
+                }
+                bump_edit_revision();
+            });
+        })
+    };
+
+    // Insert, duplicate, delete or move frame files on disk, then reload the
+    // project and show `next_index`
+    let run_frame_op = {
+        let directory_path = props.directory_path.clone();
+        let dirty_frames = dirty_frames.clone();
+        let dirty_colors = dirty_colors.clone();
+        let edit_status = edit_status.clone();
+        let reload_tick = reload_tick.clone();
+        let pending_index = pending_index.clone();
+        Callback::from(move |(cmd, args, next_index): (&'static str, serde_json::Value, usize)| {
+            if !dirty_frames.borrow().is_empty() || !dirty_colors.borrow().is_empty() {
+                let confirmed = web_sys::window()
+                    .and_then(|w| w.confirm_with_message("This reloads the frames. Discard unsaved frame edits?").ok())
+                    .unwrap_or(true);
+                if !confirmed {
+                    return;
+                }
+            }
+
+            let mut args = args;
+            args["directoryPath"] = json!(directory_path);
+            let args = serde_wasm_bindgen::to_value(&args).unwrap();
+            let edit_status = edit_status.clone();
+            let reload_tick = reload_tick.clone();
+            let pending_index = pending_index.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match tauri_try_invoke(cmd, args).await {
+                    Ok(_) => {
+                        *pending_index.borrow_mut() = Some(next_index);
+                        edit_status.set(None);
+                        reload_tick.set((*reload_tick).wrapping_add(1));
+                    }
+                    Err(e) => edit_status.set(Some(invoke_error(e))),
+                }
+            });
+        })
+    };
+
+    // Undo or redo the latest project change on disk, then reload the frames
+    let run_history = {
+        let directory_path = props.directory_path.clone();
+        let dirty_frames = dirty_frames.clone();
+        let dirty_colors = dirty_colors.clone();
+        let history = history.clone();
+        let history_status = history_status.clone();
+        let reload_tick = reload_tick.clone();
+        let pending_index = pending_index.clone();
+        let current_index = current_index.clone();
+        Callback::from(move |cmd: &'static str| {
+            if !dirty_frames.borrow().is_empty() || !dirty_colors.borrow().is_empty() {
+                let confirmed = web_sys::window()
+                    .and_then(|w| w.confirm_with_message("This reloads the frames. Discard unsaved frame edits?").ok())
+                    .unwrap_or(true);
+                if !confirmed {
+                    return;
+                }
+            }
+
+            let args = serde_wasm_bindgen::to_value(&json!({ "directoryPath": directory_path })).unwrap();
+            let history = history.clone();
+            let history_status = history_status.clone();
+            let reload_tick = reload_tick.clone();
+            let pending_index = pending_index.clone();
+            let idx = *current_index;
+            wasm_bindgen_futures::spawn_local(async move {
+                match tauri_try_invoke(cmd, args).await {
+                    Ok(state) => {
+                        if let Ok(state) = serde_wasm_bindgen::from_value::<HistoryState>(state) {
+                            history.set(state);
+                        }
+                        history_status.set(None);
+                        *pending_index.borrow_mut() = Some(idx);
+                        reload_tick.set((*reload_tick).wrapping_add(1));
+                    }
+                    Err(e) => history_status.set(Some(invoke_error(e))),
+                }
+            });
+        })
+    };
+
+    // Keyboard input while editing: move the cursor, type, delete, copy/paste, save
+    let on_edit_keydown = {
+        let editing = *editing;
+        let frames_ref = frames_ref.clone();
+        let current_index = current_index.clone();
+        let edit_cursor = edit_cursor.clone();
+        let selection = selection.clone();
+        let selection_box = selection_box.clone();
+        let apply_edit = apply_edit.clone();
+        let on_edit_copy = on_edit_copy.clone();
+        let on_edit_paste = on_edit_paste.clone();
+        let on_edit_save = on_edit_save.clone();
+        let run_history = run_history.clone();
+        let widget = widget_mode.active;
+        let on_exit_widget = widget_mode.exit.clone();
+        Callback::from(move |e: web_sys::KeyboardEvent| {
+            if widget && e.key() == "Escape" {
+                on_exit_widget.emit(());
+                return;
+            }
+            if !editing {
+                return;
+            }
+            let Some((cols, rows)) = frames_ref.borrow().get(*current_index).map(|f| f.dimensions()) else {
+                return;
+            };
+            let (col, row) = edit_cursor.unwrap_or((0, 0));
+            let max_col = cols.saturating_sub(1);
+            let max_row = rows.saturating_sub(1);
+            let key = e.key();
+            let modifier = e.ctrl_key() || e.meta_key();
+
+            let next_cursor = match key.as_str() {
+                "ArrowLeft" => Some((col.saturating_sub(1), row)),
+                "ArrowRight" => Some(((col + 1).min(max_col), row)),
+                "ArrowUp" => Some((col, row.saturating_sub(1))),
+                "ArrowDown" => Some((col, (row + 1).min(max_row))),
+                "Enter" => Some((0, (row + 1).min(max_row))),
+                "Backspace" => {
+                    let target = col.saturating_sub(1);
+                    apply_edit(&|grid: &mut TextGrid| grid.set(target, row, ' '));
+                    Some((target, row))
+                }
+                "Delete" => {
+                    apply_edit(&|grid: &mut TextGrid| grid.set(col, row, ' '));
+                    Some((col, row))
+                }
+                "Escape" => {
+                    selection.set(None);
+                    selection_box.set(None);
+                    Some((col, row))
+                }
+                "c" | "C" if modifier => {
+                    on_edit_copy.emit(());
+                    Some((col, row))
+                }
+                "v" | "V" if modifier => {
+                    on_edit_paste.emit(());
+                    Some((col, row))
+                }
+                "s" | "S" if modifier => {
+                    on_edit_save.emit(());
+                    Some((col, row))
+                }
+                "z" | "Z" if modifier => {
+                    run_history.emit(if e.shift_key() { "redo" } else { "undo" });
+                    Some((col, row))
+                }
+                "y" | "Y" if modifier => {
+                    run_history.emit("redo");
+                    Some((col, row))
+                }
+                _ if !modifier && key.chars().count() == 1 => {
+                    let ch = key.chars().next().unwrap_or(' ');
+                    apply_edit(&|grid: &mut TextGrid| grid.set(col, row, ch));
+                    Some(((col + 1).min(max_col), row))
+                }
+                _ => None,
+            };
+
+            if let Some(cursor) = next_cursor {
+                e.prevent_default();
+                edit_cursor.set(Some(cursor));
+            }
+        })
+    };
+
+    // Everything an export of a frame needs, read when it starts
+    let export_frame = {
+        let frames_ref = frames_ref.clone();
+        let frame_files_ref = frame_files_ref.clone();
+        let color_layers = color_layers.clone();
+        let details_rgb = details_rgb.clone();
+        let font_size = calculated_font_size;
+        let font_family = font_family.clone();
+        let cell_size = glyph_metrics.cell_size(font_size);
+        let color_mode = *color_mode;
+        let recolor = recolor.clone();
+        let gradient = color_settings.gradient;
+        let frame_timing = frame_timing.clone();
+        Callback::from(move |(idx, rect): (usize, Option<CellRect>)| {
+            let layer = color_layers.borrow().get(idx).cloned().flatten();
+            let frames = frames_ref.borrow();
+            let frame = frames.get(idx)?;
+            let (fg, bg) = *details_rgb;
+            Some(FrameExport {
+                content: frame.content.clone(),
+                size: frame.dimensions(),
+                colors: layer.or_else(|| ColorGrid::from_frame(frame)),
+                txt_path: frame_files_ref.borrow().get(idx).map(|f| f.path.clone()),
+                rect,
+                fg,
+                bg,
+                color_mode,
+                recolor: recolor.clone(),
+                gradient,
+                seconds: frame_timing.start_seconds(idx),
+                font_size,
+                font_family: font_family.clone(),
+                cell_size,
+            })
+        })
+    };
+
+    // Copy the current frame (or the selected cells) through the backend clipboard
+    let on_copy = {
+        let export_frame = export_frame.clone();
+        let current_index = current_index.clone();
+        let selection = selection.clone();
+        let copy_status = copy_status.clone();
+        Callback::from(move |format: CopyFormat| {
+            let Some(export) = export_frame.emit((*current_index, *selection)) else {
+                return;
+            };
+            let copy_status = copy_status.clone();
+
+            wasm_bindgen_futures::spawn_local(async move {
+                let request = export.render(format).await.map(|exported| match exported {
+                    Exported::Text(text) => ("copy_text_to_clipboard", json!({ "text": text })),
+                    Exported::Html { html, alt_text } => {
+                        ("copy_html_to_clipboard", json!({ "html": html, "altText": alt_text }))
+                    }
+                    Exported::Png(data) => ("copy_png_to_clipboard", json!({ "pngData": data })),
+                });
+
+                let result = match request {
+                    Ok((cmd, args)) => {
+                        let args = serde_wasm_bindgen::to_value(&args).unwrap();
+                        tauri_try_invoke(cmd, args).await.map(|_| ()).map_err(invoke_error)
+                    }
+                    Err(e) => Err(e),
+                };
+                copy_status.set(Some(match result {
+                    Ok(()) => format!("Copied {}", format.label()),
+                    Err(e) => e,
+                }));
+            });
+        })
+    };
+
+    // Exports asked for over JSON-RPC; each request id is new, so the effect runs for every one
+    {
+        let rpc_export = rpc_export.clone();
+        use_effect_with((), move |_| {
+            let callback = Closure::<dyn Fn(JsValue)>::new(move |payload| {
+                if let Ok(request) = serde_wasm_bindgen::from_value::<ExportRequest>(payload) {
+                    rpc_export.set(Some(request));
+                }
+            });
+            let handle = listen_window_event("rpc-export", &callback);
+            move || {
+                unlisten_window_event(&handle);
+                drop(callback);
+            }
+        });
+    }
+
+    {
+        let export_frame = export_frame.clone();
+        let current_index_ref = current_index_ref.clone();
+        use_effect_with((*rpc_export).clone(), move |request| {
+            if let Some(request) = request.clone() {
+                let frame = request.frame.unwrap_or(*current_index_ref.borrow());
+                let export = match CopyFormat::from_name(&request.format) {
+                    Some(format) => export_frame
+                        .emit((frame, None))
+                        .map(|export| (export, format))
+                        .ok_or_else(|| format!("Frame {} is not loaded", frame)),
+                    None => Err(format!("Unknown export format: {}", request.format)),
+                };
+                wasm_bindgen_futures::spawn_local(async move {
+                    let result = match export {
+                        Ok((export, format)) => export.render(format).await,
+                        Err(e) => Err(e),
+                    };
+                    let (data, error) = match result {
+                        Ok(Exported::Text(text)) | Ok(Exported::Html { html: text, .. }) | Ok(Exported::Png(text)) => {
+                            (Some(text), None)
+                        }
+                        Err(e) => (None, Some(e)),
+                    };
+                    let args = serde_wasm_bindgen::to_value(&json!({
+                        "id": request.id,
+                        "frame": frame,
+                        "data": data,
+                        "error": error,
+                    }))
+                    .unwrap();
+                    let _ = tauri_try_invoke("finish_rpc_export", args).await;
+                });
+            }
+        });
+    }
+
+    let on_toggle_search = {
+        let search_open = search_open.clone();
+        Callback::from(move |_| {
+            search_open.set(!*search_open);
+        })
+    };
+
+    let on_search_input = {
+        let search_query = search_query.clone();
+        Callback::from(move |e: web_sys::InputEvent| {
+            if let Some(target) = e.target() {
+                if let Ok(input) = target.dyn_into::<web_sys::HtmlTextAreaElement>() {
+                    search_query.set(input.value());
+                }
+            }
+        })
+    };
+
+    let on_search_regex = {
+        let search_options = search_options.clone();
+        Callback::from(move |_| {
+            let mut options = (*search_options).clone();
+            options.mode = match options.mode {
+                SearchMode::Substring => SearchMode::Regex,
+                SearchMode::Regex => SearchMode::Substring,
+            };
+            search_options.set(options);
+        })
+    };
+
+    let on_search_case = {
+        let search_options = search_options.clone();
+        Callback::from(move |_| {
+            let mut options = (*search_options).clone();
+            options.case_sensitive = !options.case_sensitive;
+            search_options.set(options);
+        })
+    };
+
+    let on_search_block = {
+        let search_options = search_options.clone();
+        Callback::from(move |_| {
+            let mut options = (*search_options).clone();
+            options.block = !options.block;
+            search_options.set(options);
+        })
+    };
+
+    // Select the n-th search result and seek to its frame
+    let jump_to_match = {
+        let search_results = search_results.clone();
+        let search_cursor = search_cursor.clone();
+        let seek_to_frame = seek_to_frame.clone();
+        Callback::from(move |position: usize| {
+            if let Some(result) = search_results.get(position) {
+                search_cursor.set(Some(position));
+                seek_to_frame.emit(result.frame_index);
+            }
+        })
+    };
+
+    let on_search_next = {
+        let search_results = search_results.clone();
+        let search_cursor = search_cursor.clone();
+        let current_index = current_index.clone();
+        let jump_to_match = jump_to_match.clone();
+        Callback::from(move |_| {
+            if search_results.is_empty() {
+                return;
+            }
+            let next = match *search_cursor {
+                Some(pos) => (pos + 1) % search_results.len(),
+                // Start from the first match after the current frame
+                None => search_results
+                    .iter()
+                    .position(|m| m.frame_index > *current_index)
+                    .unwrap_or(0),
+            };
+            jump_to_match.emit(next);
+        })
+    };
+
+    let on_search_prev = {
+        let search_results = search_results.clone();
+        let search_cursor = search_cursor.clone();
+        let current_index = current_index.clone();
+        let jump_to_match = jump_to_match.clone();
+        Callback::from(move |_| {
+            if search_results.is_empty() {
+                return;
+            }
+            let prev = match *search_cursor {
+                Some(0) => search_results.len() - 1,
+                Some(pos) => pos - 1,
+                None => search_results
+                    .iter()
+                    .rposition(|m| m.frame_index < *current_index)
+                    .unwrap_or(search_results.len() - 1),
+            };
+            jump_to_match.emit(prev);
+        })
+    };
+
+    let on_search_keydown = {
+        let on_search_next = on_search_next.clone();
+        let on_search_prev = on_search_prev.clone();
+        let block = search_options.block;
+        Callback::from(move |e: web_sys::KeyboardEvent| {
+            // In block mode Enter inserts a newline; Ctrl/Cmd+Enter jumps instead
+            if e.key() == "Enter" && (!block || e.ctrl_key() || e.meta_key()) {
+                e.prevent_default();
+                if e.shift_key() {
+                    on_search_prev.emit(());
+                } else {
+                    on_search_next.emit(());
+                }
+            }
+        })
+    };
+
+    let on_fps_change = {
+        let current_fps = current_fps.clone();
+        Callback::from(move |e: web_sys::InputEvent| {
+            if let Some(target) = e.target() {
+                if let Ok(input) = target.dyn_into::<web_sys::HtmlInputElement>() {
+                    if let Ok(fps) = input.value().parse::<u32>() {
+                        if fps > 0 {
+                            current_fps.set(fps);
+                        }
+                    }
+                }
+            }
+        })
+    };
+
+    // Shift the audio against the frames; takes effect immediately
+    let on_audio_offset = {
+        let audio_offset_ms = audio_offset_ms.clone();
+        let audio_offset_status = audio_offset_status.clone();
+        Callback::from(move |offset: i32| {
+            audio_offset_ms.set(offset.clamp(-60_000, 60_000));
+            audio_offset_status.set(None);
+        })
+    };
+
+    let on_audio_offset_input = {
+        let on_audio_offset = on_audio_offset.clone();
+        Callback::from(move |e: web_sys::InputEvent| {
+            if let Some(input) = e.target_dyn_into::<web_sys::HtmlInputElement>() {
+                if let Ok(offset) = input.value().parse::<i32>() {
+                    on_audio_offset.emit(offset);
+                }
+            }
+        })
+    };
+
+    // Persist the calibrated offset as audio_offset_ms in details.toml
+    let on_audio_offset_save = {
+        let directory_path = props.directory_path.clone();
+        let audio_offset_ms = *audio_offset_ms;
+        let audio_offset_status = audio_offset_status.clone();
+        let history_refresh = history_refresh.clone();
+        Callback::from(move |_| {
+            let value = if audio_offset_ms == 0 { String::new() } else { audio_offset_ms.to_string() };
+            let fields = vec![DetailsField {
+                key: "audio_offset_ms".to_string(),
+                value,
+                kind: "integer".to_string(),
+            }];
+            let args = serde_wasm_bindgen::to_value(
+                &json!({ "directoryPath": directory_path, "fields": fields }),
+            )
+            .unwrap();
+            let audio_offset_status = audio_offset_status.clone();
+            let history_refresh = history_refresh.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match tauri_try_invoke("write_project_details", args).await {
+                    Ok(_) => {
+                        audio_offset_status.set(Some("Saved".to_string()));
+                        history_refresh.set((*history_refresh).wrapping_add(1));
+                    }
+                    Err(e) => audio_offset_status.set(Some(invoke_error(e))),
+                }
+            });
+        })
+    };
+
+    let on_volume_change = {
+        let audio_volume = audio_volume.clone();
+        Callback::from(move |e: web_sys::InputEvent| {
+            if let Some(target) = e.target() {
+                if let Ok(input) = target.dyn_into::<web_sys::HtmlInputElement>() {
+                    let vol = input.value_as_number();
+                    if vol.is_finite() {
+                        audio_volume.set(vol.clamp(0.0, 1.0));
+                    }
+                }
+            }
+        })
+    };
+
+    let on_toggle_mute = {
+        let audio_muted = audio_muted.clone();
+        Callback::from(move |_| {
+            audio_muted.set(!*audio_muted);
+        })
+    };
+
+    let on_step_backward = {
+        let current_index = current_index.clone();
+        let frame_count = frame_count.clone();
+        let is_playing = is_playing.clone();
+        Callback::from(move |_| {
+            if *is_playing {
+                is_playing.set(false);
             }
             let total_frames = *frame_count;
             if total_frames > 0 {
@@ -809,13 +2716,19 @@ pub fn ascii_frames_viewer(props: &AsciiFramesViewerProps) -> Html {
 
     let total_frames = *frame_count;
     let current_frame = (*current_index).min(total_frames.saturating_sub(1));
-    let progress = if total_frames > 1 {
-        current_frame as f64 / (total_frames - 1) as f64
+    let last_frame_start = frame_timing.start_seconds(total_frames.saturating_sub(1));
+    let progress = if total_frames > 1 && last_frame_start > 0.0 {
+        frame_timing.start_seconds(current_frame) / last_frame_start
     } else {
         0.0
     };
 
-    let loading_message = "Loading frames...".to_string();
+    let streaming = props.stream.is_some();
+    let loading_message = match *stream_status {
+        Some(ref status) if streaming => status.clone(),
+        _ => "Loading frames...".to_string(),
+    };
+    let stream_status_text = (*stream_status).clone().filter(|_| streaming);
 
     // Color loading progress message (read from RefCell - updated by color loading without re-renders)
     let (color_loaded, color_total) = *color_progress.borrow();
@@ -826,30 +2739,40 @@ pub fn ascii_frames_viewer(props: &AsciiFramesViewerProps) -> Html {
         None
     };
     let font_size_style = {
-        let font_size = *calculated_font_size;
-        let sizing = FontSizing::default();
-        let line_height_px = sizing.line_height(font_size);
+        let font_size = calculated_font_size;
+        let (_, line_height_px) = glyph_metrics.cell_size(font_size);
         let frames = frames_ref.borrow();
         if let Some(frame) = frames.get(current_frame) {
             let (cols, rows) = frame.dimensions();
-            let (w, h) = sizing.canvas_dimensions(cols, rows, font_size);
+            let (w, h) = glyph_metrics.canvas_dimensions(cols, rows, font_size);
             format!(
-                "font-size: {:.2}px; line-height: {:.2}px; width: {:.2}px; height: {:.2}px; padding: 0;",
-                font_size, line_height_px, w, h
+                "font-family: {}; font-size: {:.2}px; line-height: {:.2}px; width: {:.2}px; height: {:.2}px; padding: 0;",
+                font_family, font_size, line_height_px, w, h
             )
         } else {
-            format!("font-size: {:.2}px; line-height: {:.2}px;", font_size, line_height_px)
+            format!("font-family: {}; font-size: {:.2}px; line-height: {:.2}px;", font_family, font_size, line_height_px)
         }
     };
+    // Zoom, pan and non-font scaling, shared by the text and canvas elements
+    let view_style = {
+        let frames = frames_ref.borrow();
+        let (cols, rows) = frames.first().map(|f| f.dimensions()).unwrap_or((0, 0));
+        let layout = view_scale.layout(&glyph_metrics, cols, rows, *container_size);
+        view_scale.element_style(&layout)
+    };
 
     // Lucide icon IDs
     let play_icon = if *is_playing { IconId::LucidePause } else { IconId::LucidePlay };
     let mute_icon_id = if *audio_muted { IconId::LucideVolumeX } else { IconId::LucideVolume2 };
     let overlay_icon_id = if *overlay_hidden { IconId::LucideEyeOff } else { IconId::LucideEye };
     let color_icon = match *color_mode {
-        0 => IconId::LucidePencil,
-        1 => IconId::LucidePaintbrush,
-        _ => IconId::LucideBrush,
+        ColorMode::BlackWhite => IconId::LucidePencil,
+        ColorMode::Details => IconId::LucidePaintbrush,
+        ColorMode::Frames => IconId::LucideBrush,
+        ColorMode::Palette => IconId::LucidePalette,
+        ColorMode::Grayscale => IconId::LucideDroplet,
+        ColorMode::Inverted => IconId::LucideContrast,
+        ColorMode::Gradient => IconId::LucideSunrise,
     };
 
     let on_toggle_color = {
@@ -857,26 +2780,178 @@ pub fn ascii_frames_viewer(props: &AsciiFramesViewerProps) -> Html {
         let color_available = *has_any_color;
         let has_custom = *has_custom_colors;
         Callback::from(move |_| {
-            let next = match *color_mode {
-                0 => {
-                    if has_custom { 1 }
-                    else if color_available { 2 }
-                    else { 0 }
-                }
-                1 => if color_available { 2 } else { 0 },
-                _ => 0,
-            };
-            color_mode.set(next);
+            color_mode.set(color_mode.next(has_custom, color_available));
+        })
+    };
+
+    let on_color_settings = {
+        let color_settings = color_settings.clone();
+        Callback::from(move |settings: ColorSettings| color_settings.set(settings))
+    };
+
+    // Recolored canvases are only valid for the recoloring they were drawn with
+    {
+        let recolor_canvas_cache = recolor_canvas_cache.clone();
+        use_effect_with(recolor.clone(), move |_| {
+            recolor_canvas_cache.borrow_mut().clear();
+        });
+    }
+
+    // Selection coordinates only hold for the current layout
+    {
+        let selection = selection.clone();
+        let selection_box = selection_box.clone();
+        let font_size_key = (calculated_font_size * 100.0) as i32;
+        let container_key = (container_size.0 as i32, container_size.1 as i32);
+        let view = *view_scale;
+        use_effect_with((font_size_key, container_key, view), move |_| {
+            selection.set(None);
+            selection_box.set(None);
+        });
+    }
+
+    // Refresh the history list while the panel is open
+    {
+        let directory_path = props.directory_path.clone();
+        let history = history.clone();
+        let history_status = history_status.clone();
+        use_effect_with((*history_open, directory_path, *reload_tick, *history_refresh), move |(open, directory_path, _, _)| {
+            if *open && !directory_path.is_empty() {
+                let args = serde_wasm_bindgen::to_value(&json!({ "directoryPath": directory_path })).unwrap();
+                wasm_bindgen_futures::spawn_local(async move {
+                    match tauri_try_invoke("get_history", args).await {
+                        Ok(state) => {
+                            if let Ok(state) = serde_wasm_bindgen::from_value::<HistoryState>(state) {
+                                history.set(state);
+                            }
+                        }
+                        Err(e) => history_status.set(Some(invoke_error(e))),
+                    }
+                });
+            }
+            || ()
+        });
+    }
+
+    // Load details.toml keys when the settings panel opens or the project reloads
+    {
+        let directory_path = props.directory_path.clone();
+        let settings_fields = settings_fields.clone();
+        let settings_status = settings_status.clone();
+        use_effect_with((*settings_open, directory_path, *reload_tick, *history_refresh), move |(open, directory_path, _, _)| {
+            if *open && !directory_path.is_empty() {
+                let args = serde_wasm_bindgen::to_value(&json!({ "directoryPath": directory_path })).unwrap();
+                wasm_bindgen_futures::spawn_local(async move {
+                    match tauri_try_invoke("read_details_fields", args).await {
+                        Ok(fields) => {
+                            let fields = serde_wasm_bindgen::from_value::<Vec<DetailsField>>(fields).unwrap_or_default();
+                            settings_fields.set(with_known_fields(fields));
+                        }
+                        Err(e) => settings_status.set(Some(invoke_error(e))),
+                    }
+                });
+            }
+            || ()
+        });
+    }
+
+    let on_toggle_settings = {
+        let settings_open = settings_open.clone();
+        let settings_status = settings_status.clone();
+        Callback::from(move |_| {
+            settings_status.set(None);
+            settings_open.set(!*settings_open);
+        })
+    };
+
+    let on_app_settings_error = {
+        let settings_status = settings_status.clone();
+        Callback::from(move |e: String| settings_status.set(Some(e)))
+    };
+
+    let on_settings_input = {
+        let settings_fields = settings_fields.clone();
+        Callback::from(move |(idx, value): (usize, String)| {
+            let mut fields = (*settings_fields).clone();
+            if let Some(field) = fields.get_mut(idx) {
+                field.value = value;
+            }
+            settings_fields.set(fields);
+        })
+    };
+
+    // Write the settings back to details.toml, then reload so they take effect
+    let on_settings_save = {
+        let directory_path = props.directory_path.clone();
+        let settings_fields = settings_fields.clone();
+        let settings_status = settings_status.clone();
+        let dirty_frames = dirty_frames.clone();
+        let dirty_colors = dirty_colors.clone();
+        let reload_tick = reload_tick.clone();
+        let pending_index = pending_index.clone();
+        let current_index = current_index.clone();
+        Callback::from(move |_| {
+            let fields = (*settings_fields).clone();
+            if let Err(e) = validate_fields(&fields) {
+                settings_status.set(Some(e));
+                return;
+            }
+            if !dirty_frames.borrow().is_empty() || !dirty_colors.borrow().is_empty() {
+                let confirmed = web_sys::window()
+                    .and_then(|w| w.confirm_with_message("This reloads the frames. Discard unsaved frame edits?").ok())
+                    .unwrap_or(true);
+                if !confirmed {
+                    return;
+                }
+            }
+
+            let args = serde_wasm_bindgen::to_value(
+                &json!({ "directoryPath": directory_path, "fields": fields }),
+            )
+            .unwrap();
+            let settings_status = settings_status.clone();
+            let reload_tick = reload_tick.clone();
+            let pending_index = pending_index.clone();
+            let idx = *current_index;
+            wasm_bindgen_futures::spawn_local(async move {
+                match tauri_try_invoke("write_project_details", args).await {
+                    Ok(_) => {
+                        settings_status.set(Some("Saved details.toml".to_string()));
+                        *pending_index.borrow_mut() = Some(idx);
+                        reload_tick.set((*reload_tick).wrapping_add(1));
+                    }
+                    Err(e) => settings_status.set(Some(invoke_error(e))),
+                }
+            });
+        })
+    };
+
+    let on_toggle_history = {
+        let history_open = history_open.clone();
+        Callback::from(move |_| {
+            history_open.set(!*history_open);
         })
     };
 
     let on_clear_click = {
         let on_clear = props.on_clear.clone();
+        let dirty_frames = dirty_frames.clone();
+        let dirty_colors = dirty_colors.clone();
         Callback::from(move |_| {
+            if !dirty_frames.borrow().is_empty() || !dirty_colors.borrow().is_empty() {
+                let confirmed = web_sys::window()
+                    .and_then(|w| w.confirm_with_message("Discard unsaved frame edits?").ok())
+                    .unwrap_or(true);
+                if !confirmed {
+                    return;
+                }
+            }
             on_clear.emit(());
         })
     };
 
+    let on_open_new_window = use_open_viewer_window(props.directory_path.clone());
+
     let on_toggle_overlay = {
         let overlay_hidden = overlay_hidden.clone();
         Callback::from(move |_| {
@@ -900,31 +2975,89 @@ pub fn ascii_frames_viewer(props: &AsciiFramesViewerProps) -> Html {
 
     let color_available = *has_any_color;
 
+    let paint_mode = *editing && edit_tool.is_color_tool();
     let has_colors = {
-        if *color_mode != 2 || !color_available {
+        if paint_mode {
+            true
+        } else if !color_mode.draws_cells() || *editing {
+            false
+        } else if !color_mode.uses_frame_colors() {
+            // Inverted and gradient modes color every frame
+            true
+        } else if !color_available {
             false
         } else {
             let frames = frames_ref.borrow();
-            frames
-                .get(current_frame)
-                .map(|f| f.has_color())
-                .unwrap_or(false)
+            let has_layer = matches!(color_layers.borrow().get(current_frame), Some(Some(_)));
+            has_layer
+                || frames
+                    .get(current_frame)
+                    .map(|f| f.has_color())
+                    .unwrap_or(false)
         }
     };
 
     let audio_data_url = (*audio_src).clone().unwrap_or_default();
     let has_audio = audio_src.is_some();
 
-    let viewer_class = if *overlay_hidden { "ascii-frames-viewer fullscreen-mode" } else { "ascii-frames-viewer" };
-    let show_controls = !*overlay_hidden || *is_hovering;
+    let unsaved_count = dirty_frames.borrow().union(&dirty_colors.borrow()).count();
+    let cursor_box = if *editing {
+        edit_cursor.and_then(|(col, row)| {
+            let (element, container, cols, rows) = frame_geometry()?;
+            Some(cell_rect_box(&element, &container, CellRect { col, row, cols: 1, rows: 1 }, cols, rows))
+        })
+    } else {
+        None
+    };
+
+    let on_insert_frame = {
+        let run_frame_op = run_frame_op.clone();
+        Callback::from(move |_: MouseEvent| {
+            run_frame_op.emit(("insert_blank_frame", json!({ "index": current_frame }), current_frame));
+        })
+    };
+
+    let on_duplicate_frame = {
+        let run_frame_op = run_frame_op.clone();
+        Callback::from(move |_: MouseEvent| {
+            run_frame_op.emit(("duplicate_frame", json!({ "index": current_frame }), current_frame + 1));
+        })
+    };
+
+    let on_delete_frame = {
+        let run_frame_op = run_frame_op.clone();
+        Callback::from(move |_: MouseEvent| {
+            let confirmed = web_sys::window()
+                .and_then(|w| w.confirm_with_message(&format!("Delete frame {}?", current_frame + 1)).ok())
+                .unwrap_or(false);
+            if confirmed {
+                let next_index = current_frame.min(total_frames.saturating_sub(2));
+                run_frame_op.emit(("delete_frame", json!({ "index": current_frame }), next_index));
+            }
+        })
+    };
+
+    let on_move_frame = {
+        let run_frame_op = run_frame_op.clone();
+        Callback::from(move |to: usize| {
+            run_frame_op.emit(("move_frame", json!({ "from": current_frame, "to": to }), to));
+        })
+    };
+
+    let viewer_class = classes!(
+        "ascii-frames-viewer",
+        (*overlay_hidden || widget_mode.active).then_some("fullscreen-mode"),
+        widget_mode.active.then_some("widget-mode"),
+    );
+    let show_controls = !widget_mode.active && !props.hide_controls && (!*overlay_hidden || *is_hovering);
 
     html! {
         <div class={viewer_class} onmouseenter={on_mouse_enter} onmouseleave={on_mouse_leave}>
             if has_audio {
                 <audio ref={audio_ref} src={audio_data_url} preload="auto" style="display: none;"></audio>
             }
-            <div class="frames-display" ref={container_ref} style={
-                if *color_mode == 1 {
+            <div class={classes!("frames-display", (*selecting || *editing).then_some("selecting"), editing.then_some("editing"), (view_scale.pannable() && !*selecting && !*editing && !widget_mode.active).then_some("pannable"))} ref={container_ref} onmousedown={on_select_down} onmousemove={on_select_move} onmouseup={on_select_up.clone()} onmouseleave={on_select_up} onwheel={on_display_wheel} onkeydown={on_edit_keydown} tabindex="0" style={
+                if *color_mode == ColorMode::Details && !widget_mode.active {
                     let bg = (*details_bg_css).clone().unwrap_or_default();
                     let fg = (*details_fg_css).clone().unwrap_or_default();
                     format!("background: {}; color: {};", bg, fg)
@@ -940,18 +3073,24 @@ pub fn ascii_frames_viewer(props: &AsciiFramesViewerProps) -> Html {
                     <div class="no-frames">{"No frames available"}</div>
                 } else {
                     if has_colors {
-                        <canvas ref={canvas_ref.clone()} class="ascii-frame-canvas"></canvas>
+                        <canvas ref={canvas_ref.clone()} class="ascii-frame-canvas" style={view_style.clone()}></canvas>
                     } else {
                         <pre class="ascii-frame-content" style={
-                            if *color_mode == 1 {
+                            if *color_mode == ColorMode::Details {
                                 let fg = (*details_fg_css).clone().unwrap_or_default();
-                                format!("{}; color: {};", font_size_style, fg)
+                                format!("{}; color: {}; {}", font_size_style, fg, view_style)
                             } else {
-                                font_size_style.clone()
+                                format!("{} {}", font_size_style, view_style)
                             }
                         } ref={content_ref.clone()}></pre>
                     }
                 }
+                if let Some((left, top, width, height)) = *selection_box {
+                    <div class="selection-box" style={format!("left: {:.1}px; top: {:.1}px; width: {:.1}px; height: {:.1}px;", left, top, width, height)}></div>
+                }
+                if let Some((left, top, width, height)) = cursor_box {
+                    <div class="edit-cursor" style={format!("left: {:.1}px; top: {:.1}px; width: {:.1}px; height: {:.1}px;", left, top, width, height)}></div>
+                }
             </div>
 
             if show_controls {
@@ -959,7 +3098,12 @@ pub fn ascii_frames_viewer(props: &AsciiFramesViewerProps) -> Html {
                     // Row 1: Progress bar + Play/Pause button (only for multiple frames)
                     if total_frames > 1 {
                         <div class="control-row">
-                            <input id="progress-slider" class="progress" type="range" min="0" max="1" step="0.001" value={progress.to_string()} oninput={on_seek} disabled={total_frames == 0} />
+                            <div class={if waveform.is_some() { "progress-wrap with-waveform" } else { "progress-wrap" }}>
+                                if waveform.is_some() {
+                                    <canvas ref={waveform_ref} class="waveform-canvas"></canvas>
+                                }
+                                <input id="progress-slider" class="progress" type="range" min="0" max="1" step="0.001" value={progress.to_string()} oninput={on_seek} disabled={total_frames == 0} />
+                            </div>
                             <button id="play-pause-btn" class="ctrl-btn play-btn" type="button" onclick={on_toggle_play} disabled={total_frames == 0} title={if *is_playing { "Pause" } else { "Play" }}><Icon icon_id={play_icon} width={"20"} height={"20"} /></button>
                         </div>
                     }
@@ -972,25 +3116,230 @@ pub fn ascii_frames_viewer(props: &AsciiFramesViewerProps) -> Html {
                         </div>
                     }
 
+                    // Per-mode color settings
+                    if *color_mode == ColorMode::Palette && !*editing {
+                        <PaletteControls palettes={palettes.clone()} settings={(*color_settings).clone()} on_change={on_color_settings.clone()} />
+                    }
+                    if *color_mode == ColorMode::Gradient && !*editing {
+                        <GradientControls settings={(*color_settings).clone()} on_change={on_color_settings} />
+                    }
+
+                    // Scale mode and zoom
+                    if total_frames > 0 {
+                        <ViewControls view={*view_scale} on_change={on_view_scale} />
+                    }
+
+                    // Audio offset calibration
+                    if total_frames > 1 && has_audio {
+                        <div class="control-row audio-offset-row">
+                            <label>{"Audio offset (ms):"}</label>
+                            <button class="ctrl-btn copy-format-btn" type="button" onclick={on_audio_offset.reform({ let offset = *audio_offset_ms; move |_| offset - 10 })} title="Audio 10 ms earlier">{"−10"}</button>
+                            <input id="audio-offset-input" type="number" class="fps-input" step="10" value={audio_offset_ms.to_string()} oninput={on_audio_offset_input} title="Positive values delay the audio" />
+                            <button class="ctrl-btn copy-format-btn" type="button" onclick={on_audio_offset.reform({ let offset = *audio_offset_ms; move |_| offset + 10 })} title="Audio 10 ms later">{"+10"}</button>
+                            <button id="audio-offset-save-btn" class="ctrl-btn" type="button" onclick={on_audio_offset_save} title="Save offset to details.toml"><Icon icon_id={IconId::LucideSave} width={"20"} height={"20"} /></button>
+                            if let Some(ref status) = *audio_offset_status {
+                                <span class="info-text">{status.clone()}</span>
+                            }
+                        </div>
+                    }
+
                     // Row 3: FPS, color, clear, forward/backward buttons
                     <div class="control-row">
                         if total_frames > 1 {
                             <label>{"FPS:"}</label>
                             <input id="fps-input" type="number" class="fps-input" value={current_fps.to_string()} min="1" oninput={on_fps_change} />
                         }
-                        <button id="color-btn" class="ctrl-btn color-btn" type="button" onclick={on_toggle_color} title={match *color_mode { 0 => "Black & White", 1 => "Details colors", _ => "Colored frames" }}><Icon icon_id={color_icon} width={"16"} height={"16"} /></button>
+                        <button id="color-btn" class="ctrl-btn color-btn" type="button" onclick={on_toggle_color} title={color_mode.label()}><Icon icon_id={color_icon} width={"16"} height={"16"} /></button>
+                        <button id="edit-btn" class={if *editing { "ctrl-btn active" } else { "ctrl-btn" }} type="button" onclick={on_toggle_editing} disabled={total_frames == 0 || streaming} title={if *editing { "Stop editing" } else { "Edit frame" }}><Icon icon_id={IconId::LucideType} width={"20"} height={"20"} /></button>
+                        <button id="copy-btn" class={if *copy_open { "ctrl-btn active" } else { "ctrl-btn" }} type="button" onclick={on_toggle_copy} title="Copy frame"><Icon icon_id={IconId::LucideCopy} width={"20"} height={"20"} /></button>
+                        <button id="settings-btn" class={if *settings_open { "ctrl-btn active" } else { "ctrl-btn" }} type="button" onclick={on_toggle_settings} title="Project settings"><Icon icon_id={IconId::LucideSettings} width={"20"} height={"20"} /></button>
+                        <button id="history-btn" class={if *history_open { "ctrl-btn active" } else { "ctrl-btn" }} type="button" onclick={on_toggle_history} title="History"><Icon icon_id={IconId::LucideHistory} width={"20"} height={"20"} /></button>
+                        <button id="search-btn" class={if *search_open { "ctrl-btn active" } else { "ctrl-btn" }} type="button" onclick={on_toggle_search} title="Search frames"><Icon icon_id={IconId::LucideSearch} width={"20"} height={"20"} /></button>
+                        <button id="new-window-btn" class="ctrl-btn" type="button" onclick={on_open_new_window} title="Open in new window"><Icon icon_id={IconId::LucideExternalLink} width={"20"} height={"20"} /></button>
+                        <button id="widget-btn" class={if *widget_open { "ctrl-btn active" } else { "ctrl-btn" }} type="button" onclick={on_toggle_widget} disabled={total_frames == 0} title="Desktop widget"><Icon icon_id={IconId::LucidePictureInPicture2} width={"20"} height={"20"} /></button>
                         <button id="hide-overlay-btn" class={if *overlay_hidden { "ctrl-btn active" } else { "ctrl-btn" }} type="button" onclick={on_toggle_overlay} title={if *overlay_hidden { "Show overlay" } else { "Hide overlay" }}><Icon icon_id={overlay_icon_id} width={"20"} height={"20"} /></button>
-                        <button id="clear-btn" class="ctrl-btn" type="button" onclick={on_clear_click} title="Clear"><Icon icon_id={IconId::LucideXCircle} width={"20"} height={"20"} /></button>
+                        <button id="clear-btn" class="ctrl-btn" type="button" onclick={on_clear_click} title={if props.queued > 0 { format!("Next queued project ({} waiting)", props.queued) } else { "Clear".to_string() }}><Icon icon_id={IconId::LucideXCircle} width={"20"} height={"20"} /></button>
                         <span class="info-text">{format!("{}/{}", current_frame + 1, total_frames)}</span>
+                        if total_frames > 1 {
+                            <span class="info-text">{format!("{} / {}", format_time(frame_timing.start_seconds(current_frame)), format_time(frame_timing.total_seconds()))}</span>
+                        }
                         if let Some(ref msg) = color_loading_message {
                             <span class="info-text">{msg.clone()}</span>
                         }
+                        if let Some(status) = stream_status_text {
+                            <span class="info-text stream-status">{status}</span>
+                        }
                         if total_frames > 1 {
                             <div style="flex: 1;"></div>
                             <button id="step-backward-btn" class="ctrl-btn" type="button" onclick={on_step_backward} disabled={total_frames == 0} title="Step backward"><span style="display: inline-flex; transform: scaleX(-1);"><Icon icon_id={IconId::LucideSkipForward} width={"20"} height={"20"} /></span></button>
                             <button id="step-forward-btn" class="ctrl-btn" type="button" onclick={on_step_forward} disabled={total_frames == 0} title="Step forward"><Icon icon_id={IconId::LucideSkipForward} width={"20"} height={"20"} /></button>
                         }
                     </div>
+
+                    if *editing {
+                        <div class="control-row edit-panel">
+                            <button class={if *edit_tool == EditTool::Cursor { "ctrl-btn copy-format-btn active" } else { "ctrl-btn copy-format-btn" }} type="button" onclick={on_edit_tool.reform(|_| EditTool::Cursor)} title="Place cursor, select and type">{"Cursor"}</button>
+                            <button class={if *edit_tool == EditTool::Line { "ctrl-btn copy-format-btn active" } else { "ctrl-btn copy-format-btn" }} type="button" onclick={on_edit_tool.reform(|_| EditTool::Line)} title="Drag to draw a line with the brush">{"Line"}</button>
+                            <button class={if *edit_tool == EditTool::Paint { "ctrl-btn copy-format-btn active" } else { "ctrl-btn copy-format-btn" }} type="button" onclick={on_edit_tool.reform(|_| EditTool::Paint)} title="Drag to paint cells">{"Paint"}</button>
+                            <button class={if *edit_tool == EditTool::Eyedropper { "ctrl-btn copy-format-btn active" } else { "ctrl-btn copy-format-btn" }} type="button" onclick={on_edit_tool.reform(|_| EditTool::Eyedropper)} title="Pick a color from the frame">{"Pick"}</button>
+                            <button class={if *edit_tool == EditTool::FloodFill { "ctrl-btn copy-format-btn active" } else { "ctrl-btn copy-format-btn" }} type="button" onclick={on_edit_tool.reform(|_| EditTool::FloodFill)} title="Recolor a connected region of the same color">{"Bucket"}</button>
+                            <input class="paint-color-input" type="color" value={css_rgb(*paint_color)} oninput={on_paint_color} title="Paint color" />
+                            <label>{"Brush:"}</label>
+                            <input class="fps-input brush-input" type="text" value={brush_char.to_string()} oninput={on_brush_input} />
+                            <button class="ctrl-btn copy-format-btn" type="button" onclick={on_edit_fill} disabled={selection.is_none()} title="Fill the selection with the brush">{"Fill"}</button>
+                            <button class="ctrl-btn copy-format-btn" type="button" onclick={on_edit_copy.reform(|_| ())} title="Copy selection (Ctrl+C)">{"Copy"}</button>
+                            <button class="ctrl-btn copy-format-btn" type="button" onclick={on_edit_paste.reform(|_| ())} disabled={edit_clipboard.borrow().is_none()} title="Paste at cursor (Ctrl+V)">{"Paste"}</button>
+                            <button id="save-btn" class="ctrl-btn" type="button" onclick={on_edit_save.reform(|_| ())} disabled={unsaved_count == 0} title="Save modified frames (Ctrl+S)"><Icon icon_id={IconId::LucideSave} width={"20"} height={"20"} /></button>
+                            if unsaved_count > 0 {
+                                <span class="info-text">{format!("{} unsaved", unsaved_count)}</span>
+                            }
+                            if let Some(ref status) = *edit_status {
+                                <span class="info-text">{status.clone()}</span>
+                            }
+                        </div>
+                    }
+
+                    if *editing {
+                        <div class="control-row frame-ops-panel">
+                            <label>{"Frame:"}</label>
+                            <button class="ctrl-btn copy-format-btn" type="button" onclick={on_insert_frame} title="Insert a blank frame before this one">{"Insert"}</button>
+                            <button class="ctrl-btn copy-format-btn" type="button" onclick={on_duplicate_frame} title="Duplicate this frame">{"Duplicate"}</button>
+                            <button class="ctrl-btn copy-format-btn" type="button" onclick={on_delete_frame} disabled={total_frames < 2} title="Delete this frame">{"Delete"}</button>
+                            <button class="ctrl-btn copy-format-btn" type="button" onclick={on_move_frame.reform(move |_| current_frame.saturating_sub(1))} disabled={current_frame == 0} title="Move this frame one position earlier">{"◀ Move"}</button>
+                            <button class="ctrl-btn copy-format-btn" type="button" onclick={on_move_frame.reform(move |_| current_frame + 1)} disabled={current_frame + 1 >= total_frames} title="Move this frame one position later">{"Move ▶"}</button>
+                        </div>
+                    }
+
+                    if *widget_open {
+                        <WidgetPanel aspect_ratio={widget_aspect_ratio} launch={widget_launch} streaming={props.stream.is_some()} disabled={total_frames == 0} exit_error={widget_mode.error.clone()} on_started={on_widget_started} on_stream_paused={on_stream_paused} />
+                    }
+
+                    if *copy_open {
+                        <div class="control-row copy-panel">
+                            <label>{if selection.is_some() { "Copy selection:" } else { "Copy frame:" }}</label>
+                            { for CopyFormat::ALL.iter().map(|&format| {
+                                let on_copy = on_copy.clone();
+                                html! {
+                                    <button class="ctrl-btn copy-format-btn" type="button" onclick={Callback::from(move |_| on_copy.emit(format))} disabled={total_frames == 0} title={format!("Copy as {}", format.label())}>{format.label()}</button>
+                                }
+                            }) }
+                            <button class={if *selecting { "ctrl-btn copy-format-btn active" } else { "ctrl-btn copy-format-btn" }} type="button" onclick={on_toggle_selecting} title="Drag over the frame to select a region">{"Select"}</button>
+                            if let Some(ref status) = *copy_status {
+                                <span class="info-text">{status.clone()}</span>
+                            }
+                        </div>
+                    }
+
+                    if *settings_open {
+                        <div class="settings-panel">
+                            { for settings_fields.iter().enumerate().map(|(idx, field)| {
+                                let on_settings_input = on_settings_input.clone();
+                                let oninput = Callback::from(move |e: InputEvent| {
+                                    if let Some(input) = e.target_dyn_into::<web_sys::HtmlInputElement>() {
+                                        on_settings_input.emit((idx, input.value()));
+                                    }
+                                });
+                                let input_type = match field.kind.as_str() {
+                                    "integer" | "float" => "number",
+                                    _ => "text",
+                                };
+                                let swatch = (field.is_color() && is_valid_color(field.value.trim()))
+                                    .then(|| FrameColors::from_strings(field.value.trim(), "black").foreground_css());
+                                html! {
+                                    <div class="control-row settings-field">
+                                        <label class="settings-key">{field.key.clone()}</label>
+                                        <input class="settings-input" type={input_type} value={field.value.clone()} list={(field.key == "font").then_some("font-options")} placeholder={match field.key.as_str() {
+                                            "color" | "background_color" => "e.g. white or #ff8800",
+                                            "font" => "system font or fonts/art.ttf",
+                                            _ => "",
+                                        }} {oninput} />
+                                        if let Some(css) = swatch {
+                                            <span class="settings-swatch" style={format!("background: {};", css)}></span>
+                                        }
+                                    </div>
+                                }
+                            }) }
+                            <datalist id="font-options">
+                                { for font_list.project.iter().chain(font_list.system.iter()).map(|font| html! {
+                                    <option value={font.clone()} />
+                                }) }
+                            </datalist>
+                            <AppSettingsPanel remote_status={(*remote_status).clone()} on_error={on_app_settings_error} />
+                            <div class="control-row">
+                                <button id="settings-save-btn" class="ctrl-btn" type="button" onclick={on_settings_save} title="Save details.toml"><Icon icon_id={IconId::LucideSave} width={"20"} height={"20"} /></button>
+                                <span class="info-text">{"Empty values are removed from details.toml"}</span>
+                                if let Some(ref status) = *settings_status {
+                                    <span class="info-text">{status.clone()}</span>
+                                }
+                            </div>
+                        </div>
+                    }
+
+                    if *history_open {
+                        <div class="history-panel">
+                            <div class="control-row">
+                                <button id="undo-btn" class="ctrl-btn" type="button" onclick={run_history.reform(|_| "undo")} disabled={history.cursor == 0} title="Undo (Ctrl+Z)"><Icon icon_id={IconId::LucideUndo} width={"20"} height={"20"} /></button>
+                                <button id="redo-btn" class="ctrl-btn" type="button" onclick={run_history.reform(|_| "redo")} disabled={history.cursor >= history.entries.len()} title="Redo (Ctrl+Shift+Z)"><Icon icon_id={IconId::LucideRedo} width={"20"} height={"20"} /></button>
+                                <span class="info-text">{format!("{} change{}", history.entries.len(), if history.entries.len() == 1 { "" } else { "s" })}</span>
+                                if let Some(ref status) = *history_status {
+                                    <span class="info-text">{status.clone()}</span>
+                                }
+                            </div>
+                            if !history.entries.is_empty() {
+                                <ul class="history-list">
+                                    { for history.entries.iter().enumerate().rev().map(|(i, entry)| {
+                                        let undone = i >= history.cursor;
+                                        html! {
+                                            <li class={if undone { "history-entry undone" } else { "history-entry" }}>
+                                                <span class="history-entry-label">{entry.label.clone()}</span>
+                                                <span class="history-entry-files">{format!("{} file{}", entry.files, if entry.files == 1 { "" } else { "s" })}</span>
+                                                <span class="history-entry-time">{format_timestamp(entry.timestamp)}</span>
+                                            </li>
+                                        }
+                                    }) }
+                                </ul>
+                            }
+                        </div>
+                    }
+
+                    if *search_open {
+                        <div class="search-panel">
+                            <div class="control-row">
+                                <textarea id="search-input" class="search-input" rows={if search_options.block { "3" } else { "1" }} placeholder={if search_options.block { "Search block (one line per row)" } else { "Search frames" }} value={(*search_query).clone()} oninput={on_search_input} onkeydown={on_search_keydown}></textarea>
+                                <button class={if search_options.mode == SearchMode::Regex { "ctrl-btn search-toggle active" } else { "ctrl-btn search-toggle" }} type="button" onclick={on_search_regex} title="Regular expression">{".*"}</button>
+                                <button class={if search_options.case_sensitive { "ctrl-btn search-toggle active" } else { "ctrl-btn search-toggle" }} type="button" onclick={on_search_case} title="Match case">{"Aa"}</button>
+                                <button class={if search_options.block { "ctrl-btn search-toggle active" } else { "ctrl-btn search-toggle" }} type="button" onclick={on_search_block} title="Multi-line block match">{"¶"}</button>
+                                <button class="ctrl-btn" type="button" onclick={on_search_prev.reform(|_| ())} disabled={search_results.is_empty()} title="Previous match"><Icon icon_id={IconId::LucideChevronUp} width={"20"} height={"20"} /></button>
+                                <button class="ctrl-btn" type="button" onclick={on_search_next.reform(|_| ())} disabled={search_results.is_empty()} title="Next match"><Icon icon_id={IconId::LucideChevronDown} width={"20"} height={"20"} /></button>
+                                <span class="info-text">{
+                                    match *search_cursor {
+                                        Some(pos) => format!("{}/{}", pos + 1, search_results.len()),
+                                        None => format!("{} frames", search_results.len()),
+                                    }
+                                }</span>
+                            </div>
+                            if let Some(ref error) = *search_error {
+                                <div class="search-error">{error.clone()}</div>
+                            } else if !search_results.is_empty() {
+                                <ul class="search-results">
+                                    { for search_results.iter().enumerate().map(|(pos, result)| {
+                                        let jump_to_match = jump_to_match.clone();
+                                        let selected = *search_cursor == Some(pos);
+                                        html! {
+                                            <li class={if selected { "search-result selected" } else { "search-result" }} onclick={Callback::from(move |_| jump_to_match.emit(pos))}>
+                                                <span class="search-result-frame">{format!("#{}", result.frame_index + 1)}</span>
+                                                <span class="search-result-pos">{format!("{}:{}", result.line + 1, result.column + 1)}</span>
+                                                if result.count > 1 {
+                                                    <span class="search-result-count">{format!("×{}", result.count)}</span>
+                                                }
+                                                <code class="search-result-context">{result.context.clone()}</code>
+                                            </li>
+                                        }
+                                    }) }
+                                </ul>
+                            }
+                        </div>
+                    }
                 </div>
             }
         </div>
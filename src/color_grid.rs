@@ -0,0 +1,95 @@
+use cascii_core_view::Frame;
+use serde::{Deserialize, Serialize};
+
+pub type Rgb = (u8, u8, u8);
+
+/// Per-cell RGB colors, laid out like the `.colors` file body (row-major RGB).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ColorGrid {
+    pub width: u32,
+    pub height: u32,
+    pub rgb: Vec<u8>,
+}
+
+impl ColorGrid {
+    /// A grid of the given size with every cell set to `color`.
+    pub fn filled(width: usize, height: usize, color: Rgb) -> Self {
+        ColorGrid {
+            width: width as u32,
+            height: height as u32,
+            rgb: [color.0, color.1, color.2].repeat(width * height),
+        }
+    }
+
+    /// Color data of a frame loaded from its `.cframe` file, if any.
+    pub fn from_frame(frame: &Frame) -> Option<Self> {
+        frame.cframe.as_ref().map(|cframe| ColorGrid {
+            width: cframe.width,
+            height: cframe.height,
+            rgb: cframe.rgb.clone(),
+        })
+    }
+
+    pub fn get(&self, col: usize, row: usize) -> Option<Rgb> {
+        if col >= self.width as usize || row >= self.height as usize {
+            return None;
+        }
+        let i = (row * self.width as usize + col) * 3;
+        self.rgb.get(i..i + 3).map(|c| (c[0], c[1], c[2]))
+    }
+
+    pub fn set(&mut self, col: usize, row: usize, color: Rgb) {
+        if col >= self.width as usize || row >= self.height as usize {
+            return;
+        }
+        let i = (row * self.width as usize + col) * 3;
+        if let Some(cell) = self.rgb.get_mut(i..i + 3) {
+            cell.copy_from_slice(&[color.0, color.1, color.2]);
+        }
+    }
+
+    /// Recolor the 4-connected region of cells sharing the color at (col, row).
+    pub fn flood_fill(&mut self, col: usize, row: usize, color: Rgb) {
+        let Some(target) = self.get(col, row) else {
+            return;
+        };
+        if target == color {
+            return;
+        }
+
+        let (width, height) = (self.width as usize, self.height as usize);
+        let mut stack = vec![(col, row)];
+        while let Some((x, y)) = stack.pop() {
+            if self.get(x, y) != Some(target) {
+                continue;
+            }
+            self.set(x, y, color);
+            if x > 0 {
+                stack.push((x - 1, y));
+            }
+            if x + 1 < width {
+                stack.push((x + 1, y));
+            }
+            if y > 0 {
+                stack.push((x, y - 1));
+            }
+            if y + 1 < height {
+                stack.push((x, y + 1));
+            }
+        }
+    }
+}
+
+pub fn css_rgb((r, g, b): Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Parse a `#rrggbb` color as produced by `<input type="color">`.
+pub fn parse_hex_rgb(value: &str) -> Option<Rgb> {
+    let hex = value.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some((channel(0)?, channel(2)?, channel(4)?))
+}
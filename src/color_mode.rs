@@ -0,0 +1,323 @@
+use yew::prelude::*;
+
+use crate::color_grid::{css_rgb, parse_hex_rgb, ColorGrid, Rgb};
+use crate::palette::Palette;
+
+/// How frames are colored on screen, in the order the color button cycles through.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ColorMode {
+    #[default]
+    BlackWhite,
+    /// Foreground/background from details.toml
+    Details,
+    /// Per-cell colors from `.cframe`/`.colors` files
+    Frames,
+    Palette,
+    Grayscale,
+    Inverted,
+    Gradient,
+}
+
+impl ColorMode {
+    const CYCLE: [ColorMode; 7] = [
+        ColorMode::BlackWhite,
+        ColorMode::Details,
+        ColorMode::Frames,
+        ColorMode::Palette,
+        ColorMode::Grayscale,
+        ColorMode::Inverted,
+        ColorMode::Gradient,
+    ];
+
+    /// The mode after this one, skipping modes the project can't show:
+    /// details colors need non-default details.toml colors, and the frame color
+    /// modes need at least one colored frame.
+    pub fn next(self, has_custom_colors: bool, has_frame_colors: bool) -> Self {
+        let position = Self::CYCLE.iter().position(|&m| m == self).unwrap_or(0);
+        Self::CYCLE
+            .iter()
+            .cycle()
+            .skip(position + 1)
+            .take(Self::CYCLE.len())
+            .copied()
+            .find(|mode| match mode {
+                ColorMode::Details => has_custom_colors,
+                _ if mode.uses_frame_colors() => has_frame_colors,
+                _ => true,
+            })
+            .unwrap_or(ColorMode::BlackWhite)
+    }
+
+    /// Mode named by `--color-mode` on the command line.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "bw" | "black-white" => Some(ColorMode::BlackWhite),
+            "details" => Some(ColorMode::Details),
+            "colored" | "frames" => Some(ColorMode::Frames),
+            "palette" => Some(ColorMode::Palette),
+            "grayscale" => Some(ColorMode::Grayscale),
+            "inverted" => Some(ColorMode::Inverted),
+            "gradient" => Some(ColorMode::Gradient),
+            _ => None,
+        }
+    }
+
+    /// Canonical `--color-mode` name, also used by the remote control.
+    pub fn name(self) -> &'static str {
+        match self {
+            ColorMode::BlackWhite => "bw",
+            ColorMode::Details => "details",
+            ColorMode::Frames => "colored",
+            ColorMode::Palette => "palette",
+            ColorMode::Grayscale => "grayscale",
+            ColorMode::Inverted => "inverted",
+            ColorMode::Gradient => "gradient",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ColorMode::BlackWhite => "Black & White",
+            ColorMode::Details => "Details colors",
+            ColorMode::Frames => "Colored frames",
+            ColorMode::Palette => "Palette",
+            ColorMode::Grayscale => "Grayscale",
+            ColorMode::Inverted => "Inverted",
+            ColorMode::Gradient => "Gradient",
+        }
+    }
+
+    /// Modes that show the colors stored with the frames (possibly recolored).
+    pub fn uses_frame_colors(self) -> bool {
+        matches!(self, ColorMode::Frames | ColorMode::Palette | ColorMode::Grayscale)
+    }
+
+    /// Modes that rasterize frames instead of showing plain text.
+    pub fn draws_cells(self) -> bool {
+        self.uses_frame_colors() || matches!(self, ColorMode::Inverted | ColorMode::Gradient)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GradientKind {
+    Vertical,
+    Horizontal,
+    Radial,
+    /// Diagonal hue sweep that moves with the animation time
+    Rainbow,
+}
+
+impl GradientKind {
+    pub const ALL: [GradientKind; 4] = [
+        GradientKind::Vertical,
+        GradientKind::Horizontal,
+        GradientKind::Radial,
+        GradientKind::Rainbow,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            GradientKind::Vertical => "Vertical",
+            GradientKind::Horizontal => "Horizontal",
+            GradientKind::Radial => "Radial",
+            GradientKind::Rainbow => "Rainbow",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Gradient {
+    pub kind: GradientKind,
+    pub from: Rgb,
+    pub to: Rgb,
+}
+
+/// Rainbow hue shift per second of animation, in degrees.
+const RAINBOW_DEGREES_PER_SECOND: f64 = 90.0;
+
+impl Gradient {
+    /// Colors for a `cols` x `rows` frame shown `seconds` into the animation.
+    pub fn grid(&self, cols: usize, rows: usize, seconds: f64) -> ColorGrid {
+        let mut grid = ColorGrid::filled(cols, rows, self.from);
+        let span = |n: usize| n.saturating_sub(1).max(1) as f64;
+        for row in 0..rows {
+            for col in 0..cols {
+                let x = col as f64 / span(cols);
+                let y = row as f64 / span(rows);
+                let color = match self.kind {
+                    GradientKind::Vertical => lerp(self.from, self.to, y),
+                    GradientKind::Horizontal => lerp(self.from, self.to, x),
+                    GradientKind::Radial => {
+                        // Measured in cell widths; cells are about twice as tall as wide
+                        let (half_w, half_h) = (cols as f64 / 2.0, rows as f64);
+                        let (dx, dy) = ((x - 0.5) * 2.0 * half_w, (y - 0.5) * 2.0 * half_h);
+                        let reach = half_w.hypot(half_h).max(1.0);
+                        lerp(self.from, self.to, (dx.hypot(dy) / reach).min(1.0))
+                    }
+                    GradientKind::Rainbow => {
+                        hue((x + y) * 180.0 + seconds * RAINBOW_DEGREES_PER_SECOND)
+                    }
+                };
+                grid.set(col, row, color);
+            }
+        }
+        grid
+    }
+}
+
+impl Default for Gradient {
+    fn default() -> Self {
+        Gradient {
+            kind: GradientKind::Vertical,
+            from: (0x00, 0xd7, 0xff),
+            to: (0xff, 0x5f, 0xd7),
+        }
+    }
+}
+
+/// Settings of the modes that have any, kept while cycling through other modes.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct ColorSettings {
+    /// Index into the palette list (presets followed by the project palette)
+    pub palette: usize,
+    pub dither: bool,
+    pub gradient: Gradient,
+}
+
+/// Recoloring applied to frame colors before they are rasterized.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Recolor {
+    Palette { palette: Palette, dither: bool },
+    Grayscale,
+    Inverted,
+}
+
+impl Recolor {
+    /// The recoloring `mode` applies with `settings`, picking from `palettes`.
+    pub fn for_mode(mode: ColorMode, settings: &ColorSettings, palettes: &[Palette]) -> Option<Self> {
+        match mode {
+            ColorMode::Palette => palettes.get(settings.palette).map(|palette| Recolor::Palette {
+                palette: palette.clone(),
+                dither: settings.dither,
+            }),
+            ColorMode::Grayscale => Some(Recolor::Grayscale),
+            ColorMode::Inverted => Some(Recolor::Inverted),
+            _ => None,
+        }
+    }
+
+    /// Recolor row-major RGB cells in place, `width` cells per row.
+    pub fn apply(&self, rgb: &mut [u8], width: usize) {
+        match self {
+            Recolor::Palette { palette, dither } => palette.remap(rgb, width, *dither),
+            Recolor::Grayscale => {
+                for cell in rgb.chunks_exact_mut(3) {
+                    let gray = luminance((cell[0], cell[1], cell[2]));
+                    cell.copy_from_slice(&[gray, gray, gray]);
+                }
+            }
+            Recolor::Inverted => {
+                for channel in rgb.iter_mut() {
+                    *channel = 255 - *channel;
+                }
+            }
+        }
+    }
+
+    pub fn grid(&self, grid: &ColorGrid) -> ColorGrid {
+        let mut grid = grid.clone();
+        self.apply(&mut grid.rgb, grid.width as usize);
+        grid
+    }
+
+    /// A single color recolored, e.g. the background behind the cells.
+    pub fn color(&self, color: Rgb) -> Rgb {
+        let mut rgb = [color.0, color.1, color.2];
+        self.apply(&mut rgb, 1);
+        (rgb[0], rgb[1], rgb[2])
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct GradientControlsProps {
+    pub settings: ColorSettings,
+    pub on_change: Callback<ColorSettings>,
+}
+
+/// Gradient kind and end colors shown in gradient mode.
+#[function_component(GradientControls)]
+pub fn gradient_controls(props: &GradientControlsProps) -> Html {
+    let gradient = props.settings.gradient;
+    let with_gradient = {
+        let settings = props.settings.clone();
+        move |gradient: Gradient| ColorSettings { gradient, ..settings.clone() }
+    };
+
+    let on_kind = {
+        let on_change = props.on_change.clone();
+        let with_gradient = with_gradient.clone();
+        Callback::from(move |e: Event| {
+            if let Some(select) = e.target_dyn_into::<web_sys::HtmlSelectElement>() {
+                if let Some(&kind) = GradientKind::ALL.iter().find(|k| k.label() == select.value()) {
+                    on_change.emit(with_gradient(Gradient { kind, ..gradient }));
+                }
+            }
+        })
+    };
+
+    // (is_end, event) for the two color pickers
+    let on_color = {
+        let on_change = props.on_change.clone();
+        Callback::from(move |(is_end, e): (bool, InputEvent)| {
+            if let Some(input) = e.target_dyn_into::<web_sys::HtmlInputElement>() {
+                if let Some(color) = parse_hex_rgb(&input.value()) {
+                    let gradient = if is_end {
+                        Gradient { to: color, ..gradient }
+                    } else {
+                        Gradient { from: color, ..gradient }
+                    };
+                    on_change.emit(with_gradient(gradient));
+                }
+            }
+        })
+    };
+
+    html! {
+        <div class="control-row gradient-row">
+            <label for="gradient-select">{"Gradient:"}</label>
+            <select id="gradient-select" class="palette-select" onchange={on_kind}>
+                { for GradientKind::ALL.iter().map(|kind| html! {
+                    <option value={kind.label()} selected={*kind == gradient.kind}>{kind.label()}</option>
+                }) }
+            </select>
+            if gradient.kind != GradientKind::Rainbow {
+                <input class="paint-color-input" type="color" value={css_rgb(gradient.from)} oninput={on_color.reform(|e| (false, e))} title="Gradient start color" />
+                <input class="paint-color-input" type="color" value={css_rgb(gradient.to)} oninput={on_color.reform(|e| (true, e))} title="Gradient end color" />
+            }
+        </div>
+    }
+}
+
+/// Rec. 709 luma.
+fn luminance((r, g, b): Rgb) -> u8 {
+    (0.2126 * r as f64 + 0.7152 * g as f64 + 0.0722 * b as f64).round() as u8
+}
+
+fn lerp(from: Rgb, to: Rgb, t: f64) -> Rgb {
+    let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+    (mix(from.0, to.0), mix(from.1, to.1), mix(from.2, to.2))
+}
+
+/// Fully saturated color at `degrees` on the hue wheel.
+fn hue(degrees: f64) -> Rgb {
+    let h = degrees.rem_euclid(360.0) / 60.0;
+    let x = ((1.0 - (h % 2.0 - 1.0).abs()) * 255.0).round() as u8;
+    match h as u32 {
+        0 => (255, x, 0),
+        1 => (x, 255, 0),
+        2 => (0, 255, x),
+        3 => (0, x, 255),
+        4 => (x, 0, 255),
+        _ => (255, 0, x),
+    }
+}
@@ -0,0 +1,128 @@
+use crate::frame_export::CellRect;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum EditTool {
+    /// Click to place the cursor, drag to select a rectangle, type to write.
+    #[default]
+    Cursor,
+    /// Drag from one cell to another to draw a line with the brush character.
+    Line,
+    /// Drag to paint cells with the paint color.
+    Paint,
+    /// Click a cell to pick its color.
+    Eyedropper,
+    /// Recolor the connected region of same-colored cells.
+    FloodFill,
+}
+
+impl EditTool {
+    /// Tools that change colors rather than characters
+    pub fn is_color_tool(self) -> bool {
+        matches!(self, EditTool::Paint | EditTool::Eyedropper | EditTool::FloodFill)
+    }
+}
+
+/// Editable character grid built from a frame's text content.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct TextGrid {
+    rows: Vec<Vec<char>>,
+    trailing_newline: bool,
+}
+
+impl TextGrid {
+    pub fn parse(content: &str) -> Self {
+        TextGrid {
+            rows: content.lines().map(|l| l.chars().collect()).collect(),
+            trailing_newline: content.ends_with('\n'),
+        }
+    }
+
+    /// Serialize back to text, keeping the original trailing newline (if any).
+    pub fn to_content(&self) -> String {
+        let mut content = self
+            .rows
+            .iter()
+            .map(|row| row.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n");
+        if self.trailing_newline {
+            content.push('\n');
+        }
+        content
+    }
+
+    pub fn get(&self, col: usize, row: usize) -> char {
+        self.rows
+            .get(row)
+            .and_then(|r| r.get(col))
+            .copied()
+            .unwrap_or(' ')
+    }
+
+    /// Write a character, padding the grid with spaces if the cell doesn't exist yet.
+    pub fn set(&mut self, col: usize, row: usize, ch: char) {
+        if self.rows.len() <= row {
+            self.rows.resize(row + 1, Vec::new());
+        }
+        let line = &mut self.rows[row];
+        if line.len() <= col {
+            line.resize(col + 1, ' ');
+        }
+        line[col] = ch;
+    }
+
+    pub fn fill(&mut self, rect: CellRect, ch: char) {
+        for row in rect.row..rect.row + rect.rows {
+            for col in rect.col..rect.col + rect.cols {
+                self.set(col, row, ch);
+            }
+        }
+    }
+
+    pub fn copy(&self, rect: CellRect) -> TextGrid {
+        TextGrid {
+            rows: (rect.row..rect.row + rect.rows)
+                .map(|row| (rect.col..rect.col + rect.cols).map(|col| self.get(col, row)).collect())
+                .collect(),
+            trailing_newline: false,
+        }
+    }
+
+    /// Paste `block` with its top-left corner at (col, row), clipped to `bounds`.
+    pub fn paste(&mut self, col: usize, row: usize, block: &TextGrid, bounds: (usize, usize)) {
+        for (dy, line) in block.rows.iter().enumerate() {
+            for (dx, &ch) in line.iter().enumerate() {
+                let (x, y) = (col + dx, row + dy);
+                if x < bounds.0 && y < bounds.1 {
+                    self.set(x, y, ch);
+                }
+            }
+        }
+    }
+
+    /// Draw a straight line between two cells (Bresenham).
+    pub fn line(&mut self, from: (usize, usize), to: (usize, usize), ch: char) {
+        let (mut x, mut y) = (from.0 as i64, from.1 as i64);
+        let (x1, y1) = (to.0 as i64, to.1 as i64);
+        let dx = (x1 - x).abs();
+        let dy = -(y1 - y).abs();
+        let sx = if x < x1 { 1 } else { -1 };
+        let sy = if y < y1 { 1 } else { -1 };
+        let mut err = dx + dy;
+        loop {
+            self.set(x as usize, y as usize, ch);
+            if x == x1 && y == y1 {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+    }
+}
@@ -0,0 +1,257 @@
+use wasm_bindgen::JsCast;
+
+use crate::color_grid::{css_rgb, ColorGrid, Rgb};
+use crate::glyph_metrics::css_font;
+
+/// Line height used when rasterizing frames, relative to the font size.
+const PNG_LINE_HEIGHT: f64 = 1.2;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CopyFormat {
+    Text,
+    Ansi,
+    Html,
+    Png,
+}
+
+impl CopyFormat {
+    pub const ALL: [CopyFormat; 4] = [CopyFormat::Text, CopyFormat::Ansi, CopyFormat::Html, CopyFormat::Png];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CopyFormat::Text => "Text",
+            CopyFormat::Ansi => "ANSI",
+            CopyFormat::Html => "HTML",
+            CopyFormat::Png => "PNG",
+        }
+    }
+
+    /// Look a format up by its label, ignoring case ("png", "ansi").
+    pub fn from_name(name: &str) -> Option<Self> {
+        CopyFormat::ALL
+            .into_iter()
+            .find(|format| format.label().eq_ignore_ascii_case(name))
+    }
+}
+
+/// A rectangular block of character cells.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CellRect {
+    pub col: usize,
+    pub row: usize,
+    pub cols: usize,
+    pub rows: usize,
+}
+
+impl CellRect {
+    /// Rectangle spanning two corner cells (inclusive), in any order.
+    pub fn from_corners(a: (usize, usize), b: (usize, usize)) -> Self {
+        let (col, row) = (a.0.min(b.0), a.1.min(b.1));
+        CellRect {
+            col,
+            row,
+            cols: a.0.max(b.0) - col + 1,
+            rows: a.1.max(b.1) - row + 1,
+        }
+    }
+}
+
+type Cell = (char, Option<Rgb>);
+
+/// Collect the frame cells inside `rect` (or the whole frame), padding short
+/// lines with spaces so a selection always stays rectangular.
+fn collect_cells(content: &str, colors: Option<&ColorGrid>, rect: Option<CellRect>) -> Vec<Vec<Cell>> {
+    let lines: Vec<Vec<char>> = content.lines().map(|l| l.chars().collect()).collect();
+    let rect = rect.unwrap_or_else(|| CellRect {
+        col: 0,
+        row: 0,
+        cols: lines.iter().map(|l| l.len()).max().unwrap_or(0),
+        rows: lines.len(),
+    });
+
+    (rect.row..rect.row + rect.rows)
+        .map(|row| {
+            (rect.col..rect.col + rect.cols)
+                .map(|col| {
+                    let ch = lines.get(row).and_then(|l| l.get(col)).copied().unwrap_or(' ');
+                    (ch, colors.and_then(|c| c.get(col, row)))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Split a row into runs of consecutive cells sharing the same color.
+fn color_runs(row: &[Cell]) -> Vec<(String, Option<Rgb>)> {
+    let mut runs: Vec<(String, Option<Rgb>)> = Vec::new();
+    for &(ch, color) in row {
+        match runs.last_mut() {
+            Some((text, run_color)) if *run_color == color => text.push(ch),
+            _ => runs.push((ch.to_string(), color)),
+        }
+    }
+    runs
+}
+
+pub fn to_plain_text(content: &str, rect: Option<CellRect>) -> String {
+    collect_cells(content, None, rect)
+        .iter()
+        .map(|row| row.iter().map(|(ch, _)| *ch).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Text with 24-bit ANSI foreground escapes, reset at the end of every line.
+pub fn to_ansi(content: &str, colors: Option<&ColorGrid>, rect: Option<CellRect>) -> String {
+    collect_cells(content, colors, rect)
+        .iter()
+        .map(|row| {
+            let mut line = String::new();
+            for (text, color) in color_runs(row) {
+                match color {
+                    Some((r, g, b)) => line.push_str(&format!("\x1b[38;2;{};{};{}m{}", r, g, b, text)),
+                    None => line.push_str(&format!("\x1b[39m{}", text)),
+                }
+            }
+            line.push_str("\x1b[0m");
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A standalone `<pre>` block with one inline-styled span per color run.
+pub fn to_html(content: &str, colors: Option<&ColorGrid>, rect: Option<CellRect>, fg: Rgb, bg: Rgb) -> String {
+    let mut html = format!(
+        "<pre style=\"font-family: monospace; line-height: 1.1; color: {}; background: {}; padding: 0.5em;\">",
+        css_rgb(fg),
+        css_rgb(bg)
+    );
+    let rows = collect_cells(content, colors, rect);
+    for (i, row) in rows.iter().enumerate() {
+        if i > 0 {
+            html.push('\n');
+        }
+        for (text, color) in color_runs(row) {
+            let escaped = text
+                .replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;");
+            match color {
+                Some(c) if c != fg => {
+                    html.push_str(&format!("<span style=\"color: {}\">{}</span>", css_rgb(c), escaped))
+                }
+                _ => html.push_str(&escaped),
+            }
+        }
+    }
+    html.push_str("</pre>");
+    html
+}
+
+/// How cells are rasterized by [`draw_cells`].
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct RasterStyle<'a> {
+    /// Color of cells without color data
+    pub fg: Rgb,
+    /// Background behind the cells; `None` leaves the canvas transparent
+    pub bg: Option<Rgb>,
+    pub font_size: f64,
+    /// CSS font-family list
+    pub font_family: &'a str,
+    /// Forces the cell box (to match an on-screen layout); measured from the font when `None`.
+    pub cell_size: Option<(f64, f64)>,
+}
+
+/// Rasterize frame cells onto `canvas`, resizing it to fit.
+pub fn draw_cells(
+    canvas: &web_sys::HtmlCanvasElement,
+    content: &str,
+    colors: Option<&ColorGrid>,
+    rect: Option<CellRect>,
+    style: &RasterStyle,
+) -> Result<(), String> {
+    let RasterStyle { fg, bg, font_size, font_family, cell_size } = *style;
+    let rows = collect_cells(content, colors, rect);
+    let cols = rows.first().map(|r| r.len()).unwrap_or(0);
+    if cols == 0 {
+        return Err("Frame is empty".to_string());
+    }
+
+    let ctx = canvas
+        .get_context("2d")
+        .ok()
+        .flatten()
+        .and_then(|c| c.dyn_into::<web_sys::CanvasRenderingContext2d>().ok())
+        .ok_or("Canvas 2D context is not available")?;
+
+    let font = css_font(font_size, font_family);
+    let (cell_width, cell_height) = match cell_size {
+        Some(size) => size,
+        None => {
+            ctx.set_font(&font);
+            let width = ctx
+                .measure_text("M")
+                .map(|m| m.width())
+                .map_err(|e| format!("Failed to measure glyphs: {:?}", e))?;
+            (width, (font_size * PNG_LINE_HEIGHT).ceil())
+        }
+    };
+
+    canvas.set_width((cell_width * cols as f64).ceil() as u32);
+    canvas.set_height((cell_height * rows.len() as f64).ceil() as u32);
+
+    // Resizing a canvas resets its context state
+    ctx.set_font(&font);
+    ctx.set_text_baseline("top");
+    match bg {
+        Some(bg) => {
+            ctx.set_fill_style_str(&css_rgb(bg));
+            ctx.fill_rect(0.0, 0.0, canvas.width() as f64, canvas.height() as f64);
+        }
+        None => ctx.clear_rect(0.0, 0.0, canvas.width() as f64, canvas.height() as f64),
+    }
+
+    for (y, row) in rows.iter().enumerate() {
+        let mut x = 0usize;
+        for (text, color) in color_runs(row) {
+            let len = text.chars().count();
+            if !text.trim().is_empty() {
+                ctx.set_fill_style_str(&css_rgb(color.unwrap_or(fg)));
+                let _ = ctx.fill_text(&text, x as f64 * cell_width, y as f64 * cell_height);
+            }
+            x += len;
+        }
+    }
+    Ok(())
+}
+
+/// Rasterize the cells onto a fresh, detached canvas.
+pub fn render_cells(
+    content: &str,
+    colors: Option<&ColorGrid>,
+    rect: Option<CellRect>,
+    style: &RasterStyle,
+) -> Result<web_sys::HtmlCanvasElement, String> {
+    let canvas = web_sys::window()
+        .and_then(|w| w.document())
+        .ok_or("No document available")?
+        .create_element("canvas")
+        .map_err(|e| format!("Failed to create canvas: {:?}", e))?
+        .dyn_into::<web_sys::HtmlCanvasElement>()
+        .map_err(|_| "Failed to create canvas".to_string())?;
+    draw_cells(&canvas, content, colors, rect, style)?;
+    Ok(canvas)
+}
+
+/// Rasterize the cells onto a fresh canvas and return it as a PNG data URL.
+pub fn to_png_data_url(
+    content: &str,
+    colors: Option<&ColorGrid>,
+    rect: Option<CellRect>,
+    style: &RasterStyle,
+) -> Result<String, String> {
+    render_cells(content, colors, rect, style)?
+        .to_data_url_with_type("image/png")
+        .map_err(|e| format!("Failed to encode PNG: {:?}", e))
+}
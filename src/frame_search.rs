@@ -0,0 +1,234 @@
+use cascii_core_view::Frame;
+use regex::RegexBuilder;
+
+/// Maximum number of characters shown around a match in the results list.
+const CONTEXT_CHARS: usize = 48;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum SearchMode {
+    #[default]
+    Substring,
+    Regex,
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct SearchOptions {
+    pub mode: SearchMode,
+    pub case_sensitive: bool,
+    /// Treat a multi-line query as a block: every query line must appear on
+    /// consecutive frame lines, starting at the same column.
+    pub block: bool,
+}
+
+/// First match found in a frame, plus how many matches the frame contains.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SearchMatch {
+    pub frame_index: usize,
+    pub line: usize,
+    pub column: usize,
+    pub count: usize,
+    pub context: String,
+}
+
+/// Search the text content of every loaded frame.
+/// Returns one entry per matching frame, in frame order.
+pub fn search_frames(
+    frames: &[Frame],
+    query: &str,
+    options: &SearchOptions,
+) -> Result<Vec<SearchMatch>, String> {
+    let contents: Vec<&str> = frames.iter().map(|frame| frame.content.as_str()).collect();
+    search_contents(&contents, query, options)
+}
+
+fn search_contents(contents: &[&str], query: &str, options: &SearchOptions) -> Result<Vec<SearchMatch>, String> {
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut results = Vec::new();
+
+    match options.mode {
+        SearchMode::Substring => {
+            let query_lines: Vec<Vec<char>> = if options.block {
+                query.lines().map(|l| l.chars().collect()).collect()
+            } else {
+                vec![query.chars().collect()]
+            };
+
+            for (frame_index, content) in contents.iter().enumerate() {
+                let lines: Vec<Vec<char>> =
+                    content.lines().map(|l| l.chars().collect()).collect();
+                let hits = find_block(&lines, &query_lines, options.case_sensitive);
+                if let Some(&(line, column)) = hits.first() {
+                    results.push(SearchMatch {
+                        frame_index,
+                        line,
+                        column,
+                        count: hits.len(),
+                        context: context_for(&lines[line], column),
+                    });
+                }
+            }
+        }
+        SearchMode::Regex => {
+            let re = RegexBuilder::new(query)
+                .case_insensitive(!options.case_sensitive)
+                .multi_line(true)
+                .build()
+                .map_err(|e| format!("Invalid regex: {}", e))?;
+
+            for (frame_index, content) in contents.iter().enumerate() {
+                let mut hits = Vec::new();
+                if options.block {
+                    // Match against the whole frame so patterns can span lines
+                    for m in re.find_iter(content) {
+                        hits.push(line_column_at(content, m.start()));
+                    }
+                } else {
+                    for (line, text) in content.lines().enumerate() {
+                        for m in re.find_iter(text) {
+                            hits.push((line, text[..m.start()].chars().count()));
+                        }
+                    }
+                }
+
+                if let Some(&(line, column)) = hits.first() {
+                    let line_chars: Vec<char> = content
+                        .lines()
+                        .nth(line)
+                        .unwrap_or_default()
+                        .chars()
+                        .collect();
+                    results.push(SearchMatch {
+                        frame_index,
+                        line,
+                        column,
+                        count: hits.len(),
+                        context: context_for(&line_chars, column),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(results)
+}
+
+/// Find every (line, column) where `block` appears, each block line aligned
+/// at the same column on consecutive frame lines.
+fn find_block(lines: &[Vec<char>], block: &[Vec<char>], case_sensitive: bool) -> Vec<(usize, usize)> {
+    let mut hits = Vec::new();
+    let Some(first) = block.first() else {
+        return hits;
+    };
+    if first.is_empty() || block.len() > lines.len() {
+        return hits;
+    }
+
+    for row in 0..=(lines.len() - block.len()) {
+        for column in find_in_line(&lines[row], first, case_sensitive) {
+            let rest_matches = block.iter().enumerate().skip(1).all(|(k, needle)| {
+                chars_match_at(&lines[row + k], needle, column, case_sensitive)
+            });
+            if rest_matches {
+                hits.push((row, column));
+            }
+        }
+    }
+    hits
+}
+
+fn find_in_line(line: &[char], needle: &[char], case_sensitive: bool) -> Vec<usize> {
+    if needle.len() > line.len() {
+        return Vec::new();
+    }
+    (0..=(line.len() - needle.len()))
+        .filter(|&column| chars_match_at(line, needle, column, case_sensitive))
+        .collect()
+}
+
+fn chars_match_at(line: &[char], needle: &[char], column: usize, case_sensitive: bool) -> bool {
+    if column + needle.len() > line.len() {
+        return false;
+    }
+    line[column..column + needle.len()]
+        .iter()
+        .zip(needle)
+        .all(|(a, b)| {
+            if case_sensitive {
+                a == b
+            } else {
+                a == b || a.to_lowercase().eq(b.to_lowercase())
+            }
+        })
+}
+
+/// Convert a byte offset into a (line, char column) pair.
+fn line_column_at(content: &str, byte_offset: usize) -> (usize, usize) {
+    let before = &content[..byte_offset];
+    let line = before.matches('\n').count();
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    (line, before[line_start..].chars().count())
+}
+
+fn context_for(line: &[char], column: usize) -> String {
+    let start = column.saturating_sub(CONTEXT_CHARS / 2);
+    let end = (start + CONTEXT_CHARS).min(line.len());
+    let mut context: String = line[start..end].iter().collect();
+    if start > 0 {
+        context.insert(0, '…');
+    }
+    if end < line.len() {
+        context.push('…');
+    }
+    context
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAMES: [&str; 3] = ["  /\\_/\\\n ( o.o )\n  > ^ <\n", "nothing here\n", " ( O.O ) ( o.o )\n"];
+
+    fn search(query: &str, options: SearchOptions) -> Vec<(usize, usize, usize, usize)> {
+        search_contents(&FRAMES, query, &options)
+            .unwrap()
+            .iter()
+            .map(|m| (m.frame_index, m.line, m.column, m.count))
+            .collect()
+    }
+
+    #[test]
+    fn substring_search_counts_matches_per_frame() {
+        assert_eq!(search("o.o", SearchOptions::default()), [(0, 1, 3, 1), (2, 0, 3, 2)]);
+        let exact = SearchOptions { case_sensitive: true, ..Default::default() };
+        assert_eq!(search("o.o", exact), [(0, 1, 3, 1), (2, 0, 11, 1)]);
+        assert!(search("", SearchOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn block_search_needs_aligned_lines() {
+        let block = SearchOptions { block: true, ..Default::default() };
+        assert_eq!(search("( o.o )\n > ^", block.clone()), [(0, 1, 1, 1)]);
+        assert!(search("( o.o )\n> ^", block).is_empty());
+    }
+
+    #[test]
+    fn regex_search_reports_char_columns() {
+        let regex = SearchOptions { mode: SearchMode::Regex, ..Default::default() };
+        assert_eq!(search(r"\(\s\w\.", regex.clone()), [(0, 1, 1, 1), (2, 0, 1, 2)]);
+        let spanning = SearchOptions { block: true, ..regex.clone() };
+        assert_eq!(search(r"\)\n\s+>", spanning), [(0, 1, 7, 1)]);
+        assert!(search_contents(&FRAMES, "(", &regex).is_err());
+    }
+
+    #[test]
+    fn context_is_trimmed_around_the_match() {
+        let line: Vec<char> = "x".repeat(100).chars().collect();
+        let context = context_for(&line, 50);
+        assert!(context.starts_with('…') && context.ends_with('…'));
+        assert_eq!(context.chars().count(), CONTEXT_CHARS + 2);
+        assert_eq!(line_column_at("ab\nçd", 5), (1, 1));
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::VecDeque;
+
+use cascii_core_view::{load_text_frames, Frame, FrameDataProvider, FrameFile, LoadResult};
+use serde::Deserialize;
+
+use crate::color_grid::ColorGrid;
+
+/// How long streamed frames are held by default, in milliseconds.
+pub const DEFAULT_BUFFER_MS: u32 = 100;
+/// Frames waiting beyond this are dropped, oldest first: the sender outpaces the display.
+const MAX_QUEUED: usize = 30;
+/// The one file a live frame pretends to come from.
+const STREAM_FRAME_PATH: &str = "live.txt";
+
+/// A frame pushed to a live stream, as the backend passes it on.
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamFrame {
+    pub text: String,
+    pub colors: Option<ColorGrid>,
+    /// `Date.now()` when the backend read it
+    pub received_at: f64,
+}
+
+/// Evens out the timing of streamed frames. Each frame is shown between one and two
+/// buffer lengths after it arrived, paced by the sender's usual interval, so frames
+/// that arrive in bursts still come out steadily.
+pub struct JitterBuffer {
+    delay_ms: f64,
+    queue: VecDeque<(f64, StreamFrame)>,
+    last_arrival: Option<f64>,
+    /// Smoothed time between arrivals
+    interval_ms: f64,
+    last_due: f64,
+}
+
+impl JitterBuffer {
+    pub fn new(delay_ms: u32) -> Self {
+        JitterBuffer {
+            delay_ms: delay_ms as f64,
+            queue: VecDeque::new(),
+            last_arrival: None,
+            interval_ms: 0.0,
+            last_due: 0.0,
+        }
+    }
+
+    pub fn push(&mut self, frame: StreamFrame) {
+        let arrival = frame.received_at;
+        if let Some(last) = self.last_arrival {
+            // Long pauses (the sender stopped for a while) say nothing about its rate
+            let gap = (arrival - last).clamp(0.0, 1000.0);
+            self.interval_ms = if self.interval_ms == 0.0 { gap } else { self.interval_ms * 0.9 + gap * 0.1 };
+        }
+        self.last_arrival = Some(arrival);
+
+        let earliest = arrival + self.delay_ms;
+        let due = if self.delay_ms > 0.0 {
+            (self.last_due + self.interval_ms).clamp(earliest, earliest + self.delay_ms)
+        } else {
+            earliest
+        };
+        self.last_due = due;
+        self.queue.push_back((due, frame));
+        while self.queue.len() > MAX_QUEUED {
+            self.queue.pop_front();
+        }
+    }
+
+    /// The newest frame due by `now`; older due frames are skipped.
+    pub fn pop_due(&mut self, now: f64) -> Option<StreamFrame> {
+        let mut latest = None;
+        while self.queue.front().is_some_and(|(due, _)| *due <= now) {
+            latest = self.queue.pop_front().map(|(_, frame)| frame);
+        }
+        latest
+    }
+
+    /// When the next waiting frame is due.
+    pub fn next_due(&self) -> Option<f64> {
+        self.queue.front().map(|(due, _)| *due)
+    }
+}
+
+/// Serves a single streamed frame to cascii-core-view's loader, so live frames are
+/// built the same way as frames read from disk.
+pub struct StreamFrameProvider {
+    text: String,
+}
+
+impl StreamFrameProvider {
+    pub async fn frame(text: String) -> LoadResult<Frame> {
+        let provider = StreamFrameProvider { text };
+        let (mut frames, _) = load_text_frames(&provider, "").await?;
+        frames.pop().ok_or_else(|| "Streamed frame could not be loaded".to_string())
+    }
+}
+
+impl FrameDataProvider for StreamFrameProvider {
+    async fn get_frame_files(&self, _directory: &str) -> LoadResult<Vec<FrameFile>> {
+        Ok(vec![FrameFile::new(
+            STREAM_FRAME_PATH.to_string(),
+            STREAM_FRAME_PATH.to_string(),
+            0,
+        )])
+    }
+
+    fn read_frame_text(&self, _path: &str) -> impl std::future::Future<Output = LoadResult<String>> {
+        let text = self.text.clone();
+        async move { Ok(text) }
+    }
+
+    async fn read_cframe_bytes(&self, _txt_path: &str) -> LoadResult<Option<Vec<u8>>> {
+        Ok(None)
+    }
+}
@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+
+/// Frames shown for a fixed time, from the `[timing]` table of details.toml.
+/// `start` and `end` are 0-based and inclusive.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct TimingRange {
+    pub start: usize,
+    pub end: usize,
+    pub duration_ms: u32,
+}
+
+/// When every frame starts, so playback, seeking and audio sync agree on one timeline.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct FrameTiming {
+    /// Start of each frame in milliseconds, plus the end of the last frame
+    starts_ms: Vec<f64>,
+}
+
+impl FrameTiming {
+    /// Frames last 1/fps seconds unless a timing range says otherwise;
+    /// later ranges win where ranges overlap.
+    pub fn new(total_frames: usize, fps: u32, ranges: &[TimingRange]) -> Self {
+        let default_ms = 1000.0 / fps.max(1) as f64;
+        let mut durations = vec![default_ms; total_frames];
+        for range in ranges {
+            for duration in durations.iter_mut().take(range.end + 1).skip(range.start) {
+                *duration = range.duration_ms as f64;
+            }
+        }
+
+        let mut starts_ms = Vec::with_capacity(total_frames + 1);
+        let mut t = 0.0;
+        starts_ms.push(t);
+        for duration in durations {
+            t += duration;
+            starts_ms.push(t);
+        }
+        FrameTiming { starts_ms }
+    }
+
+    pub fn duration_ms(&self, frame: usize) -> f64 {
+        match (self.starts_ms.get(frame), self.starts_ms.get(frame + 1)) {
+            (Some(start), Some(end)) => end - start,
+            _ => 0.0,
+        }
+    }
+
+    pub fn start_seconds(&self, frame: usize) -> f64 {
+        self.starts_ms.get(frame).copied().unwrap_or(0.0) / 1000.0
+    }
+
+    /// Position in the soundtrack matching the start of `frame`. Negative while
+    /// the audio hasn't started yet (positive `audio_offset_ms` delays the audio).
+    pub fn audio_seconds(&self, frame: usize, audio_offset_ms: i32) -> f64 {
+        self.start_seconds(frame) - audio_offset_ms as f64 / 1000.0
+    }
+
+    pub fn total_seconds(&self) -> f64 {
+        self.starts_ms.last().copied().unwrap_or(0.0) / 1000.0
+    }
+
+    /// Frame on screen at `seconds` into the animation.
+    pub fn frame_at(&self, seconds: f64) -> usize {
+        let total_frames = self.starts_ms.len().saturating_sub(1);
+        let ms = seconds * 1000.0;
+        self.starts_ms
+            .partition_point(|&start| start <= ms)
+            .saturating_sub(1)
+            .min(total_frames.saturating_sub(1))
+    }
+}
+
+/// `m:ss.t` for the time display.
+pub fn format_time(seconds: f64) -> String {
+    let tenths = (seconds.max(0.0) * 10.0).round() as u64;
+    format!("{}:{:02}.{}", tenths / 600, (tenths / 10) % 60, tenths % 10)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(start: usize, end: usize, duration_ms: u32) -> TimingRange {
+        TimingRange { start, end, duration_ms }
+    }
+
+    fn starts(timing: &FrameTiming, frames: usize) -> Vec<f64> {
+        (0..=frames).map(|frame| timing.start_seconds(frame)).collect()
+    }
+
+    #[test]
+    fn later_ranges_win_where_they_overlap() {
+        let timing = FrameTiming::new(5, 4, &[range(1, 3, 500), range(2, 2, 1000)]);
+        assert_eq!(starts(&timing, 5), [0.0, 0.25, 0.75, 1.75, 2.25, 2.5]);
+        assert_eq!(timing.duration_ms(2), 1000.0);
+        assert_eq!(timing.total_seconds(), 2.5);
+    }
+
+    #[test]
+    fn ranges_past_the_last_frame_are_cut_off() {
+        let timing = FrameTiming::new(3, 4, &[range(1, 9, 500), range(5, 6, 1000)]);
+        assert_eq!(starts(&timing, 3), [0.0, 0.25, 0.75, 1.25]);
+        assert_eq!(timing.duration_ms(3), 0.0);
+    }
+
+    #[test]
+    fn frame_at_starts_a_frame_on_its_boundary() {
+        let timing = FrameTiming::new(5, 4, &[range(1, 3, 500), range(2, 2, 1000)]);
+        assert_eq!(timing.frame_at(0.0), 0);
+        assert_eq!(timing.frame_at(0.2499), 0);
+        assert_eq!(timing.frame_at(0.25), 1);
+        assert_eq!(timing.frame_at(0.75), 2);
+        assert_eq!(timing.frame_at(1.7499), 2);
+        assert_eq!(timing.frame_at(1.75), 3);
+        assert_eq!(timing.frame_at(-1.0), 0);
+    }
+
+    #[test]
+    fn frame_at_holds_the_last_frame_at_the_end() {
+        let timing = FrameTiming::new(5, 4, &[range(1, 3, 500), range(2, 2, 1000)]);
+        assert_eq!(timing.frame_at(2.25), 4);
+        assert_eq!(timing.frame_at(2.5), 4);
+        assert_eq!(timing.frame_at(60.0), 4);
+        assert_eq!(FrameTiming::new(0, 4, &[]).frame_at(1.0), 0);
+    }
+}
@@ -0,0 +1,167 @@
+use std::rc::Rc;
+
+use serde::Deserialize;
+use serde_json::json;
+use wasm_bindgen::prelude::*;
+use yew::prelude::*;
+
+use crate::ipc::tauri_try_invoke;
+
+#[wasm_bindgen(inline_js = r#"
+export async function loadFontFace(family, source) {
+  const face = new FontFace(family, `url(${source})`);
+  await face.load();
+  document.fonts.add(face);
+}
+"#)]
+extern "C" {
+    #[wasm_bindgen(js_name = loadFontFace, catch)]
+    async fn load_font_face(family: &str, source: &str) -> Result<(), JsValue>;
+}
+
+/// Font size the metrics are measured at; larger sizes round less.
+const MEASURE_SIZE: f64 = 100.0;
+const FONT_FILE_EXTENSIONS: [&str; 4] = [".ttf", ".otf", ".ttc", ".woff2"];
+
+/// Cell metrics of a monospace font relative to its size, measured in the webview
+/// so the text and canvas renderers lay frames out the same way.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct GlyphMetrics {
+    /// Advance width of one character per pixel of font size
+    pub advance: f64,
+    /// Line height per pixel of font size
+    pub line_height: f64,
+}
+
+impl Default for GlyphMetrics {
+    /// Typical monospace proportions, used until the real font has been measured.
+    fn default() -> Self {
+        GlyphMetrics {
+            advance: 0.6,
+            line_height: 1.2,
+        }
+    }
+}
+
+impl GlyphMetrics {
+    /// Measure the advance of "M" and the font's ascent + descent on a scratch canvas.
+    pub fn measure(font_family: &str) -> Option<Self> {
+        let canvas = web_sys::window()?
+            .document()?
+            .create_element("canvas")
+            .ok()?
+            .dyn_into::<web_sys::HtmlCanvasElement>()
+            .ok()?;
+        let ctx = canvas
+            .get_context("2d")
+            .ok()
+            .flatten()?
+            .dyn_into::<web_sys::CanvasRenderingContext2d>()
+            .ok()?;
+        ctx.set_font(&css_font(MEASURE_SIZE, font_family));
+        let metrics = ctx.measure_text("M").ok()?;
+        let line_height = metrics.font_bounding_box_ascent() + metrics.font_bounding_box_descent();
+        if metrics.width() <= 0.0 || line_height <= 0.0 {
+            return None;
+        }
+        Some(GlyphMetrics {
+            advance: metrics.width() / MEASURE_SIZE,
+            line_height: line_height / MEASURE_SIZE,
+        })
+    }
+
+    /// Largest font size at which `cols` x `rows` cells fit in the given box.
+    pub fn fit_font_size(&self, cols: usize, rows: usize, width: f64, height: f64) -> f64 {
+        let by_width = width / (cols.max(1) as f64 * self.advance);
+        let by_height = height / (rows.max(1) as f64 * self.line_height);
+        (by_width.min(by_height) * 100.0).floor().max(100.0) / 100.0
+    }
+
+    /// Smallest font size at which `cols` x `rows` cells cover the given box.
+    pub fn cover_font_size(&self, cols: usize, rows: usize, width: f64, height: f64) -> f64 {
+        let by_width = width / (cols.max(1) as f64 * self.advance);
+        let by_height = height / (rows.max(1) as f64 * self.line_height);
+        (by_width.max(by_height) * 100.0).ceil() / 100.0
+    }
+
+    pub fn cell_size(&self, font_size: f64) -> (f64, f64) {
+        (font_size * self.advance, font_size * self.line_height)
+    }
+
+    pub fn canvas_dimensions(&self, cols: usize, rows: usize, font_size: f64) -> (f64, f64) {
+        let (cell_width, cell_height) = self.cell_size(font_size);
+        (cols as f64 * cell_width, rows as f64 * cell_height)
+    }
+}
+
+/// CSS `font-family` value for the chosen font, falling back to the generic monospace.
+pub fn css_font_family(family: Option<&str>) -> String {
+    match family {
+        Some(family) => format!("\"{}\", monospace", family.replace('"', "")),
+        None => "monospace".to_string(),
+    }
+}
+
+/// CSS `font` shorthand for canvas text.
+pub fn css_font(font_size: f64, font_family: &str) -> String {
+    format!("{}px {}", font_size, font_family)
+}
+
+/// Whether the details.toml `font` value names a font file rather than a family.
+pub fn is_font_file(font: &str) -> bool {
+    let lower = font.to_ascii_lowercase();
+    FONT_FILE_EXTENSIONS.iter().any(|ext| lower.ends_with(ext))
+}
+
+/// Make the details.toml `font` usable and return the family to render with.
+/// Font files have to be registered with the webview first, under their path;
+/// `None` when that failed.
+pub async fn load_project_font(directory_path: &str, font: String) -> Option<String> {
+    if !is_font_file(&font) {
+        return Some(font);
+    }
+    let args = serde_wasm_bindgen::to_value(&json!({ "directoryPath": directory_path, "fontPath": font })).unwrap();
+    let url = tauri_try_invoke("read_font_file", args).await.ok()?.as_string()?;
+    load_font_face(&font, &url).await.ok()?;
+    Some(font)
+}
+
+/// Metrics of `font_family`, measured again whenever it changes.
+#[hook]
+pub fn use_glyph_metrics(font_family: String) -> GlyphMetrics {
+    let metrics = use_state(GlyphMetrics::default);
+    {
+        let metrics = metrics.clone();
+        use_effect_with(font_family, move |font_family| {
+            metrics.set(GlyphMetrics::measure(font_family).unwrap_or_default());
+        });
+    }
+    *metrics
+}
+
+/// Fonts the settings panel suggests for the `font` key.
+#[derive(Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct FontList {
+    pub system: Vec<String>,
+    pub project: Vec<String>,
+}
+
+/// Fonts to suggest while `open`; the system scan is cached by the backend.
+#[hook]
+pub fn use_font_list(open: bool, directory_path: String) -> Rc<FontList> {
+    let fonts = use_state(|| Rc::new(FontList::default()));
+    {
+        let fonts = fonts.clone();
+        use_effect_with((open, directory_path), move |(open, directory_path)| {
+            if *open && !directory_path.is_empty() {
+                let args = serde_wasm_bindgen::to_value(&json!({ "directoryPath": directory_path })).unwrap();
+                wasm_bindgen_futures::spawn_local(async move {
+                    if let Ok(list) = tauri_try_invoke("list_fonts", args).await {
+                        fonts.set(Rc::new(serde_wasm_bindgen::from_value::<FontList>(list).unwrap_or_default()));
+                    }
+                });
+            }
+        });
+    }
+    (*fonts).clone()
+}
@@ -0,0 +1,39 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::closure::Closure;
+
+// Backend commands and events of the current window, shared by every component
+#[wasm_bindgen(inline_js = r#"
+export async function tauriInvoke(cmd, args) {
+  const g = globalThis.__TAURI__;
+  if (g?.core?.invoke) return g.core.invoke(cmd, args);
+  if (g?.tauri?.invoke) return g.tauri.invoke(cmd, args);
+  throw new Error('Tauri invoke is not available');
+}
+
+export function listenWindowEvent(event, callback) {
+  const current = globalThis.__TAURI__?.webviewWindow?.getCurrentWebviewWindow?.();
+  return current ? current.listen(event, (e) => callback(e.payload)) : null;
+}
+
+export function unlistenWindowEvent(handle) {
+  handle?.then((unlisten) => unlisten());
+}
+"#)]
+extern "C" {
+    #[wasm_bindgen(js_name = tauriInvoke)]
+    pub async fn tauri_invoke(cmd: &str, args: JsValue) -> JsValue;
+
+    #[wasm_bindgen(js_name = tauriInvoke, catch)]
+    pub async fn tauri_try_invoke(cmd: &str, args: JsValue) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(js_name = listenWindowEvent)]
+    pub fn listen_window_event(event: &str, callback: &Closure<dyn Fn(JsValue)>) -> JsValue;
+
+    #[wasm_bindgen(js_name = unlistenWindowEvent)]
+    pub fn unlisten_window_event(handle: &JsValue);
+}
+
+/// Turn a rejected invoke into a readable message.
+pub fn invoke_error(e: JsValue) -> String {
+    e.as_string().unwrap_or_else(|| format!("{:?}", e))
+}
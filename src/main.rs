@@ -1,5 +1,22 @@
 mod app;
+mod app_settings;
 mod ascii_frames_viewer;
+mod color_grid;
+mod color_mode;
+mod frame_editor;
+mod frame_export;
+mod frame_search;
+mod frame_stream;
+mod frame_timing;
+mod glyph_metrics;
+mod ipc;
+mod palette;
+mod project_settings;
+mod remote;
+mod slideshow;
+mod view_scale;
+mod waveform;
+mod widget;
 
 use app::App;
 
@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use serde::Deserialize;
+use serde_json::json;
+use yew::prelude::*;
+
+use crate::ipc::tauri_try_invoke;
+use crate::color_grid::{parse_hex_rgb, Rgb};
+use crate::color_mode::ColorSettings;
+
+/// 4x4 Bayer matrix for ordered dithering. Ordered (not error-diffusion)
+/// dithering keeps the pattern fixed per cell, so animations don't shimmer.
+const BAYER_4X4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+/// How far (in RGB units) dithering may push a color before matching.
+const DITHER_SPREAD: f64 = 48.0;
+
+const CGA: [Rgb; 4] = [(0x00, 0x00, 0x00), (0x55, 0xff, 0xff), (0xff, 0x55, 0xff), (0xff, 0xff, 0xff)];
+
+const EGA: [Rgb; 16] = [
+    (0x00, 0x00, 0x00), (0x00, 0x00, 0xaa), (0x00, 0xaa, 0x00), (0x00, 0xaa, 0xaa),
+    (0xaa, 0x00, 0x00), (0xaa, 0x00, 0xaa), (0xaa, 0x55, 0x00), (0xaa, 0xaa, 0xaa),
+    (0x55, 0x55, 0x55), (0x55, 0x55, 0xff), (0x55, 0xff, 0x55), (0x55, 0xff, 0xff),
+    (0xff, 0x55, 0x55), (0xff, 0x55, 0xff), (0xff, 0xff, 0x55), (0xff, 0xff, 0xff),
+];
+
+const GAME_BOY: [Rgb; 4] = [(0x0f, 0x38, 0x0f), (0x30, 0x62, 0x30), (0x8b, 0xac, 0x0f), (0x9b, 0xbc, 0x0f)];
+
+const SOLARIZED: [Rgb; 16] = [
+    (0x00, 0x2b, 0x36), (0x07, 0x36, 0x42), (0x58, 0x6e, 0x75), (0x65, 0x7b, 0x83),
+    (0x83, 0x94, 0x96), (0x93, 0xa1, 0xa1), (0xee, 0xe8, 0xd5), (0xfd, 0xf6, 0xe3),
+    (0xb5, 0x89, 0x00), (0xcb, 0x4b, 0x16), (0xdc, 0x32, 0x2f), (0xd3, 0x36, 0x82),
+    (0x6c, 0x71, 0xc4), (0x26, 0x8b, 0xd2), (0x2a, 0xa1, 0x98), (0x85, 0x99, 0x00),
+];
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Palette {
+    pub name: String,
+    pub colors: Vec<Rgb>,
+}
+
+impl Palette {
+    /// The presets offered in the palette picker.
+    pub fn builtin() -> Vec<Palette> {
+        vec![
+            Palette::new("CGA", &CGA),
+            Palette::new("EGA", &EGA),
+            Palette::new("xterm-256", &xterm_256()),
+            Palette::new("Game Boy", &GAME_BOY),
+            Palette::new("Solarized", &SOLARIZED),
+        ]
+    }
+
+    fn new(name: &str, colors: &[Rgb]) -> Self {
+        Palette {
+            name: name.to_string(),
+            colors: colors.to_vec(),
+        }
+    }
+
+    /// Parse a palette file: GIMP `.gpl` ("R G B name" lines) or one hex
+    /// color per line as in Lospec `.hex` files. Other lines are ignored.
+    pub fn parse(name: &str, text: &str) -> Result<Palette, String> {
+        let colors: Vec<Rgb> = text
+            .lines()
+            .filter_map(|line| {
+                let line = line.trim();
+                let hex = line.strip_prefix('#').unwrap_or(line);
+                if hex.len() == 6 {
+                    if let Some(color) = parse_hex_rgb(&format!("#{}", hex)) {
+                        return Some(color);
+                    }
+                }
+                let mut channels = line.split_whitespace().map(|v| v.parse::<u8>());
+                match (channels.next(), channels.next(), channels.next()) {
+                    (Some(Ok(r)), Some(Ok(g)), Some(Ok(b))) => Some((r, g, b)),
+                    _ => None,
+                }
+            })
+            .collect();
+        if colors.is_empty() {
+            return Err(format!("No colors found in palette {}", name));
+        }
+        Ok(Palette::new(name, &colors))
+    }
+
+    /// Closest palette color using the "redmean" weighted distance, which
+    /// tracks perceived difference better than plain RGB distance.
+    pub fn nearest(&self, (r, g, b): (f64, f64, f64)) -> Rgb {
+        self.colors
+            .iter()
+            .copied()
+            .min_by(|&a, &c| {
+                distance(a, (r, g, b))
+                    .partial_cmp(&distance(c, (r, g, b)))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .unwrap_or((r as u8, g as u8, b as u8))
+    }
+
+    /// Map every cell of `rgb` (row-major RGB, `width` cells per row) onto the palette.
+    pub fn remap(&self, rgb: &mut [u8], width: usize, dither: bool) {
+        let width = width.max(1);
+        let mut cache: HashMap<(Rgb, Option<u8>), Rgb> = HashMap::new();
+        for (i, cell) in rgb.chunks_exact_mut(3).enumerate() {
+            let color = (cell[0], cell[1], cell[2]);
+            let threshold = dither.then(|| BAYER_4X4[(i / width) % 4][(i % width) % 4]);
+            let mapped = *cache.entry((color, threshold)).or_insert_with(|| {
+                let offset = threshold.map(|t| ((t as f64 + 0.5) / 16.0 - 0.5) * DITHER_SPREAD).unwrap_or(0.0);
+                let shift = |c: u8| (c as f64 + offset).clamp(0.0, 255.0);
+                self.nearest((shift(color.0), shift(color.1), shift(color.2)))
+            });
+            cell.copy_from_slice(&[mapped.0, mapped.1, mapped.2]);
+        }
+    }
+}
+
+/// Custom palette shipped with a project (palette.gpl / .hex / .txt).
+#[derive(Deserialize, Clone, Debug)]
+struct PaletteFile {
+    name: String,
+    text: String,
+}
+
+/// The project's own palette, if it has a readable one.
+pub async fn read_project_palette(directory_path: &str) -> Option<Palette> {
+    let args = serde_wasm_bindgen::to_value(&json!({ "directoryPath": directory_path })).unwrap();
+    let file = tauri_try_invoke("read_palette_file", args).await.ok()?;
+    let file = serde_wasm_bindgen::from_value::<Option<PaletteFile>>(file).ok()??;
+    Palette::parse(&file.name, &file.text).ok()
+}
+
+/// The palettes offered in palette mode: the presets, then the project's palette.
+#[hook]
+pub fn use_palettes(custom: Option<Palette>) -> Rc<Vec<Palette>> {
+    use_memo(custom, |custom| {
+        let mut list = Palette::builtin();
+        list.extend(custom.iter().cloned());
+        list
+    })
+}
+
+#[derive(Properties, PartialEq)]
+pub struct PaletteControlsProps {
+    pub palettes: Rc<Vec<Palette>>,
+    pub settings: ColorSettings,
+    pub on_change: Callback<ColorSettings>,
+}
+
+/// Palette picker and dithering switch shown in palette mode.
+#[function_component(PaletteControls)]
+pub fn palette_controls(props: &PaletteControlsProps) -> Html {
+    let on_palette_change = {
+        let settings = props.settings.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: Event| {
+            if let Some(select) = e.target_dyn_into::<web_sys::HtmlSelectElement>() {
+                if let Ok(palette) = select.value().parse::<usize>() {
+                    on_change.emit(ColorSettings { palette, ..settings.clone() });
+                }
+            }
+        })
+    };
+
+    let on_toggle_dither = {
+        let settings = props.settings.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |_| {
+            on_change.emit(ColorSettings {
+                dither: !settings.dither,
+                ..settings.clone()
+            });
+        })
+    };
+
+    html! {
+        <div class="control-row palette-row">
+            <label for="palette-select">{"Palette:"}</label>
+            <select id="palette-select" class="palette-select" onchange={on_palette_change}>
+                { for props.palettes.iter().enumerate().map(|(i, palette)| html! {
+                    <option value={i.to_string()} selected={i == props.settings.palette}>{palette.name.clone()}</option>
+                }) }
+            </select>
+            <button class={if props.settings.dither { "ctrl-btn copy-format-btn active" } else { "ctrl-btn copy-format-btn" }} type="button" onclick={on_toggle_dither} title="Ordered dithering between palette colors">{"Dither"}</button>
+        </div>
+    }
+}
+
+fn distance((r1, g1, b1): Rgb, (r2, g2, b2): (f64, f64, f64)) -> f64 {
+    let mean_r = (r1 as f64 + r2) / 2.0;
+    let (dr, dg, db) = (r1 as f64 - r2, g1 as f64 - g2, b1 as f64 - b2);
+    (2.0 + mean_r / 256.0) * dr * dr + 4.0 * dg * dg + (2.0 + (255.0 - mean_r) / 256.0) * db * db
+}
+
+/// The standard xterm 256-color table: 16 system colors, a 6x6x6 cube and 24 grays.
+fn xterm_256() -> Vec<Rgb> {
+    let mut colors: Vec<Rgb> = vec![
+        (0x00, 0x00, 0x00), (0x80, 0x00, 0x00), (0x00, 0x80, 0x00), (0x80, 0x80, 0x00),
+        (0x00, 0x00, 0x80), (0x80, 0x00, 0x80), (0x00, 0x80, 0x80), (0xc0, 0xc0, 0xc0),
+        (0x80, 0x80, 0x80), (0xff, 0x00, 0x00), (0x00, 0xff, 0x00), (0xff, 0xff, 0x00),
+        (0x00, 0x00, 0xff), (0xff, 0x00, 0xff), (0x00, 0xff, 0xff), (0xff, 0xff, 0xff),
+    ];
+    let level = |i: u8| if i == 0 { 0 } else { 55 + i * 40 };
+    for r in 0..6 {
+        for g in 0..6 {
+            for b in 0..6 {
+                colors.push((level(r), level(g), level(b)));
+            }
+        }
+    }
+    for i in 0..24u8 {
+        let gray = 8 + i * 10;
+        colors.push((gray, gray, gray));
+    }
+    colors
+}
@@ -0,0 +1,74 @@
+use cascii_core_view::FrameColors;
+use serde::{Deserialize, Serialize};
+
+/// Keys the settings panel always offers, with their value kinds.
+const KNOWN_FIELDS: [(&str, &str); 5] = [
+    ("fps", "integer"),
+    ("color", "string"),
+    ("background_color", "string"),
+    ("audio_offset_ms", "integer"),
+    ("font", "string"),
+];
+
+/// One top-level details.toml key as exchanged with the backend.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct DetailsField {
+    pub key: String,
+    pub value: String,
+    /// "string", "integer", "float" or "boolean"
+    pub kind: String,
+}
+
+impl DetailsField {
+    pub fn is_color(&self) -> bool {
+        self.key == "color" || self.key == "background_color"
+    }
+}
+
+/// Put the known keys first (adding empty ones that the file lacks), followed
+/// by every other key in file order.
+pub fn with_known_fields(fields: Vec<DetailsField>) -> Vec<DetailsField> {
+    let mut result: Vec<DetailsField> = KNOWN_FIELDS
+        .iter()
+        .map(|&(key, kind)| {
+            fields.iter().find(|f| f.key == key).cloned().unwrap_or(DetailsField {
+                key: key.to_string(),
+                value: String::new(),
+                kind: kind.to_string(),
+            })
+        })
+        .collect();
+    result.extend(
+        fields
+            .into_iter()
+            .filter(|f| !KNOWN_FIELDS.iter().any(|&(key, _)| key == f.key)),
+    );
+    result
+}
+
+/// Whether cascii understands `value` as a color.
+/// `from_strings` falls back to white text on black for anything it can't
+/// parse, so a valid color is one that comes back identical in both roles.
+pub fn is_valid_color(value: &str) -> bool {
+    let colors = FrameColors::from_strings(value, value);
+    colors.foreground == colors.background
+}
+
+pub fn validate_fields(fields: &[DetailsField]) -> Result<(), String> {
+    for field in fields {
+        let value = field.value.trim();
+        if value.is_empty() {
+            continue;
+        }
+        if field.is_color() && !is_valid_color(value) {
+            return Err(format!("{} is not a valid color: {}", field.key, value));
+        }
+        if field.key == "fps" && !value.parse::<u32>().map(|fps| fps > 0).unwrap_or(false) {
+            return Err("fps must be a whole number greater than zero".to_string());
+        }
+        if field.key == "audio_offset_ms" && value.parse::<i32>().is_err() {
+            return Err("audio_offset_ms must be a whole number of milliseconds".to_string());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+
+/// A command from a remote control client, relayed by the backend.
+/// Opening a project doesn't come through here: it arrives like a forwarded launch.
+#[derive(Clone, PartialEq, Debug, Deserialize)]
+#[serde(tag = "command", rename_all = "camelCase")]
+pub enum RemoteCommand {
+    Play,
+    Pause,
+    Toggle,
+    /// Zero-based frame index
+    Seek { frame: usize },
+    /// Frames forward, or back when negative
+    Step { frames: i64 },
+    /// 0.0 to 1.0 along the timeline
+    SeekPosition { position: f64 },
+    /// Milliseconds into the timeline
+    SeekTime { ms: f64 },
+    Fps { fps: u32 },
+    /// A `--color-mode` name
+    ColorMode { mode: String },
+    Volume { volume: f64 },
+}
+
+/// What the viewer shows, streamed to remote clients while the server runs.
+#[derive(Clone, PartialEq, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaybackState {
+    pub directory_path: String,
+    pub playing: bool,
+    pub frame: usize,
+    pub frame_count: usize,
+    pub fps: u32,
+    /// When the shown frame starts on the timeline, which held frames stretch
+    pub position_ms: f64,
+    pub duration_ms: f64,
+    pub color_mode: String,
+    pub volume: f64,
+    pub muted: bool,
+}
+
+/// Whether the remote control server is listening, and where.
+#[derive(Clone, PartialEq, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RemoteStatus {
+    pub running: bool,
+    pub address: Option<String>,
+    /// Something (remote clients, OSC output, sync followers, stdio) wants the playback state
+    pub reporting: bool,
+    /// How leading or following is going, when this instance takes part in sync
+    pub sync: Option<String>,
+}
+
+/// A frame export asked for over JSON-RPC; the result goes back with `finish_rpc_export`.
+#[derive(Clone, PartialEq, Debug, Deserialize)]
+pub struct ExportRequest {
+    pub id: u64,
+    /// A `CopyFormat` name
+    pub format: String,
+    /// The current frame when left out
+    pub frame: Option<usize>,
+}
+
+/// The sync leader's playback, with `frame_started_at` already in our `Date.now()` time.
+#[derive(Clone, PartialEq, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncFollow {
+    pub playing: bool,
+    pub frame: usize,
+    pub frame_started_at: f64,
+}
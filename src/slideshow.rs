@@ -0,0 +1,281 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gloo_timers::callback::Timeout;
+use serde::Deserialize;
+use serde_json::json;
+use wasm_bindgen::prelude::*;
+use yew::prelude::*;
+
+use crate::app_settings::{SlideAdvance, SlideshowSettings};
+use crate::ascii_frames_viewer::AsciiFramesViewer;
+use crate::ipc::{invoke_error, tauri_try_invoke};
+
+#[wasm_bindgen(inline_js = r#"
+// Resolves to whether the window was fullscreen before
+export async function setWindowFullscreen(fullscreen) {
+    const current = window.__TAURI__?.webviewWindow?.getCurrentWebviewWindow?.();
+    if (!current) return false;
+    const was = await current.isFullscreen();
+    await current.setFullscreen(fullscreen);
+    return was;
+}
+"#)]
+extern "C" {
+    #[wasm_bindgen(js_name = setWindowFullscreen, catch)]
+    async fn set_window_fullscreen(fullscreen: bool) -> Result<JsValue, JsValue>;
+}
+
+/// Longest a slide stays up when advancing by loops, for animations that never
+/// finish one (e.g. because they failed to load).
+const MAX_SLIDE_MS: u32 = 120_000;
+
+/// A project folder found under the library root.
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryProject {
+    pub path: String,
+    pub name: String,
+    pub frame_count: usize,
+}
+
+/// Every project under `root_path`; just the root when it is a project itself.
+pub async fn scan(root_path: &str) -> Result<Vec<LibraryProject>, String> {
+    let args = serde_wasm_bindgen::to_value(&json!({ "rootPath": root_path })).unwrap();
+    let value = tauri_try_invoke("scan_library", args).await.map_err(invoke_error)?;
+    serde_wasm_bindgen::from_value(value).map_err(|e| format!("Failed to read library: {}", e))
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct Slide {
+    /// Counts slides shown so far; keys the viewer so every slide gets a fresh one
+    serial: usize,
+    /// Index into the project list
+    project: usize,
+}
+
+/// Order of play. Lives in a RefCell because timers and viewer callbacks outlive renders.
+#[derive(Default)]
+struct Playlist {
+    count: usize,
+    order: Vec<usize>,
+    position: usize,
+    serial: usize,
+    current: Option<Slide>,
+    /// Times the current animation has played through
+    loops: u32,
+}
+
+impl Playlist {
+    /// Move on to the next project, starting a new (reshuffled) pass after the last.
+    fn advance(&mut self, shuffle: bool) -> Slide {
+        if self.position >= self.order.len() {
+            let mut order: Vec<usize> = (0..self.count).collect();
+            if shuffle {
+                for i in (1..order.len()).rev() {
+                    let j = ((js_sys::Math::random() * (i + 1) as f64) as usize).min(i);
+                    order.swap(i, j);
+                }
+                // A new pass shouldn't repeat the project that just played
+                if order.len() > 1 && self.current.map(|s| s.project) == order.first().copied() {
+                    let last = order.len() - 1;
+                    order.swap(0, last);
+                }
+            }
+            self.order = order;
+            self.position = 0;
+        }
+        let slide = Slide {
+            serial: self.serial,
+            project: self.order[self.position],
+        };
+        self.position += 1;
+        self.serial += 1;
+        self.loops = 0;
+        self.current = Some(slide);
+        slide
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct SlideshowProps {
+    /// Folder whose projects are cycled through
+    pub root_path: String,
+    pub settings: SlideshowSettings,
+    pub on_exit: Callback<()>,
+}
+
+/// Screensaver-style playback of every project under a folder, fullscreen with
+/// the cursor hidden. Esc or a double click leaves it, → skips ahead.
+#[function_component(Slideshow)]
+pub fn slideshow(props: &SlideshowProps) -> Html {
+    let projects = use_state(|| None::<Result<Rc<Vec<LibraryProject>>, String>>);
+    let playlist: Rc<RefCell<Playlist>> = use_mut_ref(Playlist::default);
+    // The slide fading out (if any) and the one on screen
+    let slides = use_state(|| (None::<Slide>, None::<Slide>));
+    let container_ref = use_node_ref();
+    let settings = props.settings.clone();
+
+    let next_slide = {
+        let playlist = playlist.clone();
+        let slides = slides.clone();
+        let shuffle = settings.shuffle;
+        Callback::from(move |_: ()| {
+            let (previous, slide) = {
+                let mut playlist = playlist.borrow_mut();
+                if playlist.count == 0 {
+                    return;
+                }
+                (playlist.current, playlist.advance(shuffle))
+            };
+            slides.set((previous, Some(slide)));
+        })
+    };
+
+    // Find the projects, then start with the first
+    {
+        let projects = projects.clone();
+        let playlist = playlist.clone();
+        let next_slide = next_slide.clone();
+        use_effect_with(props.root_path.clone(), move |root_path| {
+            let root_path = root_path.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match scan(&root_path).await {
+                    Ok(list) if !list.is_empty() => {
+                        *playlist.borrow_mut() = Playlist { count: list.len(), ..Playlist::default() };
+                        projects.set(Some(Ok(Rc::new(list))));
+                        next_slide.emit(());
+                    }
+                    Ok(_) => projects.set(Some(Err("No projects found in this folder".to_string()))),
+                    Err(e) => projects.set(Some(Err(e))),
+                }
+            });
+            || ()
+        });
+    }
+
+    // Fullscreen while the slideshow runs; the window goes back to how it was
+    {
+        let container_ref = container_ref.clone();
+        use_effect_with((), move |_| {
+            if let Some(element) = container_ref.cast::<web_sys::HtmlElement>() {
+                let _ = element.focus();
+            }
+            let was_fullscreen = Rc::new(RefCell::new(None::<bool>));
+            {
+                let was_fullscreen = was_fullscreen.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    if let Ok(was) = set_window_fullscreen(true).await {
+                        *was_fullscreen.borrow_mut() = was.as_bool();
+                    }
+                });
+            }
+            move || {
+                let restore = was_fullscreen.borrow().unwrap_or(false);
+                wasm_bindgen_futures::spawn_local(async move {
+                    let _ = set_window_fullscreen(restore).await;
+                });
+            }
+        });
+    }
+
+    // Timed advance, or a safety limit when advancing by loops
+    {
+        let next_slide = next_slide.clone();
+        let delay_ms = match settings.advance {
+            SlideAdvance::Seconds(seconds) => seconds.max(1).saturating_mul(1000),
+            SlideAdvance::Loops(_) => MAX_SLIDE_MS,
+        };
+        let current = slides.1;
+        use_effect_with((current.map(|s| s.serial), delay_ms), move |_| {
+            let timeout = current.map(|_| Timeout::new(delay_ms, move || next_slide.emit(())));
+            move || drop(timeout)
+        });
+    }
+
+    // Drop the outgoing slide once the incoming one has faded in
+    {
+        let slides = slides.clone();
+        let (leaving, current) = *slides;
+        let crossfade_ms = settings.crossfade_ms;
+        use_effect_with(leaving.map(|s| s.serial), move |_| {
+            let timeout = leaving.map(|_| Timeout::new(crossfade_ms, move || slides.set((None, current))));
+            move || drop(timeout)
+        });
+    }
+
+    // Loops are counted per slide, so the outgoing viewer can't end the incoming slide early
+    let on_loop = {
+        let playlist = playlist.clone();
+        let next_slide = next_slide.clone();
+        let advance = settings.advance;
+        Callback::from(move |serial: usize| {
+            let SlideAdvance::Loops(loops) = advance else {
+                return;
+            };
+            let done = {
+                let mut playlist = playlist.borrow_mut();
+                if playlist.current.map(|s| s.serial) != Some(serial) {
+                    return;
+                }
+                playlist.loops += 1;
+                playlist.loops >= loops.max(1)
+            };
+            if done {
+                next_slide.emit(());
+            }
+        })
+    };
+
+    let on_keydown = {
+        let on_exit = props.on_exit.clone();
+        let next_slide = next_slide.clone();
+        Callback::from(move |e: KeyboardEvent| match e.key().as_str() {
+            "Escape" => on_exit.emit(()),
+            "ArrowRight" => next_slide.emit(()),
+            _ => {}
+        })
+    };
+
+    let on_dblclick = {
+        let on_exit = props.on_exit.clone();
+        Callback::from(move |_: MouseEvent| on_exit.emit(()))
+    };
+
+    let (leaving, current) = *slides;
+
+    html! {
+        <div class="slideshow" ref={container_ref} tabindex="0" onkeydown={on_keydown} ondblclick={on_dblclick} style={format!("--crossfade: {}ms;", settings.crossfade_ms)}>
+            {
+                match &*projects {
+                    None => html! { <div class="slideshow-message">{"Looking for projects…"}</div> },
+                    Some(Err(error)) => html! {
+                        <div class="slideshow-message">
+                            <p>{error.clone()}</p>
+                            <p>{"Press Esc to leave"}</p>
+                        </div>
+                    },
+                    Some(Ok(list)) => html! {
+                        { for leaving.into_iter().chain(current).filter_map(|slide| {
+                            let project = list.get(slide.project)?;
+                            let serial = slide.serial;
+                            Some(html! {
+                                <div key={serial.to_string()} class={classes!("slide", (Some(slide) == leaving).then_some("leaving"))} title={project.name.clone()}>
+                                    <AsciiFramesViewer
+                                        directory_path={project.path.clone()}
+                                        fps={24}
+                                        loop_enabled={true}
+                                        autoplay={true}
+                                        fullscreen={true}
+                                        hide_controls={true}
+                                        on_loop={on_loop.reform(move |_| serial)}
+                                    />
+                                </div>
+                            })
+                        }) }
+                    },
+                }
+            }
+        </div>
+    }
+}
@@ -0,0 +1,288 @@
+use std::cell::RefCell;
+use std::ops::Deref;
+use std::rc::Rc;
+
+use yew::prelude::*;
+
+use crate::glyph_metrics::GlyphMetrics;
+
+/// Font size frames are rendered at in integer scaling mode; ×1 shows them 1:1.
+const NATIVE_FONT_SIZE: f64 = 12.0;
+const MIN_FONT_SIZE: f64 = 1.0;
+/// Keeps zoomed canvases within what the webview will allocate.
+const MAX_FONT_SIZE: f64 = 256.0;
+/// Each wheel notch zooms by this factor.
+const ZOOM_STEP: f64 = 1.25;
+const MIN_ZOOM: f64 = 0.25;
+const MAX_ZOOM: f64 = 8.0;
+const MAX_MULTIPLE: u32 = 8;
+
+/// How the frame is sized to the display area.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ScaleMode {
+    /// Largest font that shows the whole frame
+    #[default]
+    Fit,
+    /// Smallest font that covers the whole display; the rest can be panned to
+    Fill,
+    /// Fit, then scaled unevenly to cover the display exactly
+    Stretch,
+    /// Rendered at a fixed font size and magnified by a whole multiple
+    Integer,
+}
+
+impl ScaleMode {
+    pub const ALL: [ScaleMode; 4] = [ScaleMode::Fit, ScaleMode::Fill, ScaleMode::Stretch, ScaleMode::Integer];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ScaleMode::Fit => "Fit",
+            ScaleMode::Fill => "Fill",
+            ScaleMode::Stretch => "Stretch",
+            ScaleMode::Integer => "1:1",
+        }
+    }
+}
+
+/// The sizing mode plus the user's zoom and pan.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ViewScale {
+    pub mode: ScaleMode,
+    /// Zoom factor in fit, fill and stretch modes
+    pub zoom: f64,
+    /// Magnification in integer mode
+    pub multiple: u32,
+    /// Offset of the frame from the center of the display, in CSS pixels
+    pub pan: (f64, f64),
+}
+
+impl Default for ViewScale {
+    fn default() -> Self {
+        ViewScale {
+            mode: ScaleMode::Fit,
+            zoom: 1.0,
+            multiple: 1,
+            pan: (0.0, 0.0),
+        }
+    }
+}
+
+/// Where a frame ends up: the font size it is rendered at, and the CSS
+/// scale applied to the rendered element on top of that.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ViewLayout {
+    pub font_size: f64,
+    pub scale: (f64, f64),
+}
+
+impl ViewScale {
+    pub fn with_mode(mode: ScaleMode) -> Self {
+        ViewScale { mode, ..ViewScale::default() }
+    }
+
+    /// Whether the frame can be dragged around.
+    pub fn pannable(&self) -> bool {
+        match self.mode {
+            ScaleMode::Fit | ScaleMode::Stretch => self.zoom > 1.0,
+            ScaleMode::Fill | ScaleMode::Integer => true,
+        }
+    }
+
+    pub fn zoom_label(&self) -> String {
+        match self.mode {
+            ScaleMode::Integer => format!("×{}", self.multiple),
+            _ => format!("{:.0}%", self.zoom * 100.0),
+        }
+    }
+
+    /// Zoom in (`steps > 0`) or out around `anchor`, a point relative to the
+    /// display center that stays put on screen.
+    pub fn zoomed(&self, steps: i32, anchor: (f64, f64)) -> Self {
+        let mut next = *self;
+        let ratio = match self.mode {
+            ScaleMode::Integer => {
+                next.multiple = (self.multiple as i32 + steps).clamp(1, MAX_MULTIPLE as i32) as u32;
+                next.multiple as f64 / self.multiple as f64
+            }
+            _ => {
+                next.zoom = (self.zoom * ZOOM_STEP.powi(steps)).clamp(MIN_ZOOM, MAX_ZOOM);
+                next.zoom / self.zoom
+            }
+        };
+        next.pan = (
+            anchor.0 - (anchor.0 - self.pan.0) * ratio,
+            anchor.1 - (anchor.1 - self.pan.1) * ratio,
+        );
+        if !next.pannable() {
+            next.pan = (0.0, 0.0);
+        }
+        next
+    }
+
+    pub fn layout(&self, metrics: &GlyphMetrics, cols: usize, rows: usize, display: (f64, f64)) -> ViewLayout {
+        let clamp = |size: f64| size.clamp(MIN_FONT_SIZE, MAX_FONT_SIZE);
+        let (width, height) = display;
+        match self.mode {
+            ScaleMode::Fit => ViewLayout {
+                font_size: clamp(metrics.fit_font_size(cols, rows, width, height) * self.zoom),
+                scale: (1.0, 1.0),
+            },
+            ScaleMode::Fill => ViewLayout {
+                font_size: clamp(metrics.cover_font_size(cols, rows, width, height) * self.zoom),
+                scale: (1.0, 1.0),
+            },
+            // Zooming only rescales the element, so cached canvases stay valid
+            ScaleMode::Stretch => {
+                let font_size = clamp(metrics.fit_font_size(cols, rows, width, height));
+                let (w, h) = metrics.canvas_dimensions(cols, rows, font_size);
+                ViewLayout {
+                    font_size,
+                    scale: (
+                        width / w.max(1.0) * self.zoom,
+                        height / h.max(1.0) * self.zoom,
+                    ),
+                }
+            }
+            ScaleMode::Integer => ViewLayout {
+                font_size: NATIVE_FONT_SIZE,
+                scale: (self.multiple as f64, self.multiple as f64),
+            },
+        }
+    }
+
+    /// CSS for the rendered frame element.
+    pub fn element_style(&self, layout: &ViewLayout) -> String {
+        let mut style = format!(
+            "transform: translate({:.1}px, {:.1}px) scale({:.4}, {:.4});",
+            self.pan.0, self.pan.1, layout.scale.0, layout.scale.1
+        );
+        // Whole multiples of the pixels stay sharp instead of being smoothed
+        if self.mode == ScaleMode::Integer {
+            style.push_str(" image-rendering: pixelated;");
+        }
+        style
+    }
+}
+
+/// Pointer position and pan when a pan drag started
+type PanStart = ((f64, f64), (f64, f64));
+
+/// The view scale, and the pan drag in progress if any.
+#[derive(Clone)]
+pub struct UseViewScaleHandle {
+    view: UseStateHandle<ViewScale>,
+    drag: Rc<RefCell<Option<PanStart>>>,
+}
+
+impl Deref for UseViewScaleHandle {
+    type Target = ViewScale;
+
+    fn deref(&self) -> &ViewScale {
+        &self.view
+    }
+}
+
+impl UseViewScaleHandle {
+    pub fn set(&self, view: ViewScale) {
+        self.view.set(view);
+    }
+
+    /// Zoom by `steps` around `anchor`, relative to the display center.
+    pub fn zoom(&self, steps: i32, anchor: (f64, f64)) {
+        self.view.set(self.view.zoomed(steps, anchor));
+    }
+
+    /// Zoom by one wheel notch around the pointer.
+    pub fn zoom_at_pointer(&self, display: &web_sys::Element, e: &web_sys::WheelEvent) {
+        let rect = display.get_bounding_client_rect();
+        let anchor = (
+            e.client_x() as f64 - rect.left() - rect.width() / 2.0,
+            e.client_y() as f64 - rect.top() - rect.height() / 2.0,
+        );
+        self.zoom(if e.delta_y() < 0.0 { 1 } else { -1 }, anchor);
+    }
+
+    pub fn start_pan(&self, e: &web_sys::MouseEvent) {
+        *self.drag.borrow_mut() = Some(((e.client_x() as f64, e.client_y() as f64), self.view.pan));
+    }
+
+    /// Follow the pointer while a pan drag is in progress; false when there is none.
+    pub fn drag_pan(&self, e: &web_sys::MouseEvent) -> bool {
+        let Some(((start_x, start_y), (pan_x, pan_y))) = *self.drag.borrow() else {
+            return false;
+        };
+        self.view.set(ViewScale {
+            pan: (pan_x + e.client_x() as f64 - start_x, pan_y + e.client_y() as f64 - start_y),
+            ..*self.view
+        });
+        true
+    }
+
+    pub fn end_pan(&self) {
+        self.drag.borrow_mut().take();
+    }
+}
+
+#[hook]
+pub fn use_view_scale() -> UseViewScaleHandle {
+    UseViewScaleHandle {
+        view: use_state(ViewScale::default),
+        drag: use_mut_ref(|| None),
+    }
+}
+
+/// Font size that lays a `frame_size` frame out in `display` as `view` asks,
+/// updated when any of them or the font metrics change.
+#[hook]
+pub fn use_fitted_font_size(frame_size: Option<(usize, usize)>, metrics: GlyphMetrics, view: ViewScale, display: (f64, f64)) -> f64 {
+    let font_size = use_state(|| 10.0f64);
+    {
+        let font_size = font_size.clone();
+        use_effect_with(
+            (
+                frame_size,
+                metrics,
+                // Panning never changes the font size
+                (view.mode, view.zoom, view.multiple),
+                (display.0 as i32, display.1 as i32),
+            ),
+            move |_| {
+                if let Some((cols, rows)) = frame_size.filter(|&(cols, rows)| cols > 0 && rows > 0) {
+                    font_size.set(view.layout(&metrics, cols, rows, display).font_size);
+                }
+            },
+        );
+    }
+    *font_size
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ViewControlsProps {
+    pub view: ViewScale,
+    pub on_change: Callback<ViewScale>,
+}
+
+/// Scale mode buttons and zoom steps.
+#[function_component(ViewControls)]
+pub fn view_controls(props: &ViewControlsProps) -> Html {
+    let view = props.view;
+    let on_zoom_step = {
+        let on_change = props.on_change.clone();
+        Callback::from(move |steps: i32| on_change.emit(view.zoomed(steps, (0.0, 0.0))))
+    };
+
+    html! {
+        <div class="control-row view-row">
+            <label>{"View:"}</label>
+            { for ScaleMode::ALL.iter().map(|&mode| {
+                let on_change = props.on_change.clone();
+                html! {
+                    <button class={if view.mode == mode { "ctrl-btn copy-format-btn active" } else { "ctrl-btn copy-format-btn" }} type="button" onclick={Callback::from(move |_| on_change.emit(ViewScale::with_mode(mode)))} title={format!("{} scaling (resets zoom and pan)", mode.label())}>{mode.label()}</button>
+                }
+            }) }
+            <button class="ctrl-btn copy-format-btn" type="button" onclick={on_zoom_step.reform(|_| -1)} title="Zoom out (mouse wheel)">{"−"}</button>
+            <span class="info-text">{view.zoom_label()}</span>
+            <button class="ctrl-btn copy-format-btn" type="button" onclick={on_zoom_step.reform(|_| 1)} title="Zoom in (mouse wheel)">{"+"}</button>
+        </div>
+    }
+}
@@ -0,0 +1,69 @@
+use serde::Deserialize;
+use wasm_bindgen::JsCast;
+
+/// Peak/RMS envelope of the soundtrack as computed by the backend.
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+pub struct Waveform {
+    pub bucket_ms: u32,
+    pub peaks: Vec<f32>,
+    pub rms: Vec<f32>,
+}
+
+impl Waveform {
+    /// Loudest peak and RMS between two audio times (seconds), or `None` outside the audio.
+    fn range(&self, from: f64, to: f64) -> Option<(f32, f32)> {
+        let bucket_seconds = self.bucket_ms.max(1) as f64 / 1000.0;
+        if to < 0.0 || from >= self.peaks.len() as f64 * bucket_seconds {
+            return None;
+        }
+        let first = (from.max(0.0) / bucket_seconds) as usize;
+        let last = ((to / bucket_seconds) as usize).clamp(first, self.peaks.len() - 1);
+        let peak = self.peaks[first..=last].iter().copied().fold(0.0, f32::max);
+        let rms = self.rms[first..=last].iter().copied().fold(0.0, f32::max);
+        Some((peak, rms))
+    }
+}
+
+/// Draw the envelope across `canvas`, which spans `timeline_seconds` of frames.
+/// Audio times are shifted by `audio_offset_ms` so the picture matches what plays.
+pub fn draw_waveform(
+    canvas: &web_sys::HtmlCanvasElement,
+    waveform: &Waveform,
+    timeline_seconds: f64,
+    audio_offset_ms: i32,
+) {
+    let Some(ctx) = canvas
+        .get_context("2d")
+        .ok()
+        .flatten()
+        .and_then(|c| c.dyn_into::<web_sys::CanvasRenderingContext2d>().ok())
+    else {
+        return;
+    };
+
+    let scale = web_sys::window().map(|w| w.device_pixel_ratio()).unwrap_or(1.0);
+    let width = (canvas.client_width() as f64 * scale).round().max(1.0);
+    let height = (canvas.client_height() as f64 * scale).round().max(1.0);
+    canvas.set_width(width as u32);
+    canvas.set_height(height as u32);
+    ctx.clear_rect(0.0, 0.0, width, height);
+    if timeline_seconds <= 0.0 || waveform.peaks.is_empty() {
+        return;
+    }
+
+    let offset = audio_offset_ms as f64 / 1000.0;
+    let seconds_per_px = timeline_seconds / width;
+    let mid = height / 2.0;
+    for x in 0..width as usize {
+        let from = x as f64 * seconds_per_px - offset;
+        let Some((peak, rms)) = waveform.range(from, from + seconds_per_px) else {
+            continue;
+        };
+        let peak_h = (peak as f64 * mid).max(0.5);
+        let rms_h = (rms as f64 * mid).max(0.5);
+        ctx.set_fill_style_str("rgba(255, 255, 255, 0.18)");
+        ctx.fill_rect(x as f64, mid - peak_h, 1.0, peak_h * 2.0);
+        ctx.set_fill_style_str("rgba(255, 255, 255, 0.35)");
+        ctx.fill_rect(x as f64, mid - rms_h, 1.0, rms_h * 2.0);
+    }
+}
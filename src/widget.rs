@@ -0,0 +1,204 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsValue;
+use yew::prelude::*;
+
+use crate::ipc::{invoke_error, listen_window_event, tauri_try_invoke, unlisten_window_event};
+
+/// The backend's `CmdOrCtrl+Shift+D`, as it reads on this platform.
+pub fn exit_shortcut_label() -> &'static str {
+    let is_mac = web_sys::window()
+        .and_then(|window| window.navigator().user_agent().ok())
+        .is_some_and(|agent| agent.contains("Mac"));
+    if is_mac {
+        "Cmd+Shift+D"
+    } else {
+        "Ctrl+Shift+D"
+    }
+}
+/// Window scale per wheel notch while in widget mode.
+pub const RESIZE_STEP: f64 = 1.1;
+
+/// Which windows the widget stays above or below.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WidgetLayer {
+    /// Above every other window
+    #[default]
+    Top,
+    /// Below every other window, like part of the desktop
+    Bottom,
+}
+
+impl WidgetLayer {
+    pub const ALL: [WidgetLayer; 2] = [WidgetLayer::Top, WidgetLayer::Bottom];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            WidgetLayer::Top => "On top",
+            WidgetLayer::Bottom => "On bottom",
+        }
+    }
+}
+
+/// How the window behaves once it becomes a desktop widget.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WidgetOptions {
+    pub layer: WidgetLayer,
+    /// Clicks go to the windows below; only the exit shortcut reaches the widget
+    pub click_through: bool,
+}
+
+/// Whether this window is a widget, and how to turn it back into a window.
+#[derive(Clone)]
+pub struct UseWidgetModeHandle {
+    pub active: bool,
+    /// Closes the widget and shows the window it came from
+    pub exit: Callback<()>,
+    /// Why the last exit failed
+    pub error: Option<String>,
+}
+
+/// Marks the document while in widget mode and calls `on_exited` when this window's
+/// widget has closed and it is shown again.
+#[hook]
+pub fn use_widget_mode(initial: bool, on_exited: Callback<()>) -> UseWidgetModeHandle {
+    let active = use_state(|| initial);
+    let error = use_state(|| None::<String>);
+
+    use_effect_with(*active, move |active| {
+        if let Some(root) = web_sys::window().and_then(|w| w.document()).and_then(|d| d.document_element()) {
+            let _ = root.class_list().toggle_with_force("widget-mode", *active);
+        }
+    });
+
+    use_effect_with((), move |_| {
+        let callback = Closure::<dyn Fn(JsValue)>::new(move |_| on_exited.emit(()));
+        let handle = listen_window_event("widget-mode-exited", &callback);
+        move || {
+            unlisten_window_event(&handle);
+            drop(callback);
+        }
+    });
+
+    let exit = {
+        let active = active.clone();
+        let error = error.clone();
+        Callback::from(move |_: ()| {
+            let active = active.clone();
+            let error = error.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                // Closes this window; it only stays when that failed, so show why
+                let args = serde_wasm_bindgen::to_value(&json!({})).unwrap();
+                if let Err(e) = tauri_try_invoke("exit_widget_mode", args).await {
+                    error.set(Some(invoke_error(e)));
+                    active.set(false);
+                }
+            });
+        })
+    };
+
+    UseWidgetModeHandle {
+        active: *active,
+        exit,
+        error: (*error).clone(),
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct WidgetPanelProps {
+    /// Width over height of the frame, which the widget window keeps
+    pub aspect_ratio: f64,
+    /// Viewer arguments the widget window opens with
+    pub launch: serde_json::Value,
+    pub streaming: bool,
+    pub disabled: bool,
+    pub exit_error: Option<String>,
+    pub on_started: Callback<()>,
+    /// A streaming viewer lets go of the source while the widget starts
+    pub on_stream_paused: Callback<bool>,
+}
+
+/// Widget options and the button that opens the widget; this window hides until it closes.
+#[function_component(WidgetPanel)]
+pub fn widget_panel(props: &WidgetPanelProps) -> Html {
+    let options = use_state(WidgetOptions::default);
+    let status = use_state(|| None::<String>);
+
+    let on_layer = {
+        let options = options.clone();
+        Callback::from(move |layer: WidgetLayer| {
+            options.set(WidgetOptions { layer, ..*options });
+        })
+    };
+
+    let on_toggle_click_through = {
+        let options = options.clone();
+        Callback::from(move |_| {
+            options.set(WidgetOptions {
+                click_through: !options.click_through,
+                ..*options
+            });
+        })
+    };
+
+    let on_start = {
+        let status = status.clone();
+        let options = *options;
+        let aspect_ratio = props.aspect_ratio;
+        let launch = props.launch.clone();
+        let streaming = props.streaming;
+        let on_started = props.on_started.clone();
+        let on_stream_paused = props.on_stream_paused.clone();
+        Callback::from(move |_: MouseEvent| {
+            let status = status.clone();
+            let launch = launch.clone();
+            let on_started = on_started.clone();
+            let on_stream_paused = on_stream_paused.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                // The widget listens on the same source, so this window lets go of it first
+                if streaming {
+                    on_stream_paused.emit(true);
+                    let args = serde_wasm_bindgen::to_value(&json!({})).unwrap();
+                    let _ = tauri_try_invoke("stop_stream", args).await;
+                }
+                let args = serde_wasm_bindgen::to_value(&json!({
+                    "options": options,
+                    "aspectRatio": aspect_ratio,
+                    "launch": launch,
+                }))
+                .unwrap();
+                match tauri_try_invoke("enter_widget_mode", args).await {
+                    Ok(_) => {
+                        status.set(None);
+                        on_started.emit(());
+                    }
+                    Err(e) => {
+                        on_stream_paused.emit(false);
+                        status.set(Some(invoke_error(e)));
+                    }
+                }
+            });
+        })
+    };
+
+    html! {
+        <div class="control-row widget-panel">
+            <label>{"Widget:"}</label>
+            { for WidgetLayer::ALL.iter().map(|&layer| {
+                let on_layer = on_layer.clone();
+                html! {
+                    <button class={if options.layer == layer { "ctrl-btn copy-format-btn active" } else { "ctrl-btn copy-format-btn" }} type="button" onclick={Callback::from(move |_| on_layer.emit(layer))} title={format!("Keep the widget {} other windows", if layer == WidgetLayer::Top { "above" } else { "below" })}>{layer.label()}</button>
+                }
+            }) }
+            <button class={if options.click_through { "ctrl-btn copy-format-btn active" } else { "ctrl-btn copy-format-btn" }} type="button" onclick={on_toggle_click_through} title="Let clicks pass through the widget">{"Click-through"}</button>
+            <button id="widget-start-btn" class="ctrl-btn copy-format-btn" type="button" onclick={on_start} disabled={props.disabled} title="Turn this window into a desktop widget">{"Start"}</button>
+            <span class="info-text">{format!("{} (or Esc) returns to the window; drag to move, scroll to resize", exit_shortcut_label())}</span>
+            if let Some(status) = status.as_ref().or(props.exit_error.as_ref()) {
+                <span class="info-text">{status.clone()}</span>
+            }
+        </div>
+    }
+}